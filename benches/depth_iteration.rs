@@ -0,0 +1,122 @@
+//! Benchmarks for the different ways of reading every pixel out of a depth frame.
+//!
+//! These require a connected D400 / L500 device, and are therefore gated behind the
+//! `test-single-device` feature:
+//!
+//! ```sh
+//! cargo bench --features test-single-device
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rayon::prelude::*;
+use realsense_rust::{
+    config::Config,
+    context::Context,
+    frame::{DepthFrame, PixelKind},
+    kind::{Rs2Format, Rs2ProductLine, Rs2StreamKind},
+    pipeline::InactivePipeline,
+};
+use std::{collections::HashSet, convert::TryFrom, os::raw::c_void, slice, time::Duration};
+
+/// Starts a depth-only pipeline and grabs a single depth frame to benchmark against.
+fn acquire_depth_frame() -> DepthFrame {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::Depth);
+    assert!(
+        !context.query_devices(queryable_set).is_empty(),
+        "No depth-capable device connected"
+    );
+
+    let mut config = Config::new();
+    config
+        .disable_all_streams()
+        .unwrap()
+        .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+        .unwrap();
+
+    let pipeline = InactivePipeline::try_from(&context).unwrap();
+    let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+    // Skip the first few frames while auto-exposure settles.
+    for _ in 0..10 {
+        let _ = pipeline.wait(Some(Duration::from_millis(5000)));
+    }
+
+    let frames = pipeline.wait(Some(Duration::from_millis(5000))).unwrap();
+    frames.frames_of_type::<DepthFrame>().pop().unwrap()
+}
+
+/// Views the Z16 data of a depth frame as a `u16` slice, row by row.
+fn depth_rows(frame: &DepthFrame) -> impl Iterator<Item = &[u16]> {
+    let stride = frame.stride() / std::mem::size_of::<u16>();
+    let width = frame.width();
+    let data = unsafe {
+        slice::from_raw_parts(
+            (frame.get_data() as *const c_void).cast::<u16>(),
+            frame.get_data_size() / std::mem::size_of::<u16>(),
+        )
+    };
+    data.chunks_exact(stride).map(move |row| &row[..width])
+}
+
+/// Compares per-pixel `PixelKind` dispatch, raw slice access (serial and parallel), and per-pixel
+/// calls to `rs2_depth_frame_get_distance`.
+fn depth_iteration(c: &mut Criterion) {
+    let frame = acquire_depth_frame();
+    let depth_units = frame.depth_units().unwrap();
+
+    let mut group = c.benchmark_group("depth_iteration");
+
+    group.bench_function("pixel_kind_iter", |b| {
+        b.iter(|| {
+            let sum: f32 = frame
+                .iter()
+                .map(|px| match px {
+                    PixelKind::Z16 { depth } => *depth as f32 * depth_units,
+                    _ => 0.0,
+                })
+                .sum();
+            black_box(sum)
+        })
+    });
+
+    group.bench_function("raw_slice", |b| {
+        b.iter(|| {
+            let sum: f32 = depth_rows(&frame)
+                .flatten()
+                .map(|&d| d as f32 * depth_units)
+                .sum();
+            black_box(sum)
+        })
+    });
+
+    group.bench_function("raw_slice_rayon", |b| {
+        b.iter(|| {
+            let rows: Vec<&[u16]> = depth_rows(&frame).collect();
+            let sum: f32 = rows
+                .par_iter()
+                .map(|row| row.iter().map(|&d| d as f32 * depth_units).sum::<f32>())
+                .sum();
+            black_box(sum)
+        })
+    });
+
+    group.bench_function("distance_per_pixel", |b| {
+        b.iter(|| {
+            let mut sum = 0.0f32;
+            for row in 0..frame.height() {
+                for col in 0..frame.width() {
+                    sum += frame.distance(col, row).unwrap();
+                }
+            }
+            black_box(sum)
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, depth_iteration);
+criterion_main!(benches);
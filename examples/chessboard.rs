@@ -0,0 +1,510 @@
+//! Detect a chessboard calibration target in the color stream of a D400 device.
+//!
+//! Calibrating a camera requires observing a target of known geometry. This example finds the
+//! inner corners of a chessboard in each color frame with a simple saddle point detector, and
+//! prints them alongside their position on the board, which is what calibration tools such as
+//! OpenCV's `calibrateCamera` take as input.
+//!
+//! The detector is a heuristic without accuracy guarantees, meant to show how to work with the
+//! pixels of a frame. Use a dedicated computer vision library for actual calibration.
+
+use anyhow::{ensure, Result};
+use realsense_rust::{
+    config::Config,
+    context::Context,
+    frame::{ColorFrame, FrameEx, ImageFrame, PixelKind},
+    kind::{Rs2Format, Rs2ProductLine, Rs2StreamKind},
+    pipeline::InactivePipeline,
+};
+use std::{collections::HashSet, convert::TryFrom, f32::consts::FRAC_PI_2, time::Duration};
+
+/// A chessboard calibration target detected in an image.
+///
+/// Holds the inner corners of the chessboard in image coordinates, in row-major order: the first
+/// `cols` corners are the top row from left to right, followed by the next row, and so on.
+#[derive(Debug, Clone, PartialEq)]
+struct CalibrationTarget {
+    /// Number of inner corners along the vertical axis of the board.
+    rows: u32,
+    /// Number of inner corners along the horizontal axis of the board.
+    cols: u32,
+    /// Length of the side of a single square, in millimeters.
+    square_size_mm: f32,
+    /// Subpixel `[x, y]` image coordinates of each inner corner, in row-major order.
+    corners: Vec<[f32; 2]>,
+}
+
+impl CalibrationTarget {
+    /// Get the `[x, y]` image coordinates of the inner corners, in pixels.
+    ///
+    /// Corners are in row-major order, and correspond one-to-one with
+    /// [`object_points`](CalibrationTarget::object_points).
+    fn corners(&self) -> &[[f32; 2]] {
+        &self.corners
+    }
+
+    /// Get the position of each inner corner on the board itself, in millimeters.
+    ///
+    /// The board lies in the `z = 0` plane, with the top-left inner corner at the origin, `x`
+    /// increasing along a row and `y` increasing down the rows.
+    fn object_points(&self) -> Vec<[f32; 3]> {
+        (0..self.rows)
+            .flat_map(|row| {
+                (0..self.cols).map(move |col| {
+                    [
+                        col as f32 * self.square_size_mm,
+                        row as f32 * self.square_size_mm,
+                        0.0,
+                    ]
+                })
+            })
+            .collect()
+    }
+}
+
+/// Detects chessboard calibration targets in image frames.
+///
+/// The board is described by its number of inner corners, i.e. the points where four squares
+/// meet. A board of 9x7 squares has 8x6 inner corners.
+///
+/// Corners are found as saddle points of the image intensity, and then arranged into a grid. The
+/// detector works best when the board fills a good part of the frame, is roughly fronto-parallel,
+/// and is the most prominent chessboard-like structure in view. Boards rotated by up to 45
+/// degrees in the image plane are supported, but rows and columns must not be swapped: the
+/// `cols` corners of a row should run roughly along the horizontal axis of the image.
+#[derive(Debug, Clone)]
+struct ChessboardDetector {
+    /// Number of inner corners along the vertical axis of the board.
+    rows: u32,
+    /// Number of inner corners along the horizontal axis of the board.
+    cols: u32,
+    /// Length of the side of a single square, in millimeters.
+    square_size_mm: f32,
+}
+
+impl ChessboardDetector {
+    /// Construct a detector for a board with `rows` x `cols` inner corners, whose squares have
+    /// sides of `square_size_mm` millimeters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rows` or `cols` is less than 2.
+    fn new(rows: u32, cols: u32, square_size_mm: f32) -> Self {
+        assert!(
+            rows >= 2 && cols >= 2,
+            "A chessboard needs at least 2x2 inner corners"
+        );
+
+        Self {
+            rows,
+            cols,
+            square_size_mm,
+        }
+    }
+
+    /// Detect the chessboard in an image frame.
+    ///
+    /// This is primarily intended for [`ColorFrame`]s, but works for any frame holding color or
+    /// grayscale pixels, e.g. an
+    /// [`InfraredFrame`](realsense_rust::frame::InfraredFrame).
+    ///
+    /// Returns `None` if the full board could not be found, or if the frame is not in an RGB,
+    /// BGR, YUV or 8-bit grayscale format.
+    fn detect<K>(&self, frame: &ImageFrame<K>) -> Option<CalibrationTarget> {
+        let (width, height) = (frame.width(), frame.height());
+
+        let mut gray = Vec::with_capacity(width * height);
+        for row in 0..height {
+            for col in 0..width {
+                gray.push(luma(frame.get(col, row)?)?);
+            }
+        }
+
+        self.detect_gray(&gray, width, height)
+    }
+
+    /// Detect the chessboard in a row-major grayscale image.
+    fn detect_gray(&self, gray: &[f32], width: usize, height: usize) -> Option<CalibrationTarget> {
+        let count = (self.rows * self.cols) as usize;
+
+        let mut candidates = saddle_points(gray, width, height);
+        if candidates.len() < count {
+            return None;
+        }
+
+        // X-junctions where four squares meet respond roughly four times more strongly than the
+        // L- and T-junctions along the border of the board, so the strongest responses are the
+        // inner corners.
+        candidates.truncate(count);
+        if candidates[count - 1].1 < MIN_RELATIVE_RESPONSE * candidates[0].1 {
+            return None;
+        }
+
+        let points = candidates.into_iter().map(|(p, _)| p).collect::<Vec<_>>();
+        let corners = arrange_grid(&points, self.rows as usize, self.cols as usize)?;
+
+        Some(CalibrationTarget {
+            rows: self.rows,
+            cols: self.cols,
+            square_size_mm: self.square_size_mm,
+            corners,
+        })
+    }
+}
+
+/// Responses weaker than this fraction of the strongest corner are not considered corners.
+const MIN_RELATIVE_RESPONSE: f32 = 0.1;
+
+/// Minimum distance between two detected corners, in pixels.
+const MIN_CORNER_DISTANCE: f32 = 3.0;
+
+/// Get the grayscale intensity of a pixel, in the range `[0, 255]`.
+fn luma(pixel: PixelKind<'_>) -> Option<f32> {
+    let rgb = |r: &u8, g: &u8, b: &u8| 0.299 * *r as f32 + 0.587 * *g as f32 + 0.114 * *b as f32;
+
+    match pixel {
+        PixelKind::Rgb8 { r, g, b }
+        | PixelKind::Bgr8 { r, g, b }
+        | PixelKind::Rgba8 { r, g, b, .. }
+        | PixelKind::Bgra8 { r, g, b, .. } => Some(rgb(r, g, b)),
+        PixelKind::Yuyv { y, .. } | PixelKind::Uyvy { y, .. } | PixelKind::Y8 { y } => {
+            Some(*y as f32)
+        }
+        _ => None,
+    }
+}
+
+/// Smooth an image with a separable 5-tap binomial kernel, clamping at the borders.
+fn blur(image: &[f32], width: usize, height: usize) -> Vec<f32> {
+    const KERNEL: [f32; 5] = [1.0 / 16.0, 4.0 / 16.0, 6.0 / 16.0, 4.0 / 16.0, 1.0 / 16.0];
+
+    // Convolve along a line of `len` pixels that are `step` apart and start at `start`.
+    let convolve = |input: &[f32], start: usize, step: usize, len: usize, pos: usize| -> f32 {
+        KERNEL
+            .iter()
+            .enumerate()
+            .map(|(k, w)| {
+                let p = (pos + k).saturating_sub(2).min(len - 1);
+                w * input[start + p * step]
+            })
+            .sum()
+    };
+
+    let mut horizontal = vec![0.0; image.len()];
+    for y in 0..height {
+        for x in 0..width {
+            horizontal[y * width + x] = convolve(image, y * width, 1, width, x);
+        }
+    }
+
+    let mut out = vec![0.0; image.len()];
+    for y in 0..height {
+        for x in 0..width {
+            out[y * width + x] = convolve(&horizontal, x, width, height, y);
+        }
+    }
+    out
+}
+
+/// Find saddle points in a grayscale image.
+///
+/// Returns the subpixel position of each saddle point alongside its response, strongest first.
+fn saddle_points(gray: &[f32], width: usize, height: usize) -> Vec<([f32; 2], f32)> {
+    if width < 5 || height < 5 {
+        return Vec::new();
+    }
+
+    let smoothed = blur(&blur(gray, width, height), width, height);
+    let at = |x: usize, y: usize| smoothed[y * width + x];
+
+    // The negated determinant of the Hessian is positive at saddle points, zero along straight
+    // edges and negative at blobs.
+    let mut response = vec![0.0f32; gray.len()];
+    for y in 1..height - 1 {
+        for x in 1..width - 1 {
+            let dxx = at(x + 1, y) - 2.0 * at(x, y) + at(x - 1, y);
+            let dyy = at(x, y + 1) - 2.0 * at(x, y) + at(x, y - 1);
+            let dxy =
+                (at(x + 1, y + 1) - at(x + 1, y - 1) - at(x - 1, y + 1) + at(x - 1, y - 1)) / 4.0;
+            response[y * width + x] = dxy * dxy - dxx * dyy;
+        }
+    }
+    let r = |x: usize, y: usize| response[y * width + x];
+
+    let mut peaks = Vec::new();
+    for y in 2..height - 2 {
+        for x in 2..width - 2 {
+            let value = r(x, y);
+            if value <= 0.0 {
+                continue;
+            }
+
+            let is_local_max = (y - 1..=y + 1)
+                .flat_map(|ny| (x - 1..=x + 1).map(move |nx| (nx, ny)))
+                .all(|(nx, ny)| r(nx, ny) <= value);
+            if !is_local_max {
+                continue;
+            }
+
+            let dx = parabola_peak(r(x - 1, y), value, r(x + 1, y));
+            let dy = parabola_peak(r(x, y - 1), value, r(x, y + 1));
+            peaks.push(([x as f32 + dx, y as f32 + dy], value));
+        }
+    }
+
+    peaks.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    // Plateaus produce several adjacent local maxima for the same corner; keep the strongest.
+    let mut corners: Vec<([f32; 2], f32)> = Vec::new();
+    for (point, value) in peaks {
+        if corners
+            .iter()
+            .all(|(other, _)| distance(point, *other) >= MIN_CORNER_DISTANCE)
+        {
+            corners.push((point, value));
+        }
+    }
+    corners
+}
+
+/// Offset of the peak of the parabola through `(-1, left)`, `(0, center)` and `(1, right)`.
+fn parabola_peak(left: f32, center: f32, right: f32) -> f32 {
+    let curvature = left - 2.0 * center + right;
+    if curvature >= 0.0 {
+        return 0.0;
+    }
+    (0.5 * (left - right) / curvature).clamp(-0.5, 0.5)
+}
+
+/// Euclidean distance between two points.
+fn distance(a: [f32; 2], b: [f32; 2]) -> f32 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)).sqrt()
+}
+
+/// Arrange exactly `rows * cols` points into a row-major grid.
+///
+/// Returns `None` if the points do not form a grid of the requested shape.
+fn arrange_grid(points: &[[f32; 2]], rows: usize, cols: usize) -> Option<Vec<[f32; 2]>> {
+    // Neighbouring corners are separated along one of the two axes of the grid. Averaging the
+    // direction to the nearest neighbour with angles multiplied by four (the grid axes repeat
+    // every 90 degrees) gives the orientation of the grid.
+    let (mut sin, mut cos) = (0.0f32, 0.0f32);
+    for (i, p) in points.iter().enumerate() {
+        let nearest = points
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(_, q)| q)
+            .min_by(|a, b| distance(*p, **a).total_cmp(&distance(*p, **b)))?;
+        let angle = (nearest[1] - p[1]).atan2(nearest[0] - p[0]);
+        sin += (4.0 * angle).sin();
+        cos += (4.0 * angle).cos();
+    }
+
+    // Pick the grid axis closest to the horizontal axis of the image as the row direction.
+    let angle = sin.atan2(cos) / 4.0;
+    let angle = angle - (angle / FRAC_PI_2).round() * FRAC_PI_2;
+    let (sin, cos) = angle.sin_cos();
+    let project = |p: &[f32; 2]| (p[0] * cos + p[1] * sin, -p[0] * sin + p[1] * cos);
+
+    let mut projected = points.iter().map(|p| (project(p), *p)).collect::<Vec<_>>();
+    projected.sort_by(|a, b| (a.0).1.total_cmp(&(b.0).1));
+
+    let mut grid = Vec::with_capacity(rows * cols);
+    let mut previous_row_end: Option<f32> = None;
+    for row in projected.chunks_exact_mut(cols) {
+        let row_start = (row[0].0).1;
+        let row_end = (row[cols - 1].0).1;
+
+        // Rows must be clearly separated from each other.
+        if let Some(previous_end) = previous_row_end {
+            if row_start - previous_end <= row_end - row_start {
+                return None;
+            }
+        }
+        previous_row_end = Some(row_end);
+
+        row.sort_by(|a, b| (a.0).0.total_cmp(&(b.0).0));
+
+        // Corners within a row must be roughly evenly spaced.
+        let mut steps = row
+            .windows(2)
+            .map(|pair| (pair[1].0).0 - (pair[0].0).0)
+            .collect::<Vec<_>>();
+        steps.sort_by(f32::total_cmp);
+        let median = steps[steps.len() / 2];
+        if steps[0] < 0.5 * median || steps[steps.len() - 1] > 2.0 * median {
+            return None;
+        }
+
+        grid.extend(row.iter().map(|(_, p)| *p));
+    }
+
+    Some(grid)
+}
+
+fn main() -> Result<()> {
+    // A board of 9x7 squares with 25 mm sides, which has 8x6 inner corners.
+    let detector = ChessboardDetector::new(6, 8, 25.0);
+
+    let mut queried_devices = HashSet::new();
+    queried_devices.insert(Rs2ProductLine::D400);
+    let context = Context::new()?;
+    ensure!(
+        !context.query_devices(queried_devices).is_empty(),
+        "No devices found"
+    );
+
+    let mut config = Config::new();
+    config.disable_all_streams()?.enable_stream(
+        Rs2StreamKind::Color,
+        None,
+        640,
+        0,
+        Rs2Format::Rgb8,
+        30,
+    )?;
+
+    let pipeline = InactivePipeline::try_from(&context)?;
+    let mut pipeline = pipeline.start(Some(config))?;
+
+    for _ in 0..100 {
+        let frames = pipeline.wait(Some(Duration::from_millis(5000)))?;
+        let color_frame = match frames.frames_of_type::<ColorFrame>().into_iter().next() {
+            Some(frame) => frame,
+            None => continue,
+        };
+
+        match detector.detect(&color_frame) {
+            Some(target) => {
+                println!("Found chessboard in frame {}:", color_frame.frame_number());
+                for (corner, point) in target.corners().iter().zip(target.object_points()) {
+                    println!("  image {:?} <- board {:?} mm", corner, point);
+                }
+                return Ok(());
+            }
+            None => println!("No chessboard in frame {}", color_frame.frame_number()),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Render a chessboard with `squares` squares along each axis, each `size` pixels wide, whose
+    /// top-left corner is at `origin` and which is rotated by `angle` radians about that corner.
+    ///
+    /// Pixels are supersampled so that edges are anti-aliased as they would be by a camera.
+    fn render(
+        width: usize,
+        height: usize,
+        squares: (usize, usize),
+        size: f32,
+        origin: [f32; 2],
+        angle: f32,
+    ) -> Vec<f32> {
+        const SAMPLES: usize = 4;
+        let (sin, cos) = angle.sin_cos();
+
+        let mut image = vec![0.0; width * height];
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = 0.0;
+                for sy in 0..SAMPLES {
+                    for sx in 0..SAMPLES {
+                        let px = x as f32 + (sx as f32 + 0.5) / SAMPLES as f32 - 0.5 - origin[0];
+                        let py = y as f32 + (sy as f32 + 0.5) / SAMPLES as f32 - 0.5 - origin[1];
+                        let u = (px * cos + py * sin) / size;
+                        let v = (-px * sin + py * cos) / size;
+
+                        let on_board =
+                            u >= 0.0 && v >= 0.0 && u < squares.0 as f32 && v < squares.1 as f32;
+                        let is_black = on_board && (u as usize + v as usize) % 2 == 0;
+                        sum += if is_black { 0.0 } else { 255.0 };
+                    }
+                }
+                image[y * width + x] = sum / (SAMPLES * SAMPLES) as f32;
+            }
+        }
+        image
+    }
+
+    /// Expected position of the inner corner at `row` and `col` of a board rendered by `render`.
+    fn expected_corner(
+        row: usize,
+        col: usize,
+        size: f32,
+        origin: [f32; 2],
+        angle: f32,
+    ) -> [f32; 2] {
+        let (sin, cos) = angle.sin_cos();
+        let u = (col + 1) as f32 * size;
+        let v = (row + 1) as f32 * size;
+        [origin[0] + u * cos - v * sin, origin[1] + u * sin + v * cos]
+    }
+
+    fn assert_detects_board(angle: f32) {
+        let (rows, cols, size, origin) = (4, 5, 16.0, [40.3, 30.7]);
+        let image = render(200, 160, (cols + 1, rows + 1), size, origin, angle);
+
+        let target = ChessboardDetector::new(rows as u32, cols as u32, 25.0)
+            .detect_gray(&image, 200, 160)
+            .expect("Board should be detected");
+
+        for row in 0..rows {
+            for col in 0..cols {
+                let corner = target.corners()[row * cols + col];
+                let expected = expected_corner(row, col, size, origin, angle);
+                assert!(
+                    distance(corner, expected) < 0.5,
+                    "Corner ({}, {}) at {:?}, expected {:?}",
+                    row,
+                    col,
+                    corner,
+                    expected,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn detects_axis_aligned_board() {
+        assert_detects_board(0.0);
+    }
+
+    #[test]
+    fn detects_rotated_board() {
+        assert_detects_board(0.3);
+    }
+
+    #[test]
+    fn rejects_image_without_board() {
+        let image = vec![128.0; 200 * 160];
+        let detector = ChessboardDetector::new(4, 5, 25.0);
+        assert_eq!(detector.detect_gray(&image, 200, 160), None);
+    }
+
+    #[test]
+    fn object_points_follow_square_size() {
+        let target = CalibrationTarget {
+            rows: 2,
+            cols: 3,
+            square_size_mm: 10.0,
+            corners: vec![[0.0; 2]; 6],
+        };
+
+        assert_eq!(
+            target.object_points(),
+            vec![
+                [0.0, 0.0, 0.0],
+                [10.0, 0.0, 0.0],
+                [20.0, 0.0, 0.0],
+                [0.0, 10.0, 0.0],
+                [10.0, 10.0, 0.0],
+                [20.0, 10.0, 0.0],
+            ]
+        );
+    }
+}
@@ -108,7 +108,7 @@ fn main() -> Result<()> {
         }
     }
 
-    pipeline.stop();
+    pipeline.stop().map_err(|(_, e)| e)?;
 
     Ok(())
 }
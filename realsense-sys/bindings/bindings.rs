@@ -3845,3 +3845,180 @@ extern "C" {
     #[doc = " Deletes an instance of a pipeline profile\n\n \\param[in] profile    A pointer to an instance of a pipeline profile"]
     pub fn rs2_delete_pipeline_profile(profile: *mut rs2_pipeline_profile);
 }
+#[doc = " All the parameters required to define a video stream."]
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct rs2_video_stream {
+    pub type_: rs2_stream,
+    pub index: ::std::os::raw::c_int,
+    pub uid: ::std::os::raw::c_int,
+    pub width: ::std::os::raw::c_int,
+    pub height: ::std::os::raw::c_int,
+    pub fps: ::std::os::raw::c_int,
+    pub bpp: ::std::os::raw::c_int,
+    pub fmt: rs2_format,
+    pub intrinsics: rs2_intrinsics,
+}
+#[doc = " All the parameters required to define a video frame."]
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct rs2_software_video_frame {
+    pub pixels: *mut ::std::os::raw::c_void,
+    pub deleter: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
+    pub stride: ::std::os::raw::c_int,
+    pub bpp: ::std::os::raw::c_int,
+    pub timestamp: rs2_time_t,
+    pub domain: rs2_timestamp_domain,
+    pub frame_number: ::std::os::raw::c_int,
+    pub profile: *const rs2_stream_profile,
+    pub depth_units: f32,
+}
+extern "C" {
+    #[doc = " Create software device to enable use librealsense logic without getting data from backend\n but inject the data from outside\n \\param[out] error  If non-null, receives any error that occurs during this call, otherwise, errors are ignored\n \\return            software device object, should be released by rs2_delete_device"]
+    pub fn rs2_create_software_device(error: *mut *mut rs2_error) -> *mut rs2_device;
+}
+extern "C" {
+    #[doc = " Add sensor to the software device\n \\param[in] dev the software device\n \\param[in] sensor_name the name of the sensor\n \\param[out] error  If non-null, receives any error that occurs during this call, otherwise, errors are ignored\n \\return            software sensor object, should be released by rs2_delete_sensor"]
+    pub fn rs2_software_device_add_sensor(
+        dev: *mut rs2_device,
+        sensor_name: *const ::std::os::raw::c_char,
+        error: *mut *mut rs2_error,
+    ) -> *mut rs2_sensor;
+}
+extern "C" {
+    #[doc = " Inject video frame to software sonsor\n \\param[in] sensor the software sensor\n \\param[in] frame all the frame components\n \\param[out] error  If non-null, receives any error that occurs during this call, otherwise, errors are ignored"]
+    pub fn rs2_software_sensor_on_video_frame(
+        sensor: *mut rs2_sensor,
+        frame: rs2_software_video_frame,
+        error: *mut *mut rs2_error,
+    );
+}
+extern "C" {
+    #[doc = " Add video stream to sensor\n \\param[in] sensor the software sensor\n \\param[in] video_stream all the stream components\n \\param[out] error  If non-null, receives any error that occurs during this call, otherwise, errors are ignored"]
+    pub fn rs2_software_sensor_add_video_stream(
+        sensor: *mut rs2_sensor,
+        video_stream: rs2_video_stream,
+        error: *mut *mut rs2_error,
+    ) -> *mut rs2_stream_profile;
+}
+extern "C" {
+    #[doc = " Enable/Disable Advanced-Mode"]
+    pub fn rs2_toggle_advanced_mode(
+        dev: *mut rs2_device,
+        enable: ::std::os::raw::c_int,
+        error: *mut *mut rs2_error,
+    );
+}
+extern "C" {
+    #[doc = " Check if Advanced-Mode is enabled"]
+    pub fn rs2_is_enabled(
+        dev: *mut rs2_device,
+        enabled: *mut ::std::os::raw::c_int,
+        error: *mut *mut rs2_error,
+    );
+}
+extern "C" {
+    #[doc = " \\brief Creates RealSense firmware log message."]
+    pub fn rs2_create_fw_log_message(
+        dev: *mut rs2_device,
+        error: *mut *mut rs2_error,
+    ) -> *mut rs2_firmware_log_message;
+}
+extern "C" {
+    #[doc = " \\brief Gets RealSense firmware log."]
+    pub fn rs2_get_fw_log(
+        dev: *mut rs2_device,
+        fw_log_msg: *mut rs2_firmware_log_message,
+        error: *mut *mut rs2_error,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    #[doc = " Delete RealSense firmware log message"]
+    pub fn rs2_delete_fw_log_message(msg: *mut rs2_firmware_log_message);
+}
+extern "C" {
+    #[doc = " \\brief Gets RealSense firmware log message data."]
+    pub fn rs2_fw_log_message_data(
+        msg: *mut rs2_firmware_log_message,
+        error: *mut *mut rs2_error,
+    ) -> *const ::std::os::raw::c_uchar;
+}
+extern "C" {
+    #[doc = " \\brief Gets RealSense firmware log message size."]
+    pub fn rs2_fw_log_message_size(
+        msg: *mut rs2_firmware_log_message,
+        error: *mut *mut rs2_error,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    #[doc = " \\brief Gets RealSense firmware log message timestamp."]
+    pub fn rs2_fw_log_message_timestamp(
+        msg: *mut rs2_firmware_log_message,
+        error: *mut *mut rs2_error,
+    ) -> ::std::os::raw::c_uint;
+}
+extern "C" {
+    #[doc = " \\brief Gets RealSense firmware log message severity."]
+    pub fn rs2_fw_log_message_severity(
+        msg: *const rs2_firmware_log_message,
+        error: *mut *mut rs2_error,
+    ) -> rs2_log_severity;
+}
+extern "C" {
+    #[doc = " \\brief Initializes RealSense firmware logs parser in device."]
+    pub fn rs2_init_fw_log_parser(
+        dev: *mut rs2_device,
+        xml_content: *const ::std::os::raw::c_char,
+        error: *mut *mut rs2_error,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    #[doc = " \\brief Creates RealSense firmware log parsed message."]
+    pub fn rs2_create_fw_log_parsed_message(
+        dev: *mut rs2_device,
+        error: *mut *mut rs2_error,
+    ) -> *mut rs2_firmware_log_parsed_message;
+}
+extern "C" {
+    #[doc = " \\brief Deletes RealSense firmware log parsed message."]
+    pub fn rs2_delete_fw_log_parsed_message(
+        fw_log_parsed_msg: *mut rs2_firmware_log_parsed_message,
+    );
+}
+extern "C" {
+    #[doc = " \\brief Parses firmware log message."]
+    pub fn rs2_parse_firmware_log(
+        dev: *mut rs2_device,
+        fw_log_msg: *mut rs2_firmware_log_message,
+        parsed_msg: *mut rs2_firmware_log_parsed_message,
+        error: *mut *mut rs2_error,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    #[doc = " \\brief Gets RealSense firmware log parsed message."]
+    pub fn rs2_get_fw_log_parsed_message(
+        fw_log_parsed_msg: *mut rs2_firmware_log_parsed_message,
+        error: *mut *mut rs2_error,
+    ) -> *const ::std::os::raw::c_char;
+}
+extern "C" {
+    #[doc = " \\brief Gets RealSense firmware log parsed message file name."]
+    pub fn rs2_get_fw_log_parsed_file_name(
+        fw_log_parsed_msg: *mut rs2_firmware_log_parsed_message,
+        error: *mut *mut rs2_error,
+    ) -> *const ::std::os::raw::c_char;
+}
+extern "C" {
+    #[doc = " \\brief Gets RealSense firmware log parsed message thread name."]
+    pub fn rs2_get_fw_log_parsed_thread_name(
+        fw_log_parsed_msg: *mut rs2_firmware_log_parsed_message,
+        error: *mut *mut rs2_error,
+    ) -> *const ::std::os::raw::c_char;
+}
+extern "C" {
+    #[doc = " \\brief Gets RealSense firmware log parsed message line."]
+    pub fn rs2_get_fw_log_parsed_line(
+        fw_log_parsed_msg: *mut rs2_firmware_log_parsed_message,
+        error: *mut *mut rs2_error,
+    ) -> ::std::os::raw::c_uint;
+}
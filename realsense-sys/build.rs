@@ -75,6 +75,15 @@ fn main() {
                     .unwrap(),
             )
             .header(include_dir.join("h").join("rs_config.h").to_str().unwrap())
+            .header(include_dir.join("h").join("rs_device.h").to_str().unwrap())
+            .header(include_dir.join("rs_advanced_mode.h").to_str().unwrap())
+            .header(
+                include_dir
+                    .join("h")
+                    .join("rs_internal.h")
+                    .to_str()
+                    .unwrap(),
+            )
             .allowlist_var("RS2_.*")
             .allowlist_type("rs2_.*")
             .allowlist_function("rs2_.*")
@@ -0,0 +1,183 @@
+//! Type for configuring devices through the advanced mode API.
+//!
+//! D400 series devices support an "advanced mode", which exposes the parameters of the depth
+//! algorithm beyond the regular [options](crate::kind::Rs2Option). These parameters are usually
+//! exchanged as JSON presets, such as those saved by the RealSense Viewer:
+//!
+//! ```no_run
+//! use realsense_rust::{advanced_mode::AdvancedModeDevice, context::Context};
+//! use std::collections::HashSet;
+//!
+//! let context = Context::new()?;
+//! let device = context.query_devices(HashSet::new()).remove(0);
+//!
+//! let mut advanced = AdvancedModeDevice::from_device(device)?;
+//! if advanced.is_enabled()? {
+//!     advanced.load_json(&std::fs::read_to_string("high_accuracy.json")?)?;
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::{
+    check_rs2_error,
+    device::Device,
+    kind::{Rs2Exception, Rs2Extension},
+};
+use realsense_sys as sys;
+use std::os::raw::{c_int, c_uint, c_void};
+use thiserror::Error;
+
+/// Type describing errors that can occur when using the advanced mode of a device.
+///
+/// Follows the standard pattern of errors where the enum variant describes what the low-level code
+/// was attempting to do while the string carried alongside describes the underlying error message
+/// from any C++ exceptions that occur.
+#[derive(Error, Debug)]
+pub enum AdvancedModeError {
+    /// The device does not support the [`Rs2Extension::AdvancedMode`] extension.
+    #[error("Device does not support advanced mode.")]
+    AdvancedModeNotSupported,
+    /// Could not enable or disable advanced mode.
+    #[error("Could not toggle advanced mode. Type: {0}; Reason: {1}")]
+    CouldNotToggleAdvancedMode(Rs2Exception, String),
+    /// Could not check whether advanced mode is enabled.
+    #[error("Could not check whether advanced mode is enabled. Type: {0}; Reason: {1}")]
+    CouldNotCheckIfEnabled(Rs2Exception, String),
+    /// Could not load the JSON configuration onto the device.
+    #[error("Could not load JSON configuration. Type: {0}; Reason: {1}")]
+    CouldNotLoadJson(Rs2Exception, String),
+    /// Could not serialize the configuration of the device to JSON.
+    #[error("Could not serialize configuration to JSON. Type: {0}; Reason: {1}")]
+    CouldNotSerializeJson(Rs2Exception, String),
+}
+
+/// A device that supports the advanced mode API.
+///
+/// Most methods require advanced mode to be [enabled](AdvancedModeDevice::is_enabled) on the
+/// device, and fail otherwise.
+#[derive(Debug)]
+pub struct AdvancedModeDevice {
+    /// The underlying device.
+    device: Device,
+}
+
+impl AdvancedModeDevice {
+    /// Wrap `device` for use with the advanced mode API.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AdvancedModeError::AdvancedModeNotSupported`] if the device does not support the
+    /// [`Rs2Extension::AdvancedMode`] extension.
+    pub fn from_device(device: Device) -> Result<Self, AdvancedModeError> {
+        if device.is_extendable_to(Rs2Extension::AdvancedMode) {
+            Ok(Self { device })
+        } else {
+            Err(AdvancedModeError::AdvancedModeNotSupported)
+        }
+    }
+
+    /// Get the underlying device.
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+
+    /// Consume the advanced mode device and return the underlying device.
+    pub fn into_device(self) -> Device {
+        self.device
+    }
+
+    /// Enable or disable advanced mode on the device.
+    ///
+    /// The setting is persisted on the device, which resets itself to apply it. The device
+    /// disconnects while doing so, after which it has to be queried again from a
+    /// [`Context`](crate::context::Context); this handle no longer refers to a connected device.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AdvancedModeError::CouldNotToggleAdvancedMode`] if the mode could not be changed.
+    pub fn toggle_advanced_mode(&mut self, enable: bool) -> Result<(), AdvancedModeError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_toggle_advanced_mode(
+                self.device.get_raw().as_ptr(),
+                enable as c_int,
+                &mut err,
+            );
+            check_rs2_error!(err, AdvancedModeError::CouldNotToggleAdvancedMode)?;
+        }
+        Ok(())
+    }
+
+    /// Check whether advanced mode is enabled on the device.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AdvancedModeError::CouldNotCheckIfEnabled`] if the device could not be queried.
+    pub fn is_enabled(&self) -> Result<bool, AdvancedModeError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let mut enabled: c_int = 0;
+            sys::rs2_is_enabled(self.device.get_raw().as_ptr(), &mut enabled, &mut err);
+            check_rs2_error!(err, AdvancedModeError::CouldNotCheckIfEnabled)?;
+            Ok(enabled != 0)
+        }
+    }
+
+    /// Load a JSON configuration preset onto the device.
+    ///
+    /// `json` is in the format produced by [`AdvancedModeDevice::serialize_json`] or saved by the
+    /// RealSense Viewer. Parameters not present in `json` keep their current values.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AdvancedModeError::CouldNotLoadJson`] if advanced mode is not enabled or the
+    /// JSON is invalid.
+    pub fn load_json(&mut self, json: &str) -> Result<(), AdvancedModeError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_load_json(
+                self.device.get_raw().as_ptr(),
+                json.as_ptr().cast::<c_void>(),
+                json.len() as c_uint,
+                &mut err,
+            );
+            check_rs2_error!(err, AdvancedModeError::CouldNotLoadJson)?;
+        }
+        Ok(())
+    }
+
+    /// Serialize the current configuration of the device to a JSON preset.
+    ///
+    /// The result can be loaded back onto this or another device with
+    /// [`AdvancedModeDevice::load_json`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AdvancedModeError::CouldNotSerializeJson`] if advanced mode is not enabled or the
+    /// configuration could not be read.
+    pub fn serialize_json(&self) -> Result<String, AdvancedModeError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let buffer = sys::rs2_serialize_json(self.device.get_raw().as_ptr(), &mut err);
+            check_rs2_error!(err, AdvancedModeError::CouldNotSerializeJson)?;
+
+            let size = sys::rs2_get_raw_data_size(buffer, &mut err);
+            if let Err(e) = check_rs2_error!(err, AdvancedModeError::CouldNotSerializeJson) {
+                sys::rs2_delete_raw_data(buffer);
+                return Err(e);
+            }
+
+            let data = sys::rs2_get_raw_data(buffer, &mut err);
+            if let Err(e) = check_rs2_error!(err, AdvancedModeError::CouldNotSerializeJson) {
+                sys::rs2_delete_raw_data(buffer);
+                return Err(e);
+            }
+
+            // librealsense2 only ever writes ASCII JSON, so a lossy conversion never loses data.
+            let json = String::from_utf8_lossy(std::slice::from_raw_parts(data, size as usize))
+                .into_owned();
+            sys::rs2_delete_raw_data(buffer);
+            Ok(json)
+        }
+    }
+}
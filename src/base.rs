@@ -3,7 +3,10 @@
 use crate::kind::Rs2DistortionModel;
 use num_traits::FromPrimitive;
 use realsense_sys as sys;
-use std::{ffi::CString, time::Duration};
+use std::{ffi::CString, ops::Mul, time::Duration};
+
+#[cfg(feature = "serde")]
+mod serde;
 
 /// The default timeout duration in librealsense2
 pub const DEFAULT_TIMEOUT: Duration = Duration::from_millis(sys::RS2_DEFAULT_TIMEOUT as u64);
@@ -87,6 +90,7 @@ unsafe impl Send for Rs2MotionDeviceIntrinsics {}
 /// The Intel RealSense documentation claims that "Other models are subject to their own interpretations". This is
 /// admittedly not too helpful, but it's worth noting in case your model isn't covered here.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct Rs2Distortion {
     /// Distortion model of the image.
     pub model: Rs2DistortionModel,
@@ -138,10 +142,277 @@ impl Rs2Intrinsics {
             coeffs: self.0.coeffs,
         }
     }
+
+    /// Project a 3D `point` in the camera's coordinate frame onto a pixel of the image.
+    ///
+    /// The point is in the RealSense convention (x right, y down, looking down +z). The distortion
+    /// model of the intrinsics is applied, see [`Rs2Intrinsics::distortion`].
+    pub fn project(&self, point: [f32; 3]) -> [f32; 2] {
+        let mut pixel = [0.0; 2];
+        unsafe {
+            sys::rs2_project_point_to_pixel(pixel.as_mut_ptr(), &self.0, point.as_ptr());
+        }
+        pixel
+    }
+
+    /// Deproject a `pixel` of the image at the given `depth` into a 3D point in the camera's
+    /// coordinate frame.
+    ///
+    /// This is the inverse of [`Rs2Intrinsics::project`]. The returned point is in the same units
+    /// as `depth`, and the distortion model of the intrinsics is taken into account.
+    pub fn deproject(&self, pixel: [f32; 2], depth: f32) -> [f32; 3] {
+        let mut point = [0.0; 3];
+        unsafe {
+            sys::rs2_deproject_pixel_to_point(point.as_mut_ptr(), &self.0, pixel.as_ptr(), depth);
+        }
+        point
+    }
+
+    /// Apply the lens distortion of the intrinsics to an undistorted pixel `point`.
+    ///
+    /// This maps where a point would be imaged by an ideal pinhole camera to where it is imaged by
+    /// the actual lens. Unlike [`Rs2Intrinsics::project`], this is computed in Rust without calling
+    /// into librealsense2. Points are returned unchanged for [`Rs2DistortionModel::None`].
+    pub fn distort(&self, point: [f32; 2]) -> [f32; 2] {
+        let coeffs = &self.0.coeffs;
+        let normalized = self.normalize(point);
+
+        self.denormalize(match self.distortion().model {
+            Rs2DistortionModel::None => normalized,
+            Rs2DistortionModel::BrownConrady => brown_conrady(normalized, coeffs),
+            Rs2DistortionModel::BrownConradyModified => modified_brown_conrady(normalized, coeffs),
+            Rs2DistortionModel::BrownConradyInverse => {
+                invert_iteratively(normalized, |p| brown_conrady(p, coeffs))
+            }
+            Rs2DistortionModel::FThetaFisheye => ftheta(normalized, coeffs[0]),
+            Rs2DistortionModel::KannalaBrandt => kannala_brandt(normalized, coeffs),
+        })
+    }
+
+    /// Remove the lens distortion of the intrinsics from a distorted pixel `point`.
+    ///
+    /// This is the inverse of [`Rs2Intrinsics::distort`]. Models without a closed-form inverse are
+    /// inverted iteratively, which converges to well within a pixel for the amount of distortion
+    /// found in RealSense lenses.
+    pub fn undistort(&self, point: [f32; 2]) -> [f32; 2] {
+        let coeffs = &self.0.coeffs;
+        let normalized = self.normalize(point);
+
+        self.denormalize(match self.distortion().model {
+            Rs2DistortionModel::None => normalized,
+            Rs2DistortionModel::BrownConrady => undistort_brown_conrady(normalized, coeffs),
+            Rs2DistortionModel::BrownConradyModified => {
+                invert_iteratively(normalized, |p| modified_brown_conrady(p, coeffs))
+            }
+            Rs2DistortionModel::BrownConradyInverse => brown_conrady(normalized, coeffs),
+            Rs2DistortionModel::FThetaFisheye => undistort_ftheta(normalized, coeffs[0]),
+            Rs2DistortionModel::KannalaBrandt => undistort_kannala_brandt(normalized, coeffs),
+        })
+    }
+
+    /// Convert a pixel into coordinates on the normalized image plane.
+    fn normalize(&self, [u, v]: [f32; 2]) -> [f32; 2] {
+        [(u - self.0.ppx) / self.0.fx, (v - self.0.ppy) / self.0.fy]
+    }
+
+    /// Convert coordinates on the normalized image plane into a pixel.
+    fn denormalize(&self, [x, y]: [f32; 2]) -> [f32; 2] {
+        [x * self.0.fx + self.0.ppx, y * self.0.fy + self.0.ppy]
+    }
+
+    /// An OpenGL-compatible projection matrix for these intrinsics.
+    ///
+    /// The matrix maps points in OpenGL view space (x right, y up, looking down -z) into clip
+    /// space, such that the projected points line up with the pixels of the image. `near` and
+    /// `far` are the distances to the near and far clipping planes, in the same units as the
+    /// points. Distortion is ignored.
+    ///
+    /// Note that RealSense points use a different convention (x right, y down, looking down +z),
+    /// so they have to have their y and z coordinates negated before being projected.
+    ///
+    /// Only available with the `glam` feature enabled.
+    #[cfg(feature = "glam")]
+    pub fn to_projection_glam(&self, near: f32, far: f32) -> glam::Mat4 {
+        let width = self.0.width as f32;
+        let height = self.0.height as f32;
+
+        glam::Mat4::from_cols(
+            glam::Vec4::new(2.0 * self.0.fx / width, 0.0, 0.0, 0.0),
+            glam::Vec4::new(0.0, 2.0 * self.0.fy / height, 0.0, 0.0),
+            glam::Vec4::new(
+                1.0 - 2.0 * self.0.ppx / width,
+                2.0 * self.0.ppy / height - 1.0,
+                -(far + near) / (far - near),
+                -1.0,
+            ),
+            glam::Vec4::new(0.0, 0.0, -2.0 * far * near / (far - near), 0.0),
+        )
+    }
+}
+
+/// Conversions into `nalgebra` types.
+///
+/// Only available with the `nalgebra` feature enabled.
+#[cfg(feature = "nalgebra")]
+impl Rs2Intrinsics {
+    /// The pinhole camera matrix `K` of the intrinsics.
+    ///
+    /// The matrix maps a point `[x, y, z]` in the camera's coordinate frame to the homogeneous
+    /// pixel `z * [u, v, 1]`, ignoring distortion. Cameras on D400 and L500 devices use a
+    /// right-handed frame with x pointing right, y pointing down and z pointing forward, out of
+    /// the lens, so `K` takes the usual form of the OpenCV convention:
+    ///
+    /// ```text
+    /// | fx  0 ppx |
+    /// |  0 fy ppy |
+    /// |  0  0   1 |
+    /// ```
+    pub fn camera_matrix(&self) -> nalgebra::Matrix3<f32> {
+        nalgebra::Matrix3::new(
+            self.0.fx, 0.0, self.0.ppx, 0.0, self.0.fy, self.0.ppy, 0.0, 0.0, 1.0,
+        )
+    }
+
+    /// The distortion coefficients of the intrinsics.
+    ///
+    /// See [`Rs2Distortion`] for how the coefficients are interpreted by each distortion model.
+    /// For the Brown-Conrady models, the order `[k1, k2, p1, p2, k3]` is the one OpenCV expects.
+    pub fn distortion_coeffs(&self) -> nalgebra::SVector<f32, 5> {
+        nalgebra::SVector::from(self.0.coeffs)
+    }
 }
 
 unsafe impl Send for Rs2Intrinsics {}
 
+/// The number of iterations used to invert distortion models without a closed-form inverse.
+const UNDISTORT_ITERATIONS: usize = 10;
+
+/// Apply Brown-Conrady distortion with coefficients `[k1, k2, p1, p2, k3]` to a normalized point.
+fn brown_conrady([x, y]: [f32; 2], coeffs: &[f32; 5]) -> [f32; 2] {
+    let [k1, k2, p1, p2, k3] = *coeffs;
+    let r2 = x * x + y * y;
+    let f = 1.0 + k1 * r2 + k2 * r2 * r2 + k3 * r2 * r2 * r2;
+
+    [
+        x * f + 2.0 * p1 * x * y + p2 * (r2 + 2.0 * x * x),
+        y * f + 2.0 * p2 * x * y + p1 * (r2 + 2.0 * y * y),
+    ]
+}
+
+/// Remove Brown-Conrady distortion from a normalized point.
+///
+/// This is the fixed-point iteration librealsense2 uses when deprojecting pixels, with one
+/// difference: librealsense2 computes the tangential term from `x / icdist`, i.e. the radially
+/// distorted estimate, which inverts [`modified_brown_conrady`] rather than [`brown_conrady`].
+/// Here the tangential term is computed from the undistorted estimate, so that this is the inverse
+/// of [`brown_conrady`].
+fn undistort_brown_conrady([xd, yd]: [f32; 2], coeffs: &[f32; 5]) -> [f32; 2] {
+    let [k1, k2, p1, p2, k3] = *coeffs;
+    let (mut x, mut y) = (xd, yd);
+
+    for _ in 0..UNDISTORT_ITERATIONS {
+        let r2 = x * x + y * y;
+        let icdist = 1.0 / (1.0 + ((k3 * r2 + k2) * r2 + k1) * r2);
+        let delta_x = 2.0 * p1 * x * y + p2 * (r2 + 2.0 * x * x);
+        let delta_y = 2.0 * p2 * x * y + p1 * (r2 + 2.0 * y * y);
+        x = (xd - delta_x) * icdist;
+        y = (yd - delta_y) * icdist;
+    }
+    [x, y]
+}
+
+/// Apply modified Brown-Conrady distortion to a normalized point.
+///
+/// Unlike [`brown_conrady`], the tangential distortion is computed from the radially distorted
+/// point.
+fn modified_brown_conrady([x, y]: [f32; 2], coeffs: &[f32; 5]) -> [f32; 2] {
+    let [k1, k2, p1, p2, k3] = *coeffs;
+    let r2 = x * x + y * y;
+    let f = 1.0 + k1 * r2 + k2 * r2 * r2 + k3 * r2 * r2 * r2;
+    let (x, y) = (x * f, y * f);
+
+    [
+        x + 2.0 * p1 * x * y + p2 * (r2 + 2.0 * x * x),
+        y + 2.0 * p2 * x * y + p1 * (r2 + 2.0 * y * y),
+    ]
+}
+
+/// Invert the distortion `distort` at the normalized point `target` by fixed-point iteration.
+///
+/// Each iteration moves the estimate by the remaining error, which converges as long as the
+/// distortion stays close to the identity.
+fn invert_iteratively<F>(target: [f32; 2], distort: F) -> [f32; 2]
+where
+    F: Fn([f32; 2]) -> [f32; 2],
+{
+    let mut point = target;
+    for _ in 0..UNDISTORT_ITERATIONS {
+        let distorted = distort(point);
+        point[0] += target[0] - distorted[0];
+        point[1] += target[1] - distorted[1];
+    }
+    point
+}
+
+/// Scale a normalized point so that its distance from the principal point is `radius`.
+fn with_radius([x, y]: [f32; 2], radius: f32) -> [f32; 2] {
+    let r = x.hypot(y);
+    if r < f32::EPSILON {
+        return [x, y];
+    }
+    [x * radius / r, y * radius / r]
+}
+
+/// Apply F-Theta fisheye distortion with field of view `w` to a normalized point.
+fn ftheta(point: [f32; 2], w: f32) -> [f32; 2] {
+    if w.abs() < f32::EPSILON {
+        return point;
+    }
+    let r = point[0].hypot(point[1]);
+    with_radius(point, (2.0 * r * (w / 2.0).tan()).atan() / w)
+}
+
+/// Remove F-Theta fisheye distortion with field of view `w` from a normalized point.
+fn undistort_ftheta(point: [f32; 2], w: f32) -> [f32; 2] {
+    if w.abs() < f32::EPSILON {
+        return point;
+    }
+    let rd = point[0].hypot(point[1]);
+    with_radius(point, (rd * w).tan() / (2.0 * (w / 2.0).tan()))
+}
+
+/// The distorted radius of a ray at angle `theta` from the optical axis, in the Kannala-Brandt
+/// model with coefficients `[k1, k2, k3, k4, _]`.
+fn kannala_brandt_radius(theta: f32, coeffs: &[f32; 5]) -> f32 {
+    let [k1, k2, k3, k4, _] = *coeffs;
+    let theta2 = theta * theta;
+    theta * (1.0 + theta2 * (k1 + theta2 * (k2 + theta2 * (k3 + theta2 * k4))))
+}
+
+/// Apply Kannala-Brandt fisheye distortion to a normalized point.
+fn kannala_brandt(point: [f32; 2], coeffs: &[f32; 5]) -> [f32; 2] {
+    let theta = point[0].hypot(point[1]).atan();
+    with_radius(point, kannala_brandt_radius(theta, coeffs))
+}
+
+/// Remove Kannala-Brandt fisheye distortion from a normalized point.
+///
+/// The angle of the ray is solved for with Newton's method, as librealsense2 does when
+/// deprojecting pixels.
+fn undistort_kannala_brandt(point: [f32; 2], coeffs: &[f32; 5]) -> [f32; 2] {
+    let [k1, k2, k3, k4, _] = *coeffs;
+    let rd = point[0].hypot(point[1]);
+
+    let mut theta = rd;
+    for _ in 0..UNDISTORT_ITERATIONS {
+        let theta2 = theta * theta;
+        let slope = 1.0
+            + theta2 * (3.0 * k1 + theta2 * (5.0 * k2 + theta2 * (7.0 * k3 + theta2 * 9.0 * k4)));
+        theta -= (kannala_brandt_radius(theta, coeffs) - rd) / slope;
+    }
+    with_radius(point, theta.tan())
+}
+
 /// The topology describing how the different devices are oriented.
 ///
 /// Use the function `stream_profile.extrinsics()` to retrieve these extrinsics from a certain stream in relation to
@@ -158,6 +429,123 @@ impl Rs2Extrinsics {
     pub fn translation(&self) -> [f32; 3usize] {
         self.0.translation
     }
+
+    /// The transform as a 4x4 homogeneous [`glam::Mat4`].
+    ///
+    /// Only available with the `glam` feature enabled.
+    #[cfg(feature = "glam")]
+    pub fn to_mat4_glam(&self) -> glam::Mat4 {
+        let [r0, r1, r2, r3, r4, r5, r6, r7, r8] = self.0.rotation;
+        let [t0, t1, t2] = self.0.translation;
+
+        glam::Mat4::from_cols_array(&[
+            r0, r1, r2, 0.0, r3, r4, r5, 0.0, r6, r7, r8, 0.0, t0, t1, t2, 1.0,
+        ])
+    }
+
+    /// The identity transform, with no rotation and no translation.
+    pub fn identity() -> Self {
+        Rs2Extrinsics(sys::rs2_extrinsics {
+            rotation: [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+            translation: [0.0; 3],
+        })
+    }
+
+    /// Chain this transform with `other`, producing a single transform that applies `self` first
+    /// and then `other`.
+    ///
+    /// If `self` transforms points from frame A to frame B and `other` transforms points from frame
+    /// B to frame C, the result transforms points from frame A to frame C. E.g. composing
+    /// depth-to-color extrinsics with color-to-world extrinsics gives depth-to-world extrinsics.
+    pub fn compose(&self, other: &Rs2Extrinsics) -> Rs2Extrinsics {
+        let r1 = &self.0.rotation;
+        let r2 = &other.0.rotation;
+
+        let mut rotation = [0.0; 9];
+        for col in 0..3 {
+            for row in 0..3 {
+                rotation[col * 3 + row] = (0..3).map(|k| r2[k * 3 + row] * r1[col * 3 + k]).sum();
+            }
+        }
+
+        Rs2Extrinsics(sys::rs2_extrinsics {
+            rotation,
+            translation: other.transform_point(self.0.translation),
+        })
+    }
+
+    /// The inverse transform, which maps points back from the destination frame to the source
+    /// frame.
+    pub fn inverse(&self) -> Rs2Extrinsics {
+        let r = &self.0.rotation;
+        let t = &self.0.translation;
+
+        // The inverse of a rotation is its transpose.
+        let mut rotation = [0.0; 9];
+        for col in 0..3 {
+            for row in 0..3 {
+                rotation[col * 3 + row] = r[row * 3 + col];
+            }
+        }
+
+        let mut translation = [0.0; 3];
+        for (row, value) in translation.iter_mut().enumerate() {
+            *value = -(0..3).map(|k| rotation[k * 3 + row] * t[k]).sum::<f32>();
+        }
+
+        Rs2Extrinsics(sys::rs2_extrinsics {
+            rotation,
+            translation,
+        })
+    }
+
+    /// Apply this transform to a point.
+    fn transform_point(&self, point: [f32; 3]) -> [f32; 3] {
+        let r = &self.0.rotation;
+        let t = &self.0.translation;
+
+        let mut out = [0.0; 3];
+        for (row, value) in out.iter_mut().enumerate() {
+            *value = (0..3).map(|k| r[k * 3 + row] * point[k]).sum::<f32>() + t[row];
+        }
+        out
+    }
+}
+
+impl Mul for Rs2Extrinsics {
+    type Output = Rs2Extrinsics;
+
+    /// Chain two transforms, applying `self` first and then `rhs`.
+    ///
+    /// See [`Rs2Extrinsics::compose`].
+    fn mul(self, rhs: Rs2Extrinsics) -> Self::Output {
+        self.compose(&rhs)
+    }
+}
+
+/// Conversions into `nalgebra` types.
+///
+/// Only available with the `nalgebra` feature enabled.
+#[cfg(feature = "nalgebra")]
+impl Rs2Extrinsics {
+    /// The rotation of the transform as a [`nalgebra::Matrix3`].
+    ///
+    /// The rotation maps points from the coordinate frame of the source stream into that of the
+    /// target stream. All RealSense coordinate frames are right-handed, so this is a proper
+    /// rotation. Image streams on D400 and L500 devices have x pointing right, y pointing down and
+    /// z pointing forward. Motion streams use the same axes on the D400 series, but are aligned
+    /// with the device on T265, where y points up and z points backwards.
+    pub fn rotation_matrix(&self) -> nalgebra::Matrix3<f32> {
+        nalgebra::Matrix3::from_column_slice(&self.0.rotation)
+    }
+
+    /// The translation of the transform as a [`nalgebra::Vector3`], in meters.
+    ///
+    /// The translation is expressed in the coordinate frame of the target stream, and is applied
+    /// after the [rotation](Rs2Extrinsics::rotation_matrix).
+    pub fn translation_vector(&self) -> nalgebra::Vector3<f32> {
+        nalgebra::Vector3::from(self.0.translation)
+    }
 }
 
 unsafe impl Send for Rs2Extrinsics {}
@@ -174,3 +562,195 @@ pub struct Rs2Roi {
     /// Bottom coordinate of the region of interest.
     pub max_y: i32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A rotation of 90 degrees about the z-axis, followed by a translation.
+    fn rotate_z_then_shift() -> Rs2Extrinsics {
+        Rs2Extrinsics(sys::rs2_extrinsics {
+            rotation: [0.0, 1.0, 0.0, -1.0, 0.0, 0.0, 0.0, 0.0, 1.0],
+            translation: [1.0, 2.0, 3.0],
+        })
+    }
+
+    fn assert_points_eq(a: [f32; 3], b: [f32; 3]) {
+        for i in 0..3 {
+            assert!((a[i] - b[i]).abs() < 1e-6, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn identity_does_not_move_points() {
+        let p = [0.5, -1.0, 2.0];
+        assert_points_eq(Rs2Extrinsics::identity().transform_point(p), p);
+    }
+
+    #[test]
+    fn compose_applies_self_then_other() {
+        let first = rotate_z_then_shift();
+        let second = Rs2Extrinsics(sys::rs2_extrinsics {
+            rotation: [1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, -1.0, 0.0],
+            translation: [-0.5, 0.0, 0.25],
+        });
+        let p = [0.5, -1.0, 2.0];
+
+        let expected = second.transform_point(first.transform_point(p));
+        assert_points_eq(first.compose(&second).transform_point(p), expected);
+        assert_points_eq((first * second).transform_point(p), expected);
+    }
+
+    #[test]
+    fn inverse_undoes_transform() {
+        let extrinsics = rotate_z_then_shift();
+        let p = [0.5, -1.0, 2.0];
+
+        assert_points_eq(
+            extrinsics
+                .inverse()
+                .transform_point(extrinsics.transform_point(p)),
+            p,
+        );
+
+        let round_trip = extrinsics.compose(&extrinsics.inverse());
+        assert_points_eq(round_trip.transform_point(p), p);
+    }
+
+    #[cfg(feature = "nalgebra")]
+    #[test]
+    fn nalgebra_rotation_and_translation_match_transform() {
+        let extrinsics = rotate_z_then_shift();
+        let p = [0.5, -1.0, 2.0];
+
+        let transformed = extrinsics.rotation_matrix() * nalgebra::Vector3::from(p)
+            + extrinsics.translation_vector();
+        assert_points_eq(transformed.into(), extrinsics.transform_point(p));
+    }
+
+    #[cfg(feature = "nalgebra")]
+    #[test]
+    fn camera_matrix_projects_without_distortion() {
+        let intrinsics = intrinsics_with(sys::rs2_distortion_RS2_DISTORTION_NONE, [0.0; 5]);
+        let pixel = intrinsics.camera_matrix() * nalgebra::Vector3::new(0.5, -0.25, 2.0);
+
+        assert!((pixel.x / pixel.z - (0.25 * 286.0 + 424.5)).abs() < 1e-4);
+        assert!((pixel.y / pixel.z - (-0.125 * 287.0 + 400.25)).abs() < 1e-4);
+    }
+
+    fn intrinsics_with(model: sys::rs2_distortion, coeffs: [f32; 5]) -> Rs2Intrinsics {
+        Rs2Intrinsics(sys::rs2_intrinsics {
+            width: 848,
+            height: 800,
+            ppx: 424.5,
+            ppy: 400.25,
+            fx: 286.0,
+            fy: 287.0,
+            model,
+            coeffs,
+        })
+    }
+
+    fn assert_pixels_eq(a: [f32; 2], b: [f32; 2]) {
+        for i in 0..2 {
+            assert!((a[i] - b[i]).abs() < 1e-2, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn undistort_inverts_distort() {
+        let models = [
+            (sys::rs2_distortion_RS2_DISTORTION_NONE, [0.0; 5]),
+            (
+                sys::rs2_distortion_RS2_DISTORTION_BROWN_CONRADY,
+                [0.05, -0.02, 0.001, -0.0005, 0.003],
+            ),
+            (
+                sys::rs2_distortion_RS2_DISTORTION_MODIFIED_BROWN_CONRADY,
+                [0.05, -0.02, 0.001, -0.0005, 0.003],
+            ),
+            (
+                sys::rs2_distortion_RS2_DISTORTION_INVERSE_BROWN_CONRADY,
+                [0.05, -0.02, 0.001, -0.0005, 0.003],
+            ),
+            (
+                sys::rs2_distortion_RS2_DISTORTION_FTHETA,
+                [0.9, 0.0, 0.0, 0.0, 0.0],
+            ),
+            (
+                sys::rs2_distortion_RS2_DISTORTION_KANNALA_BRANDT4,
+                [-0.01, 0.04, -0.04, 0.007, 0.0],
+            ),
+        ];
+
+        for &(model, coeffs) in &models {
+            let intrinsics = intrinsics_with(model, coeffs);
+            for &pixel in &[
+                [424.5, 400.25],
+                [100.0, 50.0],
+                [700.0, 650.0],
+                [424.5, 780.0],
+            ] {
+                assert_pixels_eq(intrinsics.undistort(intrinsics.distort(pixel)), pixel);
+            }
+        }
+    }
+
+    #[test]
+    fn distortion_moves_points_off_center() {
+        let intrinsics = intrinsics_with(
+            sys::rs2_distortion_RS2_DISTORTION_BROWN_CONRADY,
+            [0.05, -0.02, 0.001, -0.0005, 0.003],
+        );
+
+        assert_pixels_eq(intrinsics.distort([424.5, 400.25]), [424.5, 400.25]);
+        let corner = [100.0, 50.0];
+        let distorted = intrinsics.distort(corner);
+        assert!((distorted[0] - corner[0]).abs() > 1.0);
+    }
+
+    // librealsense2 is not linked in docs-only builds, so the projection tests cannot run there.
+    #[cfg(not(feature = "docs-only"))]
+    fn brown_conrady_intrinsics() -> Rs2Intrinsics {
+        Rs2Intrinsics(sys::rs2_intrinsics {
+            width: 1280,
+            height: 720,
+            ppx: 640.5,
+            ppy: 360.25,
+            fx: 915.0,
+            fy: 914.0,
+            model: sys::rs2_distortion_RS2_DISTORTION_BROWN_CONRADY,
+            coeffs: [0.05, -0.02, 0.001, -0.0005, 0.003],
+        })
+    }
+
+    #[cfg(not(feature = "docs-only"))]
+    #[test]
+    fn principal_axis_projects_onto_principal_point() {
+        let intrinsics = brown_conrady_intrinsics();
+        let pixel = intrinsics.project([0.0, 0.0, 2.0]);
+
+        assert!((pixel[0] - intrinsics.ppx()).abs() < 1e-4);
+        assert!((pixel[1] - intrinsics.ppy()).abs() < 1e-4);
+    }
+
+    #[cfg(not(feature = "docs-only"))]
+    #[test]
+    fn deproject_inverts_project() {
+        let intrinsics = brown_conrady_intrinsics();
+
+        for &point in &[[0.3, -0.2, 1.5], [-0.5, 0.25, 2.0], [0.1, 0.1, 0.5]] {
+            let pixel = intrinsics.project(point);
+            let deprojected = intrinsics.deproject(pixel, point[2]);
+
+            for (actual, expected) in deprojected.iter().zip(point.iter()) {
+                assert!(
+                    (actual - expected).abs() < 1e-4,
+                    "{:?} deprojected to {:?}",
+                    point,
+                    deprojected
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,215 @@
+//! [serde](https://serde.rs) support for intrinsics and extrinsics.
+//!
+//! The librealsense2 structs wrapped by [`Rs2Intrinsics`], [`Rs2Extrinsics`] and
+//! [`Rs2MotionDeviceIntrinsics`] are (de)serialized through mirror structs with the same fields,
+//! so that e.g. calibration obtained at startup can be persisted to disk and loaded for offline
+//! processing. The distortion model of intrinsics is stored as an [`Rs2DistortionModel`] rather
+//! than its raw value.
+//!
+//! This module is only available with the `serde` feature enabled.
+
+use super::{Rs2Extrinsics, Rs2Intrinsics, Rs2MotionDeviceIntrinsics};
+use crate::kind::Rs2DistortionModel;
+use ::serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use num_traits::{FromPrimitive, ToPrimitive};
+use realsense_sys as sys;
+
+/// Serializable mirror of `sys::rs2_intrinsics`.
+#[derive(Serialize, Deserialize)]
+struct Intrinsics {
+    /// Width of the image in pixels.
+    width: i32,
+    /// Height of the image in pixels.
+    height: i32,
+    /// Horizontal coordinate of the principal point.
+    ppx: f32,
+    /// Vertical coordinate of the principal point.
+    ppy: f32,
+    /// Focal length as a multiple of pixel width.
+    fx: f32,
+    /// Focal length as a multiple of pixel height.
+    fy: f32,
+    /// Distortion model of the image.
+    model: Rs2DistortionModel,
+    /// Distortion coefficients.
+    coeffs: [f32; 5],
+}
+
+impl Serialize for Rs2Intrinsics {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let model = Rs2DistortionModel::from_u32(self.0.model).ok_or_else(|| {
+            ::serde::ser::Error::custom(format!("unknown distortion model {}", self.0.model))
+        })?;
+
+        Intrinsics {
+            width: self.0.width,
+            height: self.0.height,
+            ppx: self.0.ppx,
+            ppy: self.0.ppy,
+            fx: self.0.fx,
+            fy: self.0.fy,
+            model,
+            coeffs: self.0.coeffs,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rs2Intrinsics {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let intrinsics = Intrinsics::deserialize(deserializer)?;
+        let model = intrinsics
+            .model
+            .to_u32()
+            .ok_or_else(|| de::Error::custom("distortion model cannot be converted"))?;
+
+        Ok(Rs2Intrinsics(sys::rs2_intrinsics {
+            width: intrinsics.width,
+            height: intrinsics.height,
+            ppx: intrinsics.ppx,
+            ppy: intrinsics.ppy,
+            fx: intrinsics.fx,
+            fy: intrinsics.fy,
+            model,
+            coeffs: intrinsics.coeffs,
+        }))
+    }
+}
+
+/// Serializable mirror of `sys::rs2_extrinsics`.
+#[derive(Serialize, Deserialize)]
+struct Extrinsics {
+    /// Column-major 3x3 rotation matrix.
+    rotation: [f32; 9],
+    /// Three-element translation vector, in meters.
+    translation: [f32; 3],
+}
+
+impl Serialize for Rs2Extrinsics {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Extrinsics {
+            rotation: self.0.rotation,
+            translation: self.0.translation,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rs2Extrinsics {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let extrinsics = Extrinsics::deserialize(deserializer)?;
+
+        Ok(Rs2Extrinsics(sys::rs2_extrinsics {
+            rotation: extrinsics.rotation,
+            translation: extrinsics.translation,
+        }))
+    }
+}
+
+/// Serializable mirror of `sys::rs2_motion_device_intrinsic`.
+#[derive(Serialize, Deserialize)]
+struct MotionDeviceIntrinsics {
+    /// 3x4 matrix of scale and bias intrinsics.
+    data: [[f32; 4]; 3],
+    /// Variance of noise for X, Y, and Z axis.
+    noise_variances: [f32; 3],
+    /// Variance of bias for X, Y, and Z axis.
+    bias_variances: [f32; 3],
+}
+
+impl Serialize for Rs2MotionDeviceIntrinsics {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        MotionDeviceIntrinsics {
+            data: self.0.data,
+            noise_variances: self.0.noise_variances,
+            bias_variances: self.0.bias_variances,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rs2MotionDeviceIntrinsics {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let intrinsics = MotionDeviceIntrinsics::deserialize(deserializer)?;
+
+        Ok(Rs2MotionDeviceIntrinsics(
+            sys::rs2_motion_device_intrinsic {
+                data: intrinsics.data,
+                noise_variances: intrinsics.noise_variances,
+                bias_variances: intrinsics.bias_variances,
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::Rs2Distortion;
+
+    #[test]
+    fn intrinsics_round_trip_through_json() {
+        let intrinsics = Rs2Intrinsics(sys::rs2_intrinsics {
+            width: 640,
+            height: 480,
+            ppx: 320.5,
+            ppy: 240.25,
+            fx: 600.0,
+            fy: 601.0,
+            model: sys::rs2_distortion_RS2_DISTORTION_INVERSE_BROWN_CONRADY,
+            coeffs: [0.1, -0.2, 0.001, 0.002, 0.03],
+        });
+
+        let json = serde_json::to_string(&intrinsics).unwrap();
+        assert!(json.contains("\"model\":\"BrownConradyInverse\""));
+
+        let round_trip: Rs2Intrinsics = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_trip.width(), 640);
+        assert_eq!(round_trip.height(), 480);
+        assert_eq!(round_trip.ppx(), 320.5);
+        assert_eq!(round_trip.ppy(), 240.25);
+        assert_eq!(round_trip.fx(), 600.0);
+        assert_eq!(round_trip.fy(), 601.0);
+        assert_eq!(
+            round_trip.distortion().model,
+            Rs2DistortionModel::BrownConradyInverse
+        );
+        assert_eq!(round_trip.distortion().coeffs, intrinsics.0.coeffs);
+    }
+
+    #[test]
+    fn extrinsics_and_motion_intrinsics_round_trip_through_json() {
+        let extrinsics = Rs2Extrinsics(sys::rs2_extrinsics {
+            rotation: [0.0, 1.0, 0.0, -1.0, 0.0, 0.0, 0.0, 0.0, 1.0],
+            translation: [0.015, 0.0, -0.001],
+        });
+        let round_trip: Rs2Extrinsics =
+            serde_json::from_str(&serde_json::to_string(&extrinsics).unwrap()).unwrap();
+        assert_eq!(round_trip.rotation(), extrinsics.rotation());
+        assert_eq!(round_trip.translation(), extrinsics.translation());
+
+        let motion = Rs2MotionDeviceIntrinsics(sys::rs2_motion_device_intrinsic {
+            data: [
+                [1.0, 0.01, 0.02, 0.1],
+                [0.01, 1.0, 0.03, -0.2],
+                [0.02, 0.03, 1.0, 0.3],
+            ],
+            noise_variances: [0.001, 0.002, 0.003],
+            bias_variances: [0.0001, 0.0002, 0.0003],
+        });
+        let round_trip: Rs2MotionDeviceIntrinsics =
+            serde_json::from_str(&serde_json::to_string(&motion).unwrap()).unwrap();
+        assert_eq!(round_trip.data(), motion.data());
+        assert_eq!(round_trip.noise_variances(), motion.noise_variances());
+        assert_eq!(round_trip.bias_variances(), motion.bias_variances());
+
+        let distortion = Rs2Distortion {
+            model: Rs2DistortionModel::FThetaFisheye,
+            coeffs: [0.5, 0.0, 0.0, 0.0, 0.0],
+        };
+        let round_trip: Rs2Distortion =
+            serde_json::from_str(&serde_json::to_string(&distortion).unwrap()).unwrap();
+        assert_eq!(round_trip.model, distortion.model);
+        assert_eq!(round_trip.coeffs, distortion.coeffs);
+    }
+}
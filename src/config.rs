@@ -3,11 +3,21 @@
 use crate::{
     base::from_path,
     check_rs2_error,
-    kind::{Rs2Exception, Rs2Format, Rs2StreamKind},
+    context::Context,
+    device::Device,
+    kind::{Rs2CameraInfo, Rs2Exception, Rs2Format, Rs2StreamKind},
+    pipeline::{InactivePipeline, PipelineProfile},
+    stream_profile::StreamProfile,
 };
 use anyhow::Result;
 use realsense_sys as sys;
-use std::{convert::TryInto, ffi::CStr, path::Path, ptr::NonNull};
+use std::{
+    collections::HashSet,
+    convert::TryInto,
+    ffi::{CStr, CString},
+    path::Path,
+    ptr::NonNull,
+};
 use thiserror::Error;
 
 /// Type describing all possible errors that can occur when trying to configure a pipeline.
@@ -28,16 +38,99 @@ pub enum ConfigurationError {
     /// The specified device could not be enabled.
     #[error("Could not enable requested device. Type: {0}; Reason: {1}")]
     CouldNotEnableDevice(Rs2Exception, String),
+    /// The device connected to the specified port could not be enabled.
+    #[error("Could not enable device from port. Type: {0}; Reason: {1}")]
+    CouldNotEnableDeviceFromPort(Rs2Exception, String),
     /// Recording to file could not be enabled for the specified device.
     #[error("Could not enable recording to file from device. Type: {0}; Reason: {1}")]
     CouldNotEnableRecordingToFile(Rs2Exception, String),
+    /// The configuration cannot be resolved by any connected device / stream combination.
+    #[error("Config cannot be resolved by any active devices / stream combinations.")]
+    ConfigCannotBeResolved,
+    /// The enabled streams can each be resolved on their own, but not together.
+    ///
+    /// See [`Config::validate_stream_combination`] for more information.
+    #[error("Streams cannot be enabled together. Kinds: {0:?}")]
+    ConflictingStreams(Vec<Rs2StreamKind>),
+}
+
+/// The attributes of a stream enabled through [`Config::enable_stream`].
+///
+/// See [`Config::streams`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamDescriptor {
+    /// The kind of stream.
+    kind: Rs2StreamKind,
+    /// The stream index, or `None` to let librealsense2 pick it.
+    index: Option<usize>,
+    /// The requested width in pixels, or zero for any.
+    width: usize,
+    /// The requested height in pixels, or zero for any.
+    height: usize,
+    /// The requested format.
+    format: Rs2Format,
+    /// The requested framerate, or zero for any.
+    framerate: usize,
+}
+
+impl StreamDescriptor {
+    /// Get the kind of stream.
+    pub fn kind(&self) -> Rs2StreamKind {
+        self.kind
+    }
+
+    /// Get the stream index, or `None` if librealsense2 picks it.
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    /// Get the requested width in pixels, or zero for any.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get the requested height in pixels, or zero for any.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the requested format.
+    pub fn format(&self) -> Rs2Format {
+        self.format
+    }
+
+    /// Get the requested framerate, or zero for any.
+    pub fn framerate(&self) -> usize {
+        self.framerate
+    }
+}
+
+/// The device that a configuration has been restricted to.
+#[derive(Debug, Clone)]
+enum DeviceRequest {
+    /// A connected device with the given serial number.
+    Serial(CString),
+    /// Playback from the given file, and whether playback loops.
+    File(CString, bool),
 }
 
 /// Type representing the [`Pipeline`](crate::pipeline::InactivePipeline) configuration.
+///
+/// librealsense2 cannot copy a configuration, so cloning a `Config` constructs a new one and
+/// replays the device, streams and recording that were enabled on this one.
 #[derive(Debug)]
 pub struct Config {
     /// A non-null pointer to the underlying librealsense2 configuration.
     config_ptr: NonNull<sys::rs2_config>,
+    /// The streams enabled through [`Config::enable_stream`], tracked so that conflicting streams
+    /// can be identified in [`Config::validate_stream_combination`].
+    streams: Vec<StreamDescriptor>,
+    /// Whether all streams were enabled through [`Config::enable_all_streams`].
+    all_streams: bool,
+    /// The device the configuration has been restricted to, if any.
+    device: Option<DeviceRequest>,
+    /// The file that streams are recorded to, if any.
+    record_to: Option<CString>,
 }
 
 impl Drop for Config {
@@ -50,6 +143,22 @@ impl Drop for Config {
 
 unsafe impl Send for Config {}
 
+// Config is deliberately not `Sync`: librealsense2 caches the resolved pipeline profile inside
+// the config while resolving, so even `&self` methods like `can_resolve` mutate it.
+
+impl Clone for Config {
+    /// Clone the configuration, see [`Config::try_clone`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if librealsense2 rejects the configuration when it is replayed, see
+    /// [`Config::try_clone`] for a non-panicking alternative.
+    fn clone(&self) -> Self {
+        self.try_clone()
+            .expect("Could not replay configuration that was previously accepted")
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self::new()
@@ -74,8 +183,262 @@ impl Config {
 
             Self {
                 config_ptr: NonNull::new(ptr).unwrap(),
+                streams: Vec::new(),
+                all_streams: false,
+                device: None,
+                record_to: None,
+            }
+        }
+    }
+
+    /// Construct a new configuration that only applies to the device with the given serial number.
+    ///
+    /// This is shorthand for calling [`Config::new`] followed by
+    /// [`Config::enable_device_from_serial`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigurationError::CouldNotEnableDevice`] if the device could not be enabled.
+    ///
+    pub fn with_device_serial(serial: &CStr) -> Result<Self, ConfigurationError> {
+        let mut config = Self::new();
+        config.enable_device_from_serial(serial)?;
+        Ok(config)
+    }
+
+    /// Construct a new configuration that records data streams to the file at `path`.
+    ///
+    /// This is shorthand for calling [`Config::new`] followed by
+    /// [`Config::enable_record_to_file`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Config::enable_record_to_file`].
+    ///
+    pub fn with_recording<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let mut config = Self::new();
+        config.enable_record_to_file(path)?;
+        Ok(config)
+    }
+
+    /// Construct a new configuration that enables exactly the streams described by `profiles`.
+    ///
+    /// Each profile is enabled with its kind, index, format and framerate, as well as its
+    /// resolution for video streams. This makes it possible to reproduce a stream configuration,
+    /// e.g. one taken from [`PipelineProfile::streams`](crate::pipeline::PipelineProfile::streams)
+    /// in a previous session. If `serial` is provided, the configuration is restricted to the
+    /// device with that serial number.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigurationError::CouldNotEnableDevice`] if the device could not be enabled.
+    ///
+    /// Returns [`ConfigurationError::CouldNotEnableStream`] if any of the streams could not be
+    /// enabled.
+    ///
+    pub fn from_stream_profiles<'a, I>(
+        profiles: I,
+        serial: Option<&CStr>,
+    ) -> Result<Self, ConfigurationError>
+    where
+        I: IntoIterator<Item = &'a StreamProfile>,
+    {
+        let mut config = Self::new();
+        if let Some(serial) = serial {
+            config.enable_device_from_serial(serial)?;
+        }
+
+        for profile in profiles {
+            let (width, height) = profile
+                .try_as_video()
+                .map(|video| (video.width(), video.height()))
+                .unwrap_or((0, 0));
+
+            config.enable_stream(
+                profile.kind(),
+                Some(profile.index()),
+                width,
+                height,
+                profile.format(),
+                profile.framerate() as usize,
+            )?;
+        }
+        Ok(config)
+    }
+
+    /// Get the stream profiles that would be active if `pipeline` were started with this
+    /// configuration.
+    ///
+    /// This is shorthand for [`InactivePipeline::resolve`] followed by
+    /// [`PipelineProfile::into_streams`](crate::pipeline::PipelineProfile::into_streams). Returns
+    /// `None` if the configuration cannot be resolved.
+    pub fn preview_streams(&self, pipeline: &InactivePipeline) -> Option<Vec<StreamProfile>> {
+        pipeline.resolve(self).map(|profile| profile.into_streams())
+    }
+
+    /// Check that the enabled streams can be resolved together by `pipeline`.
+    ///
+    /// Some combinations of streams cannot be streamed at the same time, even though each of the
+    /// streams is supported on its own, e.g. some depth and infrared resolutions on D400 devices.
+    /// Such configurations otherwise only fail once the pipeline is started. If the configuration
+    /// cannot be resolved, every pair of streams enabled through [`Config::enable_stream`] is
+    /// resolved separately to find the streams that conflict with each other.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigurationError::ConflictingStreams`] with the kinds of the conflicting
+    /// streams if every enabled stream can be resolved on its own, but not together with the
+    /// others.
+    ///
+    /// Returns [`ConfigurationError::ConfigCannotBeResolved`] if the configuration cannot be
+    /// resolved for any other reason, e.g. because one of the streams is not supported at all.
+    ///
+    pub fn validate_stream_combination(
+        &self,
+        pipeline: &InactivePipeline,
+    ) -> Result<(), ConfigurationError> {
+        if pipeline.can_resolve(self) {
+            return Ok(());
+        }
+
+        let resolves = |streams: &[StreamDescriptor]| -> Result<bool, ConfigurationError> {
+            Ok(pipeline.can_resolve(&self.with_streams(streams)?))
+        };
+
+        for stream in &self.streams {
+            if !resolves(&[*stream])? {
+                return Err(ConfigurationError::ConfigCannotBeResolved);
+            }
+        }
+
+        let mut conflicting = Vec::new();
+        for (i, first) in self.streams.iter().enumerate() {
+            for second in &self.streams[i + 1..] {
+                if !resolves(&[*first, *second])? {
+                    for kind in [first.kind, second.kind].iter() {
+                        if !conflicting.contains(kind) {
+                            conflicting.push(*kind);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Each pair resolves on its own, so the conflict is between all of the streams.
+        if conflicting.is_empty() && self.streams.len() > 1 {
+            for stream in &self.streams {
+                if !conflicting.contains(&stream.kind) {
+                    conflicting.push(stream.kind);
+                }
+            }
+        }
+
+        if conflicting.is_empty() {
+            Err(ConfigurationError::ConfigCannotBeResolved)
+        } else {
+            Err(ConfigurationError::ConflictingStreams(conflicting))
+        }
+    }
+
+    /// Resolve the configuration against a specific `device`.
+    ///
+    /// This checks whether `pipeline` could be started with this configuration on `device`, and
+    /// returns the pipeline profile that would be active if it were. The configuration itself is
+    /// left unchanged: it is resolved through a copy that is restricted to the serial number of
+    /// `device`, since librealsense2 provides no way to lift a device restriction once set.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigurationError::CouldNotEnableDevice`] if `device` has no serial number or
+    /// cannot be enabled.
+    ///
+    /// Returns [`ConfigurationError::ConfigCannotBeResolved`] if the configuration cannot be
+    /// resolved on `device`, including when it is already restricted to another device.
+    ///
+    pub fn resolve_with_device(
+        &self,
+        pipeline: &InactivePipeline,
+        device: &Device,
+    ) -> Result<PipelineProfile, ConfigurationError> {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).ok_or_else(|| {
+            ConfigurationError::CouldNotEnableDevice(
+                Rs2Exception::InvalidValue,
+                "Device does not have a serial number.".to_owned(),
+            )
+        })?;
+
+        let device_request = match &self.device {
+            None => DeviceRequest::Serial(serial.to_owned()),
+            Some(DeviceRequest::Serial(configured)) if configured.as_c_str() == serial => {
+                DeviceRequest::Serial(configured.clone())
+            }
+            Some(_) => return Err(ConfigurationError::ConfigCannotBeResolved),
+        };
+
+        let config =
+            Self::with_device_and_streams(Some(&device_request), self.all_streams, &self.streams)?;
+
+        pipeline
+            .resolve(&config)
+            .ok_or(ConfigurationError::ConfigCannotBeResolved)
+    }
+
+    /// Construct a configuration for the same device as this one, with only `streams` enabled.
+    fn with_streams(&self, streams: &[StreamDescriptor]) -> Result<Self, ConfigurationError> {
+        Self::with_device_and_streams(self.device.as_ref(), false, streams)
+    }
+
+    /// Construct a configuration restricted to `device`, with only `streams` enabled.
+    ///
+    /// If `all_streams` is true, all streams are enabled before `streams`.
+    fn with_device_and_streams(
+        device: Option<&DeviceRequest>,
+        all_streams: bool,
+        streams: &[StreamDescriptor],
+    ) -> Result<Self, ConfigurationError> {
+        let mut config = Self::new();
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            match device {
+                Some(DeviceRequest::Serial(serial)) => {
+                    sys::rs2_config_enable_device(
+                        config.config_ptr.as_ptr(),
+                        serial.as_ptr(),
+                        &mut err,
+                    );
+                }
+                Some(DeviceRequest::File(path, loop_playback)) => {
+                    sys::rs2_config_enable_device_from_file_repeat_option(
+                        config.config_ptr.as_ptr(),
+                        path.as_ptr(),
+                        *loop_playback as i32,
+                        &mut err,
+                    );
+                }
+                None => {}
             }
+            check_rs2_error!(err, ConfigurationError::CouldNotEnableDevice)?;
+        }
+        config.device = device.cloned();
+
+        if all_streams {
+            config.enable_all_streams()?;
         }
+        for stream in streams {
+            config.enable_stream(
+                stream.kind,
+                stream.index,
+                stream.width,
+                stream.height,
+                stream.format,
+                stream.framerate,
+            )?;
+        }
+        Ok(config)
     }
 
     /// Enable the stream of kind `stream` with the provided attributes.
@@ -116,14 +479,13 @@ impl Config {
         format: Rs2Format,
         framerate: usize,
     ) -> Result<&mut Self, ConfigurationError> {
-        let index: i32 = if let Some(i) = index { i as i32 } else { -1 };
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
             sys::rs2_config_enable_stream(
                 self.config_ptr.as_ptr(),
                 #[allow(clippy::useless_conversion)]
                 (stream as i32).try_into().unwrap(),
-                index,
+                index.map_or(-1, |i| i as i32),
                 width as i32,
                 height as i32,
                 #[allow(clippy::useless_conversion)]
@@ -133,6 +495,18 @@ impl Config {
             );
             check_rs2_error!(err, ConfigurationError::CouldNotEnableStream)?;
         };
+
+        // librealsense2 replaces any earlier request for the same stream kind and index.
+        self.streams
+            .retain(|request| !(request.kind == stream && request.index == index));
+        self.streams.push(StreamDescriptor {
+            kind: stream,
+            index,
+            width,
+            height,
+            format,
+            framerate,
+        });
         Ok(self)
     }
 
@@ -155,6 +529,9 @@ impl Config {
             sys::rs2_config_enable_all_stream(self.config_ptr.as_ptr(), &mut err);
             check_rs2_error!(err, ConfigurationError::CouldNotEnableAllStreams)?;
         }
+        // librealsense2 drops any streams requested before all streams were enabled.
+        self.streams.clear();
+        self.all_streams = true;
         Ok(self)
     }
 
@@ -180,6 +557,45 @@ impl Config {
             sys::rs2_config_enable_device(self.config_ptr.as_ptr(), serial.as_ptr(), &mut err);
             check_rs2_error!(err, ConfigurationError::CouldNotEnableDevice)?;
         }
+        self.device = Some(DeviceRequest::Serial(serial.to_owned()));
+        Ok(self)
+    }
+
+    /// Enable the device connected to a specific physical port.
+    ///
+    /// This is useful to tell apart identical devices, e.g. in rigs whose units get swapped
+    /// around. The port ID can be obtained from the [`Device`](crate::device::Device::info) method
+    /// by passing in [`Rs2CameraInfo::PhysicalPort`], and is platform specific, e.g. `"2-1"` on
+    /// Linux.
+    ///
+    /// Port IDs describe the USB topology at the time the devices are queried, so they change when
+    /// a device is plugged into a different port or hub, or when hubs are rearranged. Prefer
+    /// [`Config::enable_device_from_serial`] where serial numbers are unique.
+    ///
+    /// librealsense2 can only restrict a configuration to a device by serial number, so the device
+    /// at `port_id` is looked up among the currently connected devices, and the configuration is
+    /// restricted to its serial number.
+    ///
+    /// Returns a mutable reference to self if it succeeds or a configuration error.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigurationError::CouldNotEnableDeviceFromPort`] if no device is connected to
+    /// `port_id`, if another connected device has the same serial number, or if the device could
+    /// not be enabled.
+    ///
+    pub fn enable_device_from_usb_port(
+        &mut self,
+        port_id: &CStr,
+    ) -> Result<&mut Self, ConfigurationError> {
+        let serial = serial_of_device_at_port(port_id)?;
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_config_enable_device(self.config_ptr.as_ptr(), serial.as_ptr(), &mut err);
+            check_rs2_error!(err, ConfigurationError::CouldNotEnableDeviceFromPort)?;
+        }
+        self.device = Some(DeviceRequest::Serial(serial));
         Ok(self)
     }
 
@@ -218,6 +634,7 @@ impl Config {
             );
             check_rs2_error!(err, ConfigurationError::CouldNotEnableDevice)?;
         }
+        self.device = Some(DeviceRequest::File(path, loop_playback));
         Ok(self)
     }
 
@@ -245,6 +662,13 @@ impl Config {
         P: AsRef<Path>,
     {
         let path = from_path(file)?;
+        Ok(self.record_to_cstring(path)?)
+    }
+
+    /// Enable recording data streams to the file at `path`.
+    ///
+    /// See [`Config::enable_record_to_file`].
+    fn record_to_cstring(&mut self, path: CString) -> Result<&mut Self, ConfigurationError> {
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
             sys::rs2_config_enable_record_to_file(
@@ -254,6 +678,7 @@ impl Config {
             );
             check_rs2_error!(err, ConfigurationError::CouldNotEnableRecordingToFile)?;
         }
+        self.record_to = Some(path);
         Ok(self)
     }
 
@@ -281,6 +706,8 @@ impl Config {
             );
             check_rs2_error!(err, ConfigurationError::CouldNotDisableStream)?;
         }
+        self.streams
+            .retain(|request| !(request.kind == stream && request.index == Some(index)));
         Ok(self)
     }
 
@@ -308,6 +735,7 @@ impl Config {
             );
             check_rs2_error!(err, ConfigurationError::CouldNotDisableStream)?;
         }
+        self.streams.retain(|request| request.kind != stream);
         Ok(self)
     }
 
@@ -328,9 +756,40 @@ impl Config {
             sys::rs2_config_disable_all_streams(self.config_ptr.as_ptr(), &mut err);
             check_rs2_error!(err, ConfigurationError::CouldNotDisableAllStreams)?;
         }
+        self.streams.clear();
+        self.all_streams = false;
         Ok(self)
     }
 
+    /// Get the streams enabled through [`Config::enable_stream`].
+    ///
+    /// Streams are listed in the order they were enabled. Enabling a stream of the same kind and
+    /// index again replaces the earlier one, and disabling streams removes them from the list.
+    /// Streams enabled through [`Config::enable_all_streams`] are not listed.
+    pub fn streams(&self) -> &[StreamDescriptor] {
+        &self.streams
+    }
+
+    /// Create a new configuration with the same device, streams and recording as this one.
+    ///
+    /// librealsense2 cannot copy a configuration, so the device, the streams and the recording
+    /// enabled on this configuration are enabled again on a new one.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ConfigurationError`] if librealsense2 rejects any of the settings when they are
+    /// replayed. Since each of them was accepted by this configuration, this only happens if the
+    /// state of the system changed in the meantime.
+    pub fn try_clone(&self) -> Result<Self, ConfigurationError> {
+        let mut config =
+            Self::with_device_and_streams(self.device.as_ref(), self.all_streams, &self.streams)?;
+
+        if let Some(path) = &self.record_to {
+            config.record_to_cstring(path.clone())?;
+        }
+        Ok(config)
+    }
+
     /// Get the underlying low-level pointer to the configuration object.
     ///
     /// # Safety
@@ -343,3 +802,67 @@ impl Config {
         self.config_ptr
     }
 }
+
+/// Find the serial number of the device connected to the physical port `port_id`.
+///
+/// The serial number must identify the device uniquely, since librealsense2 selects devices by
+/// serial number.
+fn serial_of_device_at_port(port_id: &CStr) -> Result<CString, ConfigurationError> {
+    let context = Context::new().map_err(|e| {
+        ConfigurationError::CouldNotEnableDeviceFromPort(Rs2Exception::Unknown, e.to_string())
+    })?;
+    let devices = context.query_devices(HashSet::new());
+
+    let serial = devices
+        .iter()
+        .find(|device| device.info(Rs2CameraInfo::PhysicalPort) == Some(port_id))
+        .and_then(|device| device.info(Rs2CameraInfo::SerialNumber))
+        .ok_or_else(|| {
+            ConfigurationError::CouldNotEnableDeviceFromPort(
+                Rs2Exception::InvalidValue,
+                format!(
+                    "No device with a serial number is connected to port {:?}.",
+                    port_id
+                ),
+            )
+        })?;
+
+    let devices_with_serial = devices
+        .iter()
+        .filter(|device| device.info(Rs2CameraInfo::SerialNumber) == Some(serial))
+        .count();
+    if devices_with_serial > 1 {
+        return Err(ConfigurationError::CouldNotEnableDeviceFromPort(
+            Rs2Exception::InvalidValue,
+            format!(
+                "{} devices share the serial number {:?} of the device at port {:?}.",
+                devices_with_serial, serial, port_id
+            ),
+        ));
+    }
+
+    Ok(serial.to_owned())
+}
+
+#[cfg(all(test, not(feature = "docs-only")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clone_replays_enabled_streams() {
+        let mut config = Config::new();
+        config
+            .enable_stream(Rs2StreamKind::Depth, None, 640, 0, Rs2Format::Z16, 30)
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Infrared, Some(1), 0, 0, Rs2Format::Y8, 0)
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 848, 480, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let clone = config.clone();
+        assert_eq!(clone.streams(), config.streams());
+        assert_eq!(clone.streams().len(), 2);
+        assert_eq!(clone.streams()[1].width(), 848);
+        assert_eq!(clone.streams()[0].index(), Some(1));
+    }
+}
@@ -5,12 +5,17 @@ use crate::{
     check_rs2_error,
     device::Device,
     device_hub::DeviceHub,
-    kind::{Rs2Exception, Rs2ProductLine},
+    device_monitor::DeviceMonitorHandle,
+    kind::{Rs2CameraInfo, Rs2Exception, Rs2LogSeverity, Rs2ProductLine},
+    log_monitor::LogMonitor,
 };
 use anyhow::Result;
 use num_traits::ToPrimitive;
 use realsense_sys as sys;
-use std::{collections::HashSet, convert::From, path::Path, ptr::NonNull};
+use std::{
+    collections::HashSet, convert::From, ffi::CStr, path::Path, ptr::NonNull, sync::Arc,
+    time::Duration,
+};
 use thiserror::Error;
 
 /// Type describing a RealSense context, used by the rest of the API.
@@ -20,6 +25,14 @@ pub struct Context {
     context_ptr: NonNull<sys::rs2_context>,
 }
 
+/// A [`Context`] that can be shared between several owners.
+///
+/// Pipelines, device hubs and devices created from a shared context all refer to the same
+/// underlying librealsense2 context, so they see the same devices. Anything that accepts a
+/// `&Context` also accepts a `&SharedContext` through deref coercion, and
+/// [`InactivePipeline`](crate::pipeline::InactivePipeline) can be created directly from one.
+pub type SharedContext = Arc<Context>;
+
 /// An error type describing failure to construct a context.
 #[derive(Error, Debug)]
 #[error("Could not construct the context. Type: {0}; Reason: {1}")]
@@ -40,6 +53,11 @@ pub struct CouldNotAddDeviceError(pub Rs2Exception, pub String);
 #[error("Could not remove device from file. Type: {0}; Reason: {1}")]
 pub struct CouldNotRemoveDeviceError(pub Rs2Exception, pub String);
 
+/// An error type describing failure to configure the librealsense2 logger.
+#[derive(Error, Debug)]
+#[error("Could not configure the logger. Type: {0}; Reason: {1}")]
+pub struct CouldNotSetLoggerError(pub Rs2Exception, pub String);
+
 impl Drop for Context {
     fn drop(&mut self) {
         unsafe { sys::rs2_delete_context(self.context_ptr.as_ptr()) }
@@ -48,6 +66,10 @@ impl Drop for Context {
 
 unsafe impl Send for Context {}
 
+// The librealsense2 context guards its device list and device-changed callbacks internally, so
+// it can be used through `&Context` from several threads at once, e.g. via a `SharedContext`.
+unsafe impl Sync for Context {}
+
 impl Context {
     /// Construct a new context.
     ///
@@ -67,32 +89,126 @@ impl Context {
         }
     }
 
-    /// Creates a device hub from the context.
+    /// Convert the context into a [`SharedContext`].
+    ///
+    /// This allows the context to be stored in a long-lived [`Arc`] that is shared between
+    /// several pipelines, instead of borrowing it for the lifetime of each of them.
+    pub fn into_shared(self) -> SharedContext {
+        Arc::new(self)
+    }
+
+    /// Creates a device hub from `context`.
+    ///
+    /// The device hub keeps the context alive, so that it can enumerate the devices of the context
+    /// while [waiting with a timeout](DeviceHub::wait_for_device).
     ///
     /// # Errors
     ///
     /// Returns [`CouldNotGetDeviceHubError`] if the device hub cannot be created.
     ///
-    pub fn create_device_hub(&self) -> Result<DeviceHub, CouldNotGetDeviceHubError> {
+    pub fn create_device_hub(
+        context: &SharedContext,
+    ) -> Result<DeviceHub, CouldNotGetDeviceHubError> {
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
-            let devicehub_ptr = sys::rs2_create_device_hub(self.context_ptr.as_ptr(), &mut err);
+            let devicehub_ptr = sys::rs2_create_device_hub(context.context_ptr.as_ptr(), &mut err);
             check_rs2_error!(err, CouldNotGetDeviceHubError)?;
 
-            Ok(DeviceHub::from(NonNull::new(devicehub_ptr).unwrap()))
+            Ok(DeviceHub::new(
+                NonNull::new(devicehub_ptr).unwrap(),
+                Arc::clone(context),
+            ))
+        }
+    }
+
+    /// Start monitoring device connections and disconnections of `context` in a background
+    /// thread.
+    ///
+    /// The background thread enumerates the devices of `context` every `poll_interval` and
+    /// invokes the callbacks registered on the returned handle via
+    /// [`on_connect`](DeviceMonitorHandle::on_connect) and
+    /// [`on_disconnect`](DeviceMonitorHandle::on_disconnect). The handle keeps the context alive,
+    /// and the thread is stopped when the handle is dropped.
+    pub fn monitor_devices(
+        context: &SharedContext,
+        poll_interval: Duration,
+    ) -> DeviceMonitorHandle {
+        DeviceMonitorHandle::start(Arc::clone(context), poll_interval)
+    }
+
+    /// Log librealsense2 messages of at least `severity` to the console.
+    ///
+    /// Logging is global to the process, so this affects every context.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CouldNotSetLoggerError`] if console logging cannot be enabled.
+    pub fn set_log_severity(severity: Rs2LogSeverity) -> Result<(), CouldNotSetLoggerError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_log_to_console(severity as sys::rs2_log_severity, &mut err);
+            check_rs2_error!(err, CouldNotSetLoggerError)?;
+
+            Ok(())
+        }
+    }
+
+    /// Log librealsense2 messages of at least `severity` to `file`.
+    ///
+    /// Logging is global to the process, so this affects every context.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NulError`](std::ffi::NulError) if the path cannot be cleanly represented as a
+    /// [`CString`](std::ffi::CString).
+    ///
+    /// Returns [`CouldNotSetLoggerError`] if file logging cannot be enabled, e.g. if the file
+    /// cannot be opened.
+    ///
+    pub fn set_log_to_file<P>(severity: Rs2LogSeverity, file: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let path = from_path(file)?;
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_log_to_file(severity as sys::rs2_log_severity, path.as_ptr(), &mut err);
+            check_rs2_error!(err, CouldNotSetLoggerError)?;
+
+            Ok(())
         }
     }
 
+    /// Call `callback` with the severity and text of every librealsense2 message of at least
+    /// `severity`.
+    ///
+    /// The callback is run from librealsense2's own threads until the returned [`LogMonitor`] is
+    /// dropped. Logging is global to the process, so messages from every context are delivered.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CouldNotSetLoggerError`] if the callback cannot be registered.
+    pub fn set_log_callback<F>(
+        severity: Rs2LogSeverity,
+        callback: F,
+    ) -> Result<LogMonitor, CouldNotSetLoggerError>
+    where
+        F: Fn(Rs2LogSeverity, &str) + Send + Sync + 'static,
+    {
+        LogMonitor::start(severity, callback)
+    }
+
     /// Get a list of devices that are already connected to the host.
+    ///
+    /// The devices returned are filtered by `product_mask`. Each product line in the set is OR'd
+    /// into a single mask, so a device is returned if it belongs to _any_ of the requested
+    /// product lines. An empty set applies no filter at all and is equivalent to passing
+    /// [`Rs2ProductLine::Any`], i.e. every connected device is returned.
+    ///
+    /// Devices that fail to construct are skipped. If the device list itself cannot be queried,
+    /// an empty `Vec` is returned.
     pub fn query_devices(&self, product_mask: HashSet<Rs2ProductLine>) -> Vec<Device> {
-        // TODO/TEST: Make sure that an empty mask (therefore giving no filter) gives
-        // us _all_ devices, not _no_ devices.
-
-        let mask = if product_mask.is_empty() {
-            Rs2ProductLine::Any.to_i32().unwrap()
-        } else {
-            product_mask.iter().fold(0, |k, v| k | v.to_u32().unwrap()) as i32
-        };
+        let mask = product_mask_bits(&product_mask);
 
         let mut devices = Vec::new();
         unsafe {
@@ -183,6 +299,37 @@ impl Context {
         }
     }
 
+    /// Format every connected device, its sensors and their stream profiles for diagnostics.
+    ///
+    /// This dumps the complete enumeration tree, which is useful when diagnosing why a
+    /// [`Config`](crate::config::Config) cannot be resolved. Devices and sensors are listed by
+    /// name, and each stream profile is formatted as in [`Sensor::print_stream_profiles`].
+    ///
+    /// [`Sensor::print_stream_profiles`]: crate::sensor::Sensor::print_stream_profiles
+    pub fn list_devices_and_profiles(&self) -> String {
+        let name = |info: Option<&CStr>| {
+            info.map(|value| value.to_string_lossy().into_owned())
+                .unwrap_or_else(|| String::from("Unknown"))
+        };
+
+        let mut listing = String::new();
+        for device in self.query_devices(HashSet::new()) {
+            listing += &format!(
+                "Device: {} (serial {})\n",
+                name(device.info(Rs2CameraInfo::Name)),
+                name(device.info(Rs2CameraInfo::SerialNumber)),
+            );
+
+            for sensor in device.sensors() {
+                listing += &format!("  Sensor: {}\n", name(sensor.info(Rs2CameraInfo::Name)));
+                for line in sensor.print_stream_profiles().lines() {
+                    listing += &format!("    {}\n", line);
+                }
+            }
+        }
+        listing
+    }
+
     /// Get the underlying low-level pointer to the context object.
     ///
     /// # Safety
@@ -195,3 +342,40 @@ impl Context {
         self.context_ptr
     }
 }
+
+/// Folds a set of product lines into the bitmask expected by `rs2_query_devices_ex`.
+///
+/// An empty set is treated as "no filter" and maps to [`Rs2ProductLine::Any`].
+fn product_mask_bits(product_mask: &HashSet<Rs2ProductLine>) -> i32 {
+    if product_mask.is_empty() {
+        Rs2ProductLine::Any.to_i32().unwrap()
+    } else {
+        product_mask.iter().fold(0, |k, v| k | v.to_u32().unwrap()) as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_product_mask_selects_all_devices() {
+        assert_eq!(
+            product_mask_bits(&HashSet::new()),
+            sys::RS2_PRODUCT_LINE_ANY as i32
+        );
+    }
+
+    #[test]
+    fn product_mask_combines_product_lines() {
+        let mask: HashSet<_> = [Rs2ProductLine::D400, Rs2ProductLine::L500]
+            .iter()
+            .copied()
+            .collect();
+
+        assert_eq!(
+            product_mask_bits(&mask),
+            (sys::RS2_PRODUCT_LINE_D400 | sys::RS2_PRODUCT_LINE_L500) as i32
+        );
+    }
+}
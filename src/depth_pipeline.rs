@@ -0,0 +1,260 @@
+//! Preset chains of processing blocks for post-processing depth frames.
+//!
+//! The post-processing recommended for stereo depth cameras converts depth frames to disparity,
+//! smooths them with the spatial and temporal filters, converts them back to depth and finally
+//! fills the remaining holes. Setting this up by hand means creating five
+//! [processing blocks](crate::processing_block) and configuring each of them, so
+//! [`create_standard_depth_pipeline`] builds the whole chain from a [`DepthPipelinePreset`]:
+//!
+//! ```no_run
+//! use realsense_rust::{
+//!     context::Context,
+//!     depth_pipeline::{create_standard_depth_pipeline, DepthPipelinePreset},
+//!     frame::DepthFrame,
+//!     pipeline::InactivePipeline,
+//! };
+//! use std::convert::TryFrom;
+//!
+//! let context = Context::new()?;
+//! let mut pipeline = InactivePipeline::try_from(&context)?.start(None)?;
+//! let mut chain = create_standard_depth_pipeline(DepthPipelinePreset::MediumDensity)?;
+//!
+//! for depth_frame in pipeline.wait(None)?.frames_of_type::<DepthFrame>() {
+//!     let filtered = chain.process(depth_frame)?;
+//!     println!("{}x{}", filtered.width(), filtered.height());
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! See the [RealSense post-processing documentation](https://dev.intelrealsense.com/docs/post-processing-filters)
+//! for more information on the filters and their options.
+
+use crate::{
+    frame::{DepthFrame, DisparityFrame},
+    kind::{HoleFillingMode, PersistenceControl, Rs2Option},
+    processing_block::{DisparityTransform, HoleFillingFilter, SpatialFilter, TemporalFilter},
+};
+use anyhow::Result;
+
+/// The scenarios that [`create_standard_depth_pipeline`] has preset filter settings for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DepthPipelinePreset {
+    /// Smooth depth frames while keeping as many of the invalid pixels invalid as possible.
+    ///
+    /// Suited for measurements, where a missing depth is preferable to an inaccurate one.
+    HighAccuracy,
+    /// Fill as many invalid pixels as possible, at the cost of accuracy around edges and holes.
+    ///
+    /// Suited for e.g. obstacle avoidance, where a dense depth frame matters most.
+    HighDensity,
+    /// A balance between [`HighAccuracy`](Self::HighAccuracy) and
+    /// [`HighDensity`](Self::HighDensity), filling small holes only.
+    MediumDensity,
+    /// Configure the filters with the given settings.
+    Custom(DepthPipelineSettings),
+}
+
+/// The settings of the filters in a [`FilterChain`].
+///
+/// Each field is the value of the matching [`Rs2Option`] of the spatial, temporal or hole filling
+/// filter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DepthPipelineSettings {
+    /// The number of iterations of the spatial filter, see [`Rs2Option::FilterMagnitude`].
+    pub spatial_magnitude: f32,
+    /// The weight of the current pixel in the spatial filter, see
+    /// [`Rs2Option::FilterSmoothAlpha`].
+    pub spatial_smooth_alpha: f32,
+    /// The step size of an edge that the spatial filter preserves, see
+    /// [`Rs2Option::FilterSmoothDelta`].
+    pub spatial_smooth_delta: f32,
+    /// The size of the holes that the spatial filter fills, see [`Rs2Option::HolesFill`].
+    ///
+    /// Ranges from `0` for no hole filling to `5` for unlimited hole filling.
+    pub spatial_holes_fill: f32,
+    /// The weight of the current frame in the temporal filter, see
+    /// [`Rs2Option::FilterSmoothAlpha`].
+    pub temporal_smooth_alpha: f32,
+    /// The step size of an edge that the temporal filter preserves, see
+    /// [`Rs2Option::FilterSmoothDelta`].
+    pub temporal_smooth_delta: f32,
+    /// How the temporal filter fills pixels that are invalid in the current frame from previous
+    /// frames.
+    pub temporal_persistence: PersistenceControl,
+    /// How the hole filling filter fills the remaining invalid pixels, or `None` to leave them.
+    pub hole_filling: Option<HoleFillingMode>,
+}
+
+impl DepthPipelinePreset {
+    /// Get the filter settings of the preset.
+    pub fn settings(&self) -> DepthPipelineSettings {
+        match self {
+            Self::HighAccuracy => DepthPipelineSettings {
+                spatial_magnitude: 2.0,
+                spatial_smooth_alpha: 0.5,
+                spatial_smooth_delta: 20.0,
+                spatial_holes_fill: 0.0,
+                temporal_smooth_alpha: 0.4,
+                temporal_smooth_delta: 20.0,
+                temporal_persistence: PersistenceControl::Disabled,
+                hole_filling: None,
+            },
+            Self::HighDensity => DepthPipelineSettings {
+                spatial_magnitude: 3.0,
+                spatial_smooth_alpha: 0.5,
+                spatial_smooth_delta: 20.0,
+                spatial_holes_fill: 5.0,
+                temporal_smooth_alpha: 0.4,
+                temporal_smooth_delta: 20.0,
+                temporal_persistence: PersistenceControl::Indefinitely,
+                hole_filling: Some(HoleFillingMode::NearestFromAround),
+            },
+            Self::MediumDensity => DepthPipelineSettings {
+                spatial_magnitude: 2.0,
+                spatial_smooth_alpha: 0.5,
+                spatial_smooth_delta: 20.0,
+                spatial_holes_fill: 2.0,
+                temporal_smooth_alpha: 0.4,
+                temporal_smooth_delta: 20.0,
+                temporal_persistence: PersistenceControl::Valid2OutOf4,
+                hole_filling: Some(HoleFillingMode::FarestFromAround),
+            },
+            Self::Custom(settings) => *settings,
+        }
+    }
+}
+
+/// A chain of processing blocks that post-processes depth frames.
+///
+/// Created with [`create_standard_depth_pipeline`]. The individual filters can be reconfigured
+/// after the chain has been created through their accessors.
+#[derive(Debug)]
+pub struct FilterChain {
+    /// Converts depth frames to disparity frames.
+    to_disparity: DisparityTransform,
+    /// Smooths disparity frames spatially.
+    spatial: SpatialFilter,
+    /// Smooths disparity frames over time.
+    temporal: TemporalFilter,
+    /// Converts disparity frames back to depth frames.
+    to_depth: DisparityTransform,
+    /// Fills the remaining invalid pixels of depth frames, if enabled.
+    hole_filling: Option<HoleFillingFilter>,
+}
+
+impl FilterChain {
+    /// Pass `frame` through every filter of the chain and return the filtered depth frame.
+    ///
+    /// The temporal filter depends on the frames previously passed through the chain, so frames
+    /// should be passed in the order they were captured.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ProcessingBlockError`](crate::processing_block::ProcessingBlockError) if any of
+    /// the filters fails to process the frame, or an error if an intermediate frame cannot be
+    /// constructed.
+    pub fn process(&mut self, frame: DepthFrame) -> Result<DepthFrame> {
+        let disparity: DisparityFrame = self.to_disparity.process_into(frame)?;
+        let disparity = self.spatial.process(disparity)?;
+        let disparity = self.temporal.process(disparity)?;
+        let depth: DepthFrame = self.to_depth.process_into(disparity)?;
+
+        match &mut self.hole_filling {
+            Some(hole_filling) => hole_filling.process(depth),
+            None => Ok(depth),
+        }
+    }
+
+    /// Get the spatial filter of the chain.
+    pub fn spatial_filter_mut(&mut self) -> &mut SpatialFilter {
+        &mut self.spatial
+    }
+
+    /// Get the temporal filter of the chain.
+    pub fn temporal_filter_mut(&mut self) -> &mut TemporalFilter {
+        &mut self.temporal
+    }
+
+    /// Get the hole filling filter of the chain, if hole filling is enabled.
+    pub fn hole_filling_filter_mut(&mut self) -> Option<&mut HoleFillingFilter> {
+        self.hole_filling.as_mut()
+    }
+}
+
+/// Create a chain of filters that post-processes depth frames with the settings of `preset`.
+///
+/// The chain converts depth frames to disparity, applies the spatial and temporal filters,
+/// converts the frames back to depth and fills the remaining holes, in the order recommended for
+/// stereo depth cameras.
+///
+/// # Errors
+///
+/// Returns a [`ProcessingBlockError`](crate::processing_block::ProcessingBlockError) if any of
+/// the filters cannot be created.
+///
+/// Returns an [`OptionSetError`](crate::kind::OptionSetError) if a setting of the preset cannot
+/// be applied, e.g. because a custom setting is outside of the range of its option.
+pub fn create_standard_depth_pipeline(preset: DepthPipelinePreset) -> Result<FilterChain> {
+    let settings = preset.settings();
+
+    let mut spatial = SpatialFilter::new()?;
+    spatial.set_option(Rs2Option::FilterMagnitude, settings.spatial_magnitude)?;
+    spatial.set_option(Rs2Option::FilterSmoothAlpha, settings.spatial_smooth_alpha)?;
+    spatial.set_option(Rs2Option::FilterSmoothDelta, settings.spatial_smooth_delta)?;
+    spatial.set_option(Rs2Option::HolesFill, settings.spatial_holes_fill)?;
+
+    let mut temporal = TemporalFilter::new()?;
+    temporal.set_option(Rs2Option::FilterSmoothAlpha, settings.temporal_smooth_alpha)?;
+    temporal.set_option(Rs2Option::FilterSmoothDelta, settings.temporal_smooth_delta)?;
+    // The temporal filter exposes its persistence control as its hole filling option.
+    temporal.set_option(
+        Rs2Option::HolesFill,
+        settings.temporal_persistence as usize as f32,
+    )?;
+
+    let hole_filling = match settings.hole_filling {
+        Some(mode) => {
+            let mut hole_filling = HoleFillingFilter::new()?;
+            hole_filling.set_option(Rs2Option::HolesFill, mode as usize as f32)?;
+            Some(hole_filling)
+        }
+        None => None,
+    };
+
+    Ok(FilterChain {
+        to_disparity: DisparityTransform::new(true)?,
+        spatial,
+        temporal,
+        to_depth: DisparityTransform::new(false)?,
+        hole_filling,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_preset_uses_its_own_settings() {
+        let settings = DepthPipelinePreset::HighAccuracy.settings();
+        let custom = DepthPipelineSettings {
+            hole_filling: Some(HoleFillingMode::FillFromLeft),
+            ..settings
+        };
+
+        assert_eq!(DepthPipelinePreset::Custom(custom).settings(), custom);
+    }
+
+    #[test]
+    fn presets_fill_more_holes_as_density_increases() {
+        let accuracy = DepthPipelinePreset::HighAccuracy.settings();
+        let medium = DepthPipelinePreset::MediumDensity.settings();
+        let density = DepthPipelinePreset::HighDensity.settings();
+
+        assert!(accuracy.spatial_holes_fill < medium.spatial_holes_fill);
+        assert!(medium.spatial_holes_fill < density.spatial_holes_fill);
+        assert_eq!(accuracy.hole_filling, None);
+        assert!(medium.hole_filling.is_some());
+        assert!(density.hole_filling.is_some());
+    }
+}
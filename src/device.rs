@@ -7,14 +7,17 @@
 
 use crate::{
     check_rs2_error,
-    kind::{Rs2CameraInfo, Rs2Exception},
+    kind::{Rs2CameraInfo, Rs2Exception, Rs2Extension, Rs2ProductLine},
     sensor::Sensor,
+    sensor_list::SensorList,
 };
 use anyhow::Result;
 use realsense_sys as sys;
 use std::{
-    convert::{From, TryInto},
+    convert::{From, TryFrom, TryInto},
     ffi::CStr,
+    os::raw::c_void,
+    panic::{self, AssertUnwindSafe},
     ptr::NonNull,
 };
 use thiserror::Error;
@@ -28,6 +31,26 @@ pub enum DeviceConstructionError {
     /// Could not get device from device list
     #[error("Could not get device from device list. Type: {0}; Reason: {1}")]
     CouldNotGetDeviceFromDeviceList(Rs2Exception, String),
+    /// Could not get the device from an active pipeline
+    #[error("Could not get device from active pipeline. Type: {0}; Reason: {1}")]
+    CouldNotGetDeviceFromPipeline(Rs2Exception, String),
+}
+
+/// Enumeration of possible errors that can occur while updating the firmware of a device.
+#[derive(Error, Debug)]
+pub enum FirmwareUpdateError {
+    /// The device does not support the [`Rs2Extension::UpdateDevice`] extension.
+    #[error("Device does not support firmware updates.")]
+    DeviceDoesNotSupportFirmwareUpdate,
+    /// The firmware image passed to the update is empty.
+    #[error("Firmware data is empty.")]
+    FirmwareDataIsEmpty,
+    /// The firmware image passed to the update is larger than librealsense2 can accept.
+    #[error("Firmware data is {0} bytes, which is too large to update the firmware with.")]
+    FirmwareDataIsTooLarge(usize),
+    /// librealsense2 failed to update the firmware.
+    #[error("Could not update firmware. Type: {0}; Reason: {1}")]
+    CouldNotStartUpdate(Rs2Exception, String),
 }
 
 /// A type representing a RealSense device.
@@ -54,6 +77,11 @@ impl Drop for Device {
 
 unsafe impl Send for Device {}
 
+// Every `&self` method on a device only queries it (e.g. camera info or sensor lists), and
+// librealsense2 guards those queries internally. See the thread safety policy in
+// `crate::docs::architecture`.
+unsafe impl Sync for Device {}
+
 impl From<NonNull<sys::rs2_device>> for Device {
     /// Attempt to construct a Device from a non-null pointer to `rs2_device`.
     ///
@@ -96,40 +124,163 @@ impl Device {
     /// This can occur if the physical device is disconnected before this call is made.
     ///
     pub fn sensors(&self) -> Vec<Sensor> {
-        unsafe {
-            let mut sensors = Vec::new();
+        self.iter_sensors().collect()
+    }
+
+    /// Gets the sensors of the device that support the given extension.
+    ///
+    /// See [`Sensor::is_extendable_to`]. Sensors are created and checked one at a time as the
+    /// iterator advances, so sensors that do not match are never collected. As with
+    /// [`Device::sensors`], the iterator is empty if the sensor list cannot be read.
+    pub fn sensors_of_extension(&self, extension: Rs2Extension) -> impl Iterator<Item = Sensor> {
+        self.iter_sensors()
+            .filter(move |sensor| sensor.is_extendable_to(extension))
+    }
+
+    /// Gets the depth sensors of the device.
+    ///
+    /// See [`Device::sensors_of_extension`].
+    pub fn depth_sensors(&self) -> impl Iterator<Item = Sensor> {
+        self.sensors_of_extension(Rs2Extension::DepthSensor)
+    }
+
+    /// Gets the color sensors of the device.
+    ///
+    /// See [`Device::sensors_of_extension`].
+    pub fn color_sensors(&self) -> impl Iterator<Item = Sensor> {
+        self.sensors_of_extension(Rs2Extension::ColorSensor)
+    }
+
+    /// Gets the first depth sensor of the device, if any.
+    pub fn first_depth_sensor(&self) -> Option<Sensor> {
+        self.depth_sensors().next()
+    }
+
+    /// Gets the first color sensor of the device, if any.
+    pub fn first_color_sensor(&self) -> Option<Sensor> {
+        self.color_sensors().next()
+    }
+
+    /// Gets the first motion (IMU) sensor of the device, if any.
+    pub fn first_motion_sensor(&self) -> Option<Sensor> {
+        self.sensors_of_extension(Rs2Extension::MotionSensor).next()
+    }
 
+    /// Iterates over the sensors of the device, creating each one as the iterator advances.
+    ///
+    /// The iterator is empty if the sensor list cannot be read, and skips sensors that cannot be
+    /// created.
+    fn iter_sensors(&self) -> impl Iterator<Item = Sensor> {
+        let list = unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
             let sensor_list_ptr = sys::rs2_query_sensors(self.device_ptr.as_ptr(), &mut err);
 
             if err.as_ref().is_some() {
                 sys::rs2_free_error(err);
-                return sensors;
+                None
+            } else {
+                NonNull::new(sensor_list_ptr).map(SensorList::from)
             }
+        };
 
-            let nonnull_sensor_list = NonNull::new(sensor_list_ptr).unwrap();
+        list.into_iter().flat_map(SensorList::into_sensors)
+    }
 
-            let len = sys::rs2_get_sensors_count(nonnull_sensor_list.as_ptr(), &mut err);
+    /// Predicate for determining if the device supports a given extension.
+    ///
+    /// Returns false if an error occurs while checking the extension.
+    pub fn is_extendable_to(&self, extension: Rs2Extension) -> bool {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let is_extendable = sys::rs2_is_device_extendable_to(
+                self.device_ptr.as_ptr(),
+                #[allow(clippy::useless_conversion)]
+                (extension as i32).try_into().unwrap(),
+                &mut err,
+            );
 
-            if err.as_ref().is_some() {
+            if err.as_ref().is_none() {
+                is_extendable != 0
+            } else {
                 sys::rs2_free_error(err);
-                sys::rs2_delete_sensor_list(nonnull_sensor_list.as_ptr());
-                return sensors;
+                false
             }
+        }
+    }
 
-            sensors.reserve(len as usize);
-            for i in 0..len {
-                match Sensor::try_create(&nonnull_sensor_list, i) {
-                    Ok(s) => {
-                        sensors.push(s);
-                    }
-                    Err(_) => {
-                        continue;
-                    }
-                }
+    /// Update the firmware of the device with the firmware image in `data`.
+    ///
+    /// This blocks until the update has finished. `callback` is called with the progress of the
+    /// update, from 0.0 to 1.0, on the thread performing the update. The callback must not panic.
+    /// If it does, the process is aborted, since unwinding across the FFI boundary is undefined
+    /// behaviour.
+    ///
+    /// Only devices in update mode support firmware updates, see [`Rs2Extension::UpdateDevice`].
+    /// The device resets once the update is complete, so it must be queried again from the
+    /// [context](crate::context::Context) afterwards. Ownership of the device is taken for the
+    /// same reason as in [`Device::hardware_reset`].
+    ///
+    /// # Errors
+    ///
+    /// Every error is returned together with the device, so that it can still be used if the
+    /// update did not happen.
+    ///
+    /// Returns [`FirmwareUpdateError::DeviceDoesNotSupportFirmwareUpdate`] if the device does not
+    /// support the [`Rs2Extension::UpdateDevice`] extension.
+    ///
+    /// Returns [`FirmwareUpdateError::FirmwareDataIsEmpty`] if `data` is empty.
+    ///
+    /// Returns [`FirmwareUpdateError::FirmwareDataIsTooLarge`] if the length of `data` does not
+    /// fit in a C `int`.
+    ///
+    /// Returns [`FirmwareUpdateError::CouldNotStartUpdate`] if librealsense2 fails to update the
+    /// firmware, e.g. because the image is not valid for the device.
+    pub fn update_firmware<F>(
+        self,
+        data: &[u8],
+        callback: F,
+    ) -> Result<(), (Self, FirmwareUpdateError)>
+    where
+        F: Fn(f32) + Send,
+    {
+        if !self.is_extendable_to(Rs2Extension::UpdateDevice) {
+            return Err((
+                self,
+                FirmwareUpdateError::DeviceDoesNotSupportFirmwareUpdate,
+            ));
+        }
+
+        if data.is_empty() {
+            return Err((self, FirmwareUpdateError::FirmwareDataIsEmpty));
+        }
+
+        let len = match i32::try_from(data.len()) {
+            Ok(len) => len,
+            Err(_) => {
+                return Err((
+                    self,
+                    FirmwareUpdateError::FirmwareDataIsTooLarge(data.len()),
+                ))
             }
-            sys::rs2_delete_sensor_list(nonnull_sensor_list.as_ptr());
-            sensors
+        };
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+
+            // The update is synchronous, so the callback only needs to outlive this call.
+            sys::rs2_update_firmware(
+                self.device_ptr.as_ptr(),
+                data.as_ptr().cast::<c_void>(),
+                len,
+                Some(progress_trampoline::<F>),
+                (&callback as *const F as *mut F).cast::<c_void>(),
+                &mut err,
+            );
+            if let Err(e) = check_rs2_error!(err, FirmwareUpdateError::CouldNotStartUpdate) {
+                return Err((self, e));
+            }
+
+            Ok(())
         }
     }
 
@@ -205,6 +356,29 @@ impl Device {
         }
     }
 
+    /// Get the product line of the device.
+    ///
+    /// Returns `None` if the device does not report its product line, or if it reports a product
+    /// line that is not known to this crate.
+    pub fn product_line(&self) -> Option<Rs2ProductLine> {
+        product_line_from_info(self.info(Rs2CameraInfo::ProductLine)?.to_str().ok()?)
+    }
+
+    /// Predicate for whether the device is a T200 series tracking camera, e.g. a T265.
+    pub fn is_t265(&self) -> bool {
+        self.product_line() == Some(Rs2ProductLine::T200)
+    }
+
+    /// Predicate for whether the device is a D400 series depth camera, e.g. a D435 or D455.
+    pub fn is_d4xx(&self) -> bool {
+        self.product_line() == Some(Rs2ProductLine::D400)
+    }
+
+    /// Predicate for whether the device is an L500 series LiDAR camera, e.g. an L515.
+    pub fn is_l5xx(&self) -> bool {
+        self.product_line() == Some(Rs2ProductLine::L500)
+    }
+
     /// Get the underlying low-level pointer to the context object
     ///
     /// # Safety
@@ -216,3 +390,46 @@ impl Device {
         self.device_ptr
     }
 }
+
+/// Progress callback passed to `rs2_update_firmware`.
+///
+/// `data` is a pointer to the user callback of type `F`.
+unsafe extern "C" fn progress_trampoline<F>(progress: f32, data: *mut c_void)
+where
+    F: Fn(f32) + Send,
+{
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let callback = &*data.cast::<F>();
+        callback(progress);
+    }));
+
+    if result.is_err() {
+        eprintln!("Firmware update progress callback panicked");
+        std::process::abort();
+    }
+}
+
+/// Parses the value of [`Rs2CameraInfo::ProductLine`] into a product line.
+fn product_line_from_info(info: &str) -> Option<Rs2ProductLine> {
+    match info {
+        "D400" => Some(Rs2ProductLine::D400),
+        "SR300" => Some(Rs2ProductLine::Sr300),
+        "L500" => Some(Rs2ProductLine::L500),
+        "T200" => Some(Rs2ProductLine::T200),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn product_line_info_is_parsed() {
+        assert_eq!(product_line_from_info("D400"), Some(Rs2ProductLine::D400));
+        assert_eq!(product_line_from_info("SR300"), Some(Rs2ProductLine::Sr300));
+        assert_eq!(product_line_from_info("L500"), Some(Rs2ProductLine::L500));
+        assert_eq!(product_line_from_info("T200"), Some(Rs2ProductLine::T200));
+        assert_eq!(product_line_from_info("D500"), None);
+    }
+}
@@ -1,26 +1,44 @@
 //! Type representing the concept of a "hub" that devices can connect to.
 
-use crate::{check_rs2_error, device::Device, kind::Rs2Exception};
-use anyhow::Result;
+use crate::{check_rs2_error, context::SharedContext, device::Device, kind::Rs2Exception};
 use realsense_sys as sys;
-use std::{convert::From, ptr::NonNull};
+use std::{
+    collections::HashSet,
+    ptr::NonNull,
+    thread,
+    time::{Duration, Instant},
+};
 use thiserror::Error;
 
-/// Error describing when the device hub failed while attempting to wait for devices.
+/// The interval at which connected devices are polled while waiting with a timeout.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Type describing errors that can occur while waiting for devices on the device hub.
 ///
-/// Can occur if there is an internal system exception while waiting for devices.
+/// Follows the standard pattern of errors where the enum variant describes what the low-level code
+/// was attempting to do while the string carried alongside describes the underlying error message
+/// from any C++ exceptions that occur.
 #[derive(Error, Debug)]
-#[error("Could not wait for device due to internal error. Type: {0}; Reason: {1}")]
-pub struct CouldNotWaitForDeviceError(pub Rs2Exception, pub String);
+pub enum DeviceHubError {
+    /// An internal exception occurred while waiting for devices.
+    #[error("Could not wait for device due to internal error. Type: {0}; Reason: {1}")]
+    CouldNotWaitForDevice(Rs2Exception, String),
+    /// No device was connected before the timeout elapsed.
+    #[error("No device was connected within {0:?}.")]
+    TimedOut(Duration),
+}
 
 /// A type representing a hub for devices to connect to.
 ///
 /// The device hub is a type used for waiting on a device connection or to check if a device is
-/// still connected.
+/// still connected. It keeps the [`SharedContext`] it was created from alive, and uses it to
+/// enumerate devices while waiting with a timeout.
 #[derive(Debug)]
 pub struct DeviceHub {
     /// A non-null pointer to the underlying librealsense device hub.
     devicehub_ptr: NonNull<sys::rs2_device_hub>,
+    /// The context the device hub was created from.
+    context: SharedContext,
 }
 
 impl Drop for DeviceHub {
@@ -33,40 +51,81 @@ impl Drop for DeviceHub {
 
 unsafe impl Send for DeviceHub {}
 
-impl From<NonNull<sys::rs2_device_hub>> for DeviceHub {
-    fn from(devicehub_ptr: NonNull<sys::rs2_device_hub>) -> Self {
-        Self { devicehub_ptr }
+impl DeviceHub {
+    /// Constructs a device hub from a hub created by librealsense2 from `context`.
+    ///
+    /// This is only to be used / called from [`Context::create_device_hub`].
+    pub(crate) fn new(devicehub_ptr: NonNull<sys::rs2_device_hub>, context: SharedContext) -> Self {
+        Self {
+            devicehub_ptr,
+            context,
+        }
     }
-}
 
-impl DeviceHub {
     /// Gets a connected device, or waits for any device to be connected.
     ///
     /// If any device is connected, this method will return that device. It will cycle through
     /// devices if multiple are connected. Otherwise, it blocks the calling thread until a device
     /// is connected.
     ///
+    /// # Arguments
+    ///
+    /// * `timeout` - The maximum time to wait for a device. If `None` is passed in, this blocks
+    /// until a device is connected.
+    ///
+    /// librealsense2 can only wait for devices indefinitely, so with a timeout the devices of the
+    /// context the hub was created from are polled until one of them is connected to the hub, or
+    /// the timeout elapses. The first such device is returned.
+    ///
     /// # Errors
     ///
-    /// Returns [`CouldNotWaitForDeviceError`] if an internal exception occurs while trying to wait
-    /// for device connections.
+    /// Returns [`DeviceHubError::CouldNotWaitForDevice`] if an internal exception occurs while
+    /// trying to wait for device connections.
     ///
-    /// Returns [`DeviceConstructionError`](crate::device::DeviceConstructionError) if a device is
-    /// found but an exception occurs during type construction.
+    /// Returns [`DeviceHubError::TimedOut`] if no device was connected within `timeout`.
     ///
-    pub fn wait_for_device(&self) -> Result<Device> {
+    pub fn wait_for_device(&self, timeout: Option<Duration>) -> Result<Device, DeviceHubError> {
+        if let Some(timeout) = timeout {
+            let deadline = Instant::now() + timeout;
+
+            loop {
+                let device = self
+                    .context
+                    .query_devices(HashSet::new())
+                    .into_iter()
+                    .find(|device| self.is_connected(device));
+                if let Some(device) = device {
+                    return Ok(device);
+                }
+
+                let now = Instant::now();
+                if now >= deadline {
+                    return Err(DeviceHubError::TimedOut(timeout));
+                }
+                thread::sleep(POLL_INTERVAL.min(deadline - now));
+            }
+        }
+
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
             let device_ptr =
                 sys::rs2_device_hub_wait_for_device(self.devicehub_ptr.as_ptr(), &mut err);
-            check_rs2_error!(err, CouldNotWaitForDeviceError)?;
+            check_rs2_error!(err, DeviceHubError::CouldNotWaitForDevice)?;
 
             Ok(Device::from(NonNull::new(device_ptr).unwrap()))
         }
     }
 
     /// Predicate to check whether a given device is connected.
+    #[deprecated(note = "renamed to `DeviceHub::is_connected`, use that instead")]
     pub fn is_device_connected(&self, device: &Device) -> bool {
+        self.is_connected(device)
+    }
+
+    /// Predicate to check whether a given device is connected.
+    ///
+    /// Returns false if an error occurs while checking the device.
+    pub fn is_connected(&self, device: &Device) -> bool {
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
             let val = sys::rs2_device_hub_is_device_connected(
@@ -0,0 +1,158 @@
+//! Type for monitoring device connections and disconnections in the background.
+//!
+//! A [`DeviceMonitorHandle`] is started via
+//! [`Context::monitor_devices`](crate::context::Context::monitor_devices). It owns a background
+//! thread which periodically enumerates the devices of a [`SharedContext`] and compares them (by
+//! serial number) against the previous enumeration. Callbacks registered on the handle are invoked
+//! from the background thread whenever a device is connected or disconnected.
+//!
+//! The background thread is stopped and joined when the handle is dropped.
+
+use crate::{context::SharedContext, device::Device, kind::Rs2CameraInfo};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        mpsc::{self, RecvTimeoutError, Sender},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+/// Callback invoked with a newly connected device.
+///
+/// Callbacks are reference counted so that they can be invoked without holding the lock they are
+/// stored behind, which allows a callback to replace the callbacks of its own monitor.
+type ConnectCallback = Arc<dyn Fn(Device) + Send + Sync + 'static>;
+
+/// Callback invoked with the serial number of a disconnected device.
+type DisconnectCallback = Arc<dyn Fn(String) + Send + Sync + 'static>;
+
+/// Handle to a background thread that watches for device connections and disconnections.
+///
+/// Dropping the handle stops the background thread.
+pub struct DeviceMonitorHandle {
+    /// Callback to run when a device is connected.
+    on_connect: Arc<Mutex<Option<ConnectCallback>>>,
+    /// Callback to run when a device is disconnected.
+    on_disconnect: Arc<Mutex<Option<DisconnectCallback>>>,
+    /// Channel used to tell the background thread to stop.
+    ///
+    /// The background thread waits on the receiving end between polls, and stops as soon as this
+    /// sender is dropped.
+    stop: Option<Sender<()>>,
+    /// Join handle for the background thread.
+    thread: Option<JoinHandle<()>>,
+}
+
+impl std::fmt::Debug for DeviceMonitorHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeviceMonitorHandle")
+            .field("is_running", &self.stop.is_some())
+            .finish()
+    }
+}
+
+impl Drop for DeviceMonitorHandle {
+    fn drop(&mut self) {
+        // Disconnecting the channel wakes the background thread, even in the middle of a wait.
+        self.stop.take();
+
+        if let Some(thread) = self.thread.take() {
+            // A panic in a user callback is already reported on the monitor thread, so there is
+            // nothing more to do with it here.
+            let _ = thread.join();
+        }
+    }
+}
+
+impl DeviceMonitorHandle {
+    /// Start monitoring the devices of `context` every `poll_interval` in a background thread.
+    ///
+    /// Devices that are already connected when the monitor starts do not trigger the connect
+    /// callback.
+    pub(crate) fn start(context: SharedContext, poll_interval: Duration) -> Self {
+        let mut known = serial_numbers(&context.query_devices(HashSet::new()));
+
+        let on_connect = Arc::new(Mutex::new(None::<ConnectCallback>));
+        let on_disconnect = Arc::new(Mutex::new(None::<DisconnectCallback>));
+        let (stop, should_stop) = mpsc::channel::<()>();
+
+        let thread = {
+            let on_connect = Arc::clone(&on_connect);
+            let on_disconnect = Arc::clone(&on_disconnect);
+
+            thread::spawn(move || {
+                while let Err(RecvTimeoutError::Timeout) = should_stop.recv_timeout(poll_interval) {
+                    let mut devices: HashMap<String, Device> = context
+                        .query_devices(HashSet::new())
+                        .into_iter()
+                        .filter_map(|d| serial_number(&d).map(|s| (s, d)))
+                        .collect();
+                    let current: HashSet<String> = devices.keys().cloned().collect();
+
+                    let callback = on_disconnect.lock().unwrap().clone();
+                    if let Some(callback) = callback {
+                        for serial in known.difference(&current) {
+                            callback(serial.clone());
+                        }
+                    }
+
+                    let callback = on_connect.lock().unwrap().clone();
+                    if let Some(callback) = callback {
+                        for serial in current.difference(&known) {
+                            if let Some(device) = devices.remove(serial) {
+                                callback(device);
+                            }
+                        }
+                    }
+
+                    known = current;
+                }
+            })
+        };
+
+        Self {
+            on_connect,
+            on_disconnect,
+            stop: Some(stop),
+            thread: Some(thread),
+        }
+    }
+
+    /// Set the callback that is run whenever a device is connected.
+    ///
+    /// The callback is run on the monitor's background thread and replaces any previously set
+    /// connect callback. It may set the callbacks of this monitor, in which case the new callback
+    /// takes effect from the next poll on.
+    pub fn on_connect<F>(&self, callback: F)
+    where
+        F: Fn(Device) + Send + Sync + 'static,
+    {
+        *self.on_connect.lock().unwrap() = Some(Arc::new(callback));
+    }
+
+    /// Set the callback that is run whenever a device is disconnected.
+    ///
+    /// The callback receives the serial number of the disconnected device. It is run on the
+    /// monitor's background thread and replaces any previously set disconnect callback. As with
+    /// [`DeviceMonitorHandle::on_connect`], it may set the callbacks of this monitor.
+    pub fn on_disconnect<F>(&self, callback: F)
+    where
+        F: Fn(String) + Send + Sync + 'static,
+    {
+        *self.on_disconnect.lock().unwrap() = Some(Arc::new(callback));
+    }
+}
+
+/// Gets the serial number of a device as an owned string, if it has one.
+fn serial_number(device: &Device) -> Option<String> {
+    device
+        .info(Rs2CameraInfo::SerialNumber)
+        .map(|s| s.to_string_lossy().into_owned())
+}
+
+/// Collects the serial numbers of a list of devices.
+fn serial_numbers(devices: &[Device]) -> HashSet<String> {
+    devices.iter().filter_map(serial_number).collect()
+}
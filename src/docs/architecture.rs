@@ -144,3 +144,19 @@
 //! underlying API. The internal `String` is the exception message from librealsense2. If you find yourself hitting the same
 //! message often, this is a bug, and we would love if you [submitted a bug
 //! report](https://gitlab.com/tangram-vision-oss/realsense-rust/-/issues).
+//!
+//! ### Only share types across threads when librealsense2 allows it
+//!
+//! Every type that owns a librealsense2 pointer implements `Send`, since librealsense2 does not tie any of its objects to
+//! the thread that created them. `Sync` is a stronger promise: it means that the `&self` methods of a type can be called
+//! from several threads at once. We only implement `Sync` where every `&self` method is a read that librealsense2 guards
+//! internally, and anything that changes state takes `&mut self`. Currently this holds for:
+//!
+//! - [`Context`](crate::context::Context), whose device list and device-changed callbacks are guarded by librealsense2.
+//! - [`Device`](crate::device::Device), whose `&self` methods only query camera info and list sensors.
+//! - [`Sensor`](crate::sensor::Sensor), where reading options, camera info and stream profiles is guarded, and setting
+//!   options requires `&mut self`.
+//!
+//! [`Config`](crate::config::Config) is not `Sync`, because librealsense2 caches the resolved profile inside the config
+//! when resolving it, even through a `const` pointer. Pipelines and frames are not `Sync` either; share the data you need
+//! out of them instead, or wrap them in a `Mutex`.
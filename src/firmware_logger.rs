@@ -0,0 +1,300 @@
+//! Type for reading the log of the firmware running on a device.
+//!
+//! Devices that support the [`Rs2Extension::FirmwareLogger`] extension keep a log of what their
+//! firmware is doing, e.g. USB transfers or sensor timing, which is not otherwise exposed through
+//! librealsense2. The log is read by polling a [`FirmwareLogSession`]:
+//!
+//! ```no_run
+//! use realsense_rust::{
+//!     context::Context, firmware_logger::FirmwareLogger, kind::Rs2LogSeverity,
+//! };
+//! use std::{collections::HashSet, convert::TryFrom};
+//!
+//! let context = Context::new()?;
+//! let device = context.query_devices(HashSet::new()).remove(0);
+//!
+//! let mut logger = FirmwareLogger::try_from(device)?;
+//! let mut session = logger.start_collecting(Rs2LogSeverity::Info)?;
+//! while let Some(message) = session.get_message() {
+//!     println!("{}", message);
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! Firmware log messages are binary. Unless a parser is initialized with
+//! [`FirmwareLogger::init_parser`], messages are formatted as the hex dump of their data.
+
+use crate::{
+    check_rs2_error,
+    device::Device,
+    kind::{Rs2Exception, Rs2Extension, Rs2LogSeverity},
+};
+use num_traits::FromPrimitive;
+use realsense_sys as sys;
+use std::{convert::TryFrom, ffi::CStr, ptr::NonNull};
+use thiserror::Error;
+
+/// Type describing errors that can occur when reading the firmware log of a device.
+///
+/// Follows the standard pattern of errors where the enum variant describes what the low-level code
+/// was attempting to do while the string carried alongside describes the underlying error message
+/// from any C++ exceptions that occur.
+#[derive(Error, Debug)]
+pub enum FirmwareLogError {
+    /// The device does not support the [`Rs2Extension::FirmwareLogger`] extension.
+    #[error("Device does not support the firmware logger.")]
+    FirmwareLoggerNotSupported,
+    /// Could not initialize the firmware log parser from its XML definition.
+    #[error("Could not initialize firmware log parser. Type: {0}; Reason: {1}")]
+    CouldNotInitParser(Rs2Exception, String),
+    /// Could not allocate the buffers that firmware log messages are read into.
+    #[error("Could not create firmware log message. Type: {0}; Reason: {1}")]
+    CouldNotCreateMessage(Rs2Exception, String),
+    /// Could not read a message from the firmware log.
+    #[error("Could not read firmware log message. Type: {0}; Reason: {1}")]
+    CouldNotReadMessage(Rs2Exception, String),
+}
+
+/// A device that supports reading the log of its firmware.
+#[derive(Debug)]
+pub struct FirmwareLogger {
+    /// The underlying device.
+    device: Device,
+    /// Whether a parser was initialized for the log messages of the device.
+    has_parser: bool,
+}
+
+impl TryFrom<Device> for FirmwareLogger {
+    type Error = FirmwareLogError;
+
+    /// Wrap `device` for reading its firmware log.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FirmwareLogError::FirmwareLoggerNotSupported`] if the device does not support
+    /// the [`Rs2Extension::FirmwareLogger`] extension.
+    fn try_from(device: Device) -> Result<Self, Self::Error> {
+        if device.is_extendable_to(Rs2Extension::FirmwareLogger) {
+            Ok(Self {
+                device,
+                has_parser: false,
+            })
+        } else {
+            Err(FirmwareLogError::FirmwareLoggerNotSupported)
+        }
+    }
+}
+
+impl FirmwareLogger {
+    /// Get the underlying device.
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+
+    /// Consume the firmware logger and return the underlying device.
+    pub fn into_device(self) -> Device {
+        self.device
+    }
+
+    /// Initialize the parser for the log messages of the device from its XML definition.
+    ///
+    /// The definition matches the firmware version of the device, and is distributed alongside
+    /// the firmware. Once initialized, messages are formatted with their source file, line,
+    /// thread and text instead of as a hex dump.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FirmwareLogError::CouldNotInitParser`] if the definition cannot be parsed.
+    pub fn init_parser(&mut self, xml: &CStr) -> Result<(), FirmwareLogError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let initialized =
+                sys::rs2_init_fw_log_parser(self.device.get_raw().as_ptr(), xml.as_ptr(), &mut err);
+            check_rs2_error!(err, FirmwareLogError::CouldNotInitParser)?;
+
+            if initialized == 0 {
+                return Err(FirmwareLogError::CouldNotInitParser(
+                    Rs2Exception::InvalidValue,
+                    String::from("The XML definition was rejected by the parser."),
+                ));
+            }
+        }
+
+        self.has_parser = true;
+        Ok(())
+    }
+
+    /// Start collecting the firmware log messages of at least `severity`.
+    ///
+    /// The device starts logging once the session is first polled. Messages below `severity` are
+    /// skipped by [`FirmwareLogSession::get_message`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FirmwareLogError::CouldNotCreateMessage`] if the buffers that messages are read
+    /// into cannot be created.
+    pub fn start_collecting(
+        &mut self,
+        severity: Rs2LogSeverity,
+    ) -> Result<FirmwareLogSession<'_>, FirmwareLogError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let device_ptr = self.device.get_raw().as_ptr();
+
+            let message_ptr = sys::rs2_create_fw_log_message(device_ptr, &mut err);
+            check_rs2_error!(err, FirmwareLogError::CouldNotCreateMessage)?;
+            let message = NonNull::new(message_ptr).unwrap();
+
+            let parsed = if self.has_parser {
+                let parsed_ptr = sys::rs2_create_fw_log_parsed_message(device_ptr, &mut err);
+                if let Err(e) = check_rs2_error!(err, FirmwareLogError::CouldNotCreateMessage) {
+                    sys::rs2_delete_fw_log_message(message.as_ptr());
+                    return Err(e);
+                }
+                Some(NonNull::new(parsed_ptr).unwrap())
+            } else {
+                None
+            };
+
+            Ok(FirmwareLogSession {
+                logger: self,
+                severity,
+                message,
+                parsed,
+            })
+        }
+    }
+}
+
+/// A session polling the firmware log of a [`FirmwareLogger`] for new messages.
+#[derive(Debug)]
+pub struct FirmwareLogSession<'a> {
+    /// The logger whose device is polled.
+    logger: &'a mut FirmwareLogger,
+    /// The minimum severity of the messages returned.
+    severity: Rs2LogSeverity,
+    /// The buffer each message is read into.
+    message: NonNull<sys::rs2_firmware_log_message>,
+    /// The buffer each message is parsed into, if the logger has a parser.
+    parsed: Option<NonNull<sys::rs2_firmware_log_parsed_message>>,
+}
+
+impl<'a> Drop for FirmwareLogSession<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            sys::rs2_delete_fw_log_message(self.message.as_ptr());
+            if let Some(parsed) = self.parsed {
+                sys::rs2_delete_fw_log_parsed_message(parsed.as_ptr());
+            }
+        }
+    }
+}
+
+unsafe impl<'a> Send for FirmwareLogSession<'a> {}
+
+impl<'a> FirmwareLogSession<'a> {
+    /// Get the next firmware log message of at least the severity the session was started with.
+    ///
+    /// Messages are prefixed with their firmware timestamp and severity. Returns `None` once no
+    /// more messages are available, or if the log cannot be read; poll again later for messages
+    /// logged in the meantime.
+    pub fn get_message(&mut self) -> Option<String> {
+        loop {
+            let severity = self.next_message().ok()??;
+            if severity >= self.severity {
+                return self.format_message(severity).ok();
+            }
+        }
+    }
+
+    /// Read the next message of the firmware log into the message buffer.
+    ///
+    /// Returns the severity of the message, or `None` if there is no message to read.
+    fn next_message(&mut self) -> Result<Option<Rs2LogSeverity>, FirmwareLogError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let read = sys::rs2_get_fw_log(
+                self.logger.device.get_raw().as_ptr(),
+                self.message.as_ptr(),
+                &mut err,
+            );
+            check_rs2_error!(err, FirmwareLogError::CouldNotReadMessage)?;
+            if read == 0 {
+                return Ok(None);
+            }
+
+            let severity = sys::rs2_fw_log_message_severity(self.message.as_ptr(), &mut err);
+            check_rs2_error!(err, FirmwareLogError::CouldNotReadMessage)?;
+            Ok(Rs2LogSeverity::from_i32(severity as i32))
+        }
+    }
+
+    /// Format the message in the message buffer, which has the given `severity`.
+    fn format_message(&self, severity: Rs2LogSeverity) -> Result<String, FirmwareLogError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let timestamp = sys::rs2_fw_log_message_timestamp(self.message.as_ptr(), &mut err);
+            check_rs2_error!(err, FirmwareLogError::CouldNotReadMessage)?;
+
+            let body = match self.parsed {
+                Some(parsed) => self.parse_message(parsed)?,
+                None => self.hex_message()?,
+            };
+            Ok(format!("[{}] {:?}: {}", timestamp, severity, body))
+        }
+    }
+
+    /// Parse the message in the message buffer with the parser of the logger.
+    ///
+    /// # Safety
+    ///
+    /// `parsed` must be the parsed message buffer of this session.
+    unsafe fn parse_message(
+        &self,
+        parsed: NonNull<sys::rs2_firmware_log_parsed_message>,
+    ) -> Result<String, FirmwareLogError> {
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        sys::rs2_parse_firmware_log(
+            self.logger.device.get_raw().as_ptr(),
+            self.message.as_ptr(),
+            parsed.as_ptr(),
+            &mut err,
+        );
+        check_rs2_error!(err, FirmwareLogError::CouldNotReadMessage)?;
+
+        let file = sys::rs2_get_fw_log_parsed_file_name(parsed.as_ptr(), &mut err);
+        check_rs2_error!(err, FirmwareLogError::CouldNotReadMessage)?;
+        let line = sys::rs2_get_fw_log_parsed_line(parsed.as_ptr(), &mut err);
+        check_rs2_error!(err, FirmwareLogError::CouldNotReadMessage)?;
+        let thread = sys::rs2_get_fw_log_parsed_thread_name(parsed.as_ptr(), &mut err);
+        check_rs2_error!(err, FirmwareLogError::CouldNotReadMessage)?;
+        let message = sys::rs2_get_fw_log_parsed_message(parsed.as_ptr(), &mut err);
+        check_rs2_error!(err, FirmwareLogError::CouldNotReadMessage)?;
+
+        Ok(format!(
+            "{}:{} ({}) {}",
+            CStr::from_ptr(file).to_string_lossy(),
+            line,
+            CStr::from_ptr(thread).to_string_lossy(),
+            CStr::from_ptr(message).to_string_lossy()
+        ))
+    }
+
+    /// Format the data of the message in the message buffer as hex.
+    fn hex_message(&self) -> Result<String, FirmwareLogError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let size = sys::rs2_fw_log_message_size(self.message.as_ptr(), &mut err);
+            check_rs2_error!(err, FirmwareLogError::CouldNotReadMessage)?;
+            let data = sys::rs2_fw_log_message_data(self.message.as_ptr(), &mut err);
+            check_rs2_error!(err, FirmwareLogError::CouldNotReadMessage)?;
+
+            let data = std::slice::from_raw_parts(data, size.max(0) as usize);
+            Ok(data
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<Vec<_>>()
+                .join(" "))
+        }
+    }
+}
@@ -1,19 +1,31 @@
 //! Defines the frame type including sensor data.
 
+mod any;
 mod composite;
 mod image;
+#[cfg(feature = "image")]
+mod luma;
+#[cfg(feature = "jpeg")]
+mod mjpeg;
 mod motion;
+mod normals;
 mod pixel;
 mod points;
 mod pose;
 mod prelude;
+#[cfg(feature = "rerun")]
+mod rerun;
 
 pub use self::image::{
-    ColorFrame, ConfidenceFrame, DepthFrame, DisparityFrame, FisheyeFrame, ImageFrame,
+    ColorFrame, ConfidenceFrame, DepthFrame, DisparityFrame, FisheyeFrame, FrameData, ImageFrame,
     InfraredFrame,
 };
+#[cfg(feature = "jpeg")]
+pub use self::mjpeg::{DecodedColorFrame, MjpegDecodeError};
 pub use self::motion::{AccelFrame, GyroFrame, MotionFrame};
-pub use self::points::PointsFrame;
+pub use self::normals::NormalMap;
+pub use self::points::{PlyFormat, PointsFrame};
+pub use any::AnyFrame;
 pub use composite::CompositeFrame;
 pub use pixel::PixelKind;
 pub use pose::{Confidence, PoseFrame};
@@ -0,0 +1,158 @@
+//! Type for representing a RealSense frame of any kind.
+
+use super::{
+    image::{ColorFrame, ConfidenceFrame, DepthFrame, DisparityFrame, FisheyeFrame, InfraredFrame},
+    motion::{AccelFrame, GyroFrame},
+    points::PointsFrame,
+    pose::PoseFrame,
+    prelude::FrameCategory,
+};
+use crate::{kind::Rs2StreamKind, stream_profile::StreamProfile};
+use realsense_sys as sys;
+use std::{
+    convert::{TryFrom, TryInto},
+    ptr::NonNull,
+};
+
+/// Holds an RS2 frame whose type is not known yet.
+///
+/// This is what a sensor streaming with
+/// [`OpenSensor::start_with_any_callback`](crate::sensor::OpenSensor::start_with_any_callback)
+/// passes to its callback, since a single sensor can produce frames of several types, e.g. the
+/// accelerometer and gyroscope frames of an IMU. The frame is converted into one of the concrete
+/// frame types with [`TryFrom`]. If the frame is not of that type, the conversion hands the frame
+/// back, so that another type can be tried:
+///
+/// ```no_run
+/// use realsense_rust::frame::{AccelFrame, AnyFrame, GyroFrame};
+/// use std::convert::TryFrom;
+///
+/// fn handle(frame: AnyFrame) {
+///     let frame = match AccelFrame::try_from(frame) {
+///         Ok(accel) => return println!("accel: {:?}", accel.acceleration()),
+///         Err(frame) => frame,
+///     };
+///     if let Ok(gyro) = GyroFrame::try_from(frame) {
+///         println!("gyro: {:?}", gyro.rotational_velocity());
+///     }
+/// }
+/// ```
+///
+/// The frame is released when dropped, unless it was converted.
+#[derive(Debug)]
+pub struct AnyFrame {
+    /// The raw data pointer from the original rs2 frame.
+    frame_ptr: NonNull<sys::rs2_frame>,
+}
+
+impl Drop for AnyFrame {
+    fn drop(&mut self) {
+        unsafe {
+            sys::rs2_release_frame(self.frame_ptr.as_ptr());
+        }
+    }
+}
+
+impl From<NonNull<sys::rs2_frame>> for AnyFrame {
+    /// Takes ownership of a frame pointer returned from librealsense2.
+    fn from(frame_ptr: NonNull<sys::rs2_frame>) -> Self {
+        Self { frame_ptr }
+    }
+}
+
+impl AnyFrame {
+    /// Checks whether the frame can be converted into a `K`.
+    ///
+    /// This is the case if the frame is extendable to the extension of `K`, and its stream has the
+    /// kind of `K`.
+    pub fn is<K: FrameCategory>(&self) -> bool {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let is_extendable_to = sys::rs2_is_frame_extendable_to(
+                self.frame_ptr.as_ptr(),
+                #[allow(clippy::useless_conversion)]
+                (K::extension() as i32).try_into().unwrap(),
+                &mut err,
+            );
+            if err.as_ref().is_some() {
+                sys::rs2_free_error(err);
+                return false;
+            }
+            if is_extendable_to == 0 {
+                return false;
+            }
+            if K::kind() == Rs2StreamKind::Any {
+                return true;
+            }
+
+            let profile_ptr = sys::rs2_get_frame_stream_profile(self.frame_ptr.as_ptr(), &mut err);
+            if err.as_ref().is_some() {
+                sys::rs2_free_error(err);
+                return false;
+            }
+            NonNull::new(profile_ptr as *mut sys::rs2_stream_profile)
+                .and_then(|ptr| StreamProfile::try_from(ptr).ok())
+                .is_some_and(|profile| profile.kind() == K::kind())
+        }
+    }
+
+    /// Get the raw `rs2_frame` pointer underlying this frame.
+    ///
+    /// This is the same as [`FrameEx::frame_ptr`](crate::frame::FrameEx::frame_ptr) for the
+    /// concrete frame types.
+    ///
+    /// Only available with the `raw-access` feature enabled.
+    #[cfg(feature = "raw-access")]
+    pub fn frame_ptr(&self) -> NonNull<sys::rs2_frame> {
+        self.frame_ptr
+    }
+
+    /// Converts the frame into a `K`, handing the frame back if it is not a `K`.
+    fn downcast<K>(self) -> Result<K, Self>
+    where
+        K: TryFrom<NonNull<sys::rs2_frame>> + FrameCategory,
+    {
+        if !self.is::<K>() {
+            return Err(self);
+        }
+
+        // Frames are only released by the constructed frame type, so on failure the pointer is
+        // still owned by `self`.
+        match K::try_from(self.frame_ptr) {
+            Ok(frame) => {
+                std::mem::forget(self);
+                Ok(frame)
+            }
+            Err(_) => Err(self),
+        }
+    }
+}
+
+/// Implements converting an [`AnyFrame`] into each of the given frame types.
+macro_rules! impl_try_from_any_frame {
+    ($($frame:ty),+ $(,)?) => {
+        $(
+            impl TryFrom<AnyFrame> for $frame {
+                type Error = AnyFrame;
+
+                /// Converts the frame, handing it back as the error if it is of a different type.
+                fn try_from(frame: AnyFrame) -> Result<Self, Self::Error> {
+                    frame.downcast()
+                }
+            }
+        )+
+    };
+}
+
+impl_try_from_any_frame!(
+    AccelFrame,
+    ColorFrame,
+    ConfidenceFrame,
+    DepthFrame,
+    DisparityFrame,
+    FisheyeFrame,
+    GyroFrame,
+    InfraredFrame,
+    PointsFrame,
+    PoseFrame,
+);
@@ -56,6 +56,116 @@ impl CompositeFrame {
         self.count() == 0
     }
 
+    /// Gets the earliest timestamp of all frames in the Composite frame collection.
+    ///
+    /// Returns 0.0 if the collection is empty.
+    pub fn timestamp(&self) -> f64 {
+        self.timestamps()
+            .into_iter()
+            .reduce(f64::min)
+            .unwrap_or_default()
+    }
+
+    /// Gets the difference between the latest and earliest timestamps of all frames in the
+    /// Composite frame collection.
+    ///
+    /// This is a measure of how well the frames in the collection are synchronized. For a
+    /// well-synchronized pipeline this should be on the order of a few milliseconds.
+    ///
+    /// Returns 0.0 if the collection is empty.
+    pub fn timestamp_span(&self) -> f64 {
+        let timestamps = self.timestamps();
+        match (
+            timestamps.iter().copied().reduce(f64::min),
+            timestamps.iter().copied().reduce(f64::max),
+        ) {
+            (Some(min), Some(max)) => max - min,
+            _ => 0.0,
+        }
+    }
+
+    /// Collects the timestamps of every frame in the Composite frame collection.
+    ///
+    /// Frames whose timestamp cannot be retrieved are skipped.
+    fn timestamps(&self) -> Vec<f64> {
+        let mut timestamps = Vec::new();
+        for i in 0..self.count() {
+            unsafe {
+                let mut err = std::ptr::null_mut::<sys::rs2_error>();
+                let frame_ptr =
+                    sys::rs2_extract_frame(self.ptr.as_ptr(), i as std::os::raw::c_int, &mut err);
+
+                if err.as_ref().is_some() {
+                    sys::rs2_free_error(err);
+                    continue;
+                }
+
+                let timestamp = sys::rs2_get_frame_timestamp(frame_ptr, &mut err);
+
+                if err.as_ref().is_none() {
+                    timestamps.push(timestamp);
+                } else {
+                    sys::rs2_free_error(err);
+                }
+                sys::rs2_release_frame(frame_ptr);
+            }
+        }
+        timestamps
+    }
+
+    /// Collects the stream unique ID and frame number of every frame in the Composite frame
+    /// collection.
+    ///
+    /// Frames whose stream profile or frame number cannot be retrieved are skipped.
+    pub(crate) fn frame_numbers(&self) -> Vec<(i32, u64)> {
+        let mut frame_numbers = Vec::new();
+        for i in 0..self.count() {
+            unsafe {
+                let mut err = std::ptr::null_mut::<sys::rs2_error>();
+                let frame_ptr =
+                    sys::rs2_extract_frame(self.ptr.as_ptr(), i as std::os::raw::c_int, &mut err);
+
+                if err.as_ref().is_some() {
+                    sys::rs2_free_error(err);
+                    continue;
+                }
+
+                let profile_ptr = sys::rs2_get_frame_stream_profile(frame_ptr, &mut err);
+
+                if err.as_ref().is_none() {
+                    let mut stream = sys::rs2_stream_RS2_STREAM_ANY;
+                    let mut format = sys::rs2_format_RS2_FORMAT_ANY;
+                    let mut index = 0;
+                    let mut unique_id = 0;
+                    let mut framerate = 0;
+                    sys::rs2_get_stream_profile_data(
+                        profile_ptr,
+                        &mut stream,
+                        &mut format,
+                        &mut index,
+                        &mut unique_id,
+                        &mut framerate,
+                        &mut err,
+                    );
+
+                    if err.as_ref().is_none() {
+                        let frame_number = sys::rs2_get_frame_number(frame_ptr, &mut err);
+
+                        if err.as_ref().is_none() {
+                            frame_numbers.push((unique_id, frame_number));
+                        }
+                    }
+                }
+
+                if err.as_ref().is_some() {
+                    sys::rs2_free_error(err);
+                }
+                sys::rs2_release_frame(frame_ptr);
+            }
+        }
+        frame_numbers
+    }
+
     /// Retrieves all frames in the Composite frame collection of a given type.
     ///
     /// # Generic Arguments
@@ -73,47 +183,65 @@ impl CompositeFrame {
     where
         F: TryFrom<NonNull<sys::rs2_frame>> + FrameCategory,
     {
-        let mut frames = Vec::new();
-        for i in 0..self.count() {
-            unsafe {
-                let mut err = std::ptr::null_mut::<sys::rs2_error>();
-                let frame_ptr =
-                    sys::rs2_extract_frame(self.ptr.as_ptr(), i as std::os::raw::c_int, &mut err);
+        self.iter_of_type().collect()
+    }
 
-                if err.as_ref().is_some() {
-                    sys::rs2_free_error(err);
-                    continue;
-                }
+    /// Lazily iterates over all frames in the Composite frame collection of a given type.
+    ///
+    /// This is the same as [`CompositeFrame::frames_of_type`], but frames are only extracted from
+    /// the collection as the iterator is advanced, and without collecting them into a `Vec`.
+    pub fn iter_of_type<F>(&self) -> impl Iterator<Item = F> + '_
+    where
+        F: TryFrom<NonNull<sys::rs2_frame>> + FrameCategory,
+    {
+        (0..self.count()).filter_map(move |i| self.frame_of_type_at(i))
+    }
 
-                let nonnull_frame_ptr = NonNull::new(frame_ptr).unwrap();
+    /// Extracts the frame at `index` in the Composite frame collection, if it is of type `F`.
+    ///
+    /// The extracted frame is released if it is not of type `F`.
+    fn frame_of_type_at<F>(&self, index: usize) -> Option<F>
+    where
+        F: TryFrom<NonNull<sys::rs2_frame>> + FrameCategory,
+    {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let frame_ptr =
+                sys::rs2_extract_frame(self.ptr.as_ptr(), index as std::os::raw::c_int, &mut err);
 
-                let is_extendable_to = sys::rs2_is_frame_extendable_to(
-                    nonnull_frame_ptr.as_ptr(),
-                    #[allow(clippy::useless_conversion)]
-                    (F::extension() as i32).try_into().unwrap(),
-                    &mut err,
-                );
+            if err.as_ref().is_some() {
+                sys::rs2_free_error(err);
+                return None;
+            }
 
-                if err.as_ref().is_none() {
-                    if is_extendable_to != 0 {
-                        if let Ok(f) = F::try_from(nonnull_frame_ptr) {
-                            let kind_for_frame = F::kind();
-
-                            if kind_for_frame == Rs2StreamKind::Any || f.has_correct_kind() {
-                                frames.push(f);
-                            }
-                            // This continue is to skip releasing the frame at the end of the loop.
-                            // If the call to try_from above is successful and we can push, then
-                            // the frame is owned by the type `E` and we should not release it.
-                            continue;
+            let nonnull_frame_ptr = NonNull::new(frame_ptr).unwrap();
+
+            let is_extendable_to = sys::rs2_is_frame_extendable_to(
+                nonnull_frame_ptr.as_ptr(),
+                #[allow(clippy::useless_conversion)]
+                (F::extension() as i32).try_into().unwrap(),
+                &mut err,
+            );
+
+            if err.as_ref().is_none() {
+                if is_extendable_to != 0 {
+                    if let Ok(f) = F::try_from(nonnull_frame_ptr) {
+                        let kind_for_frame = F::kind();
+
+                        // If the call to try_from above is successful, then the frame is owned by
+                        // the type `F` and we should not release it, even if it is filtered out
+                        // here.
+                        if kind_for_frame == Rs2StreamKind::Any || f.has_correct_kind() {
+                            return Some(f);
                         }
+                        return None;
                     }
-                } else {
-                    sys::rs2_free_error(err);
                 }
-                sys::rs2_release_frame(nonnull_frame_ptr.as_ptr());
+            } else {
+                sys::rs2_free_error(err);
             }
+            sys::rs2_release_frame(nonnull_frame_ptr.as_ptr());
+            None
         }
-        frames
     }
 }
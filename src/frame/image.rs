@@ -9,27 +9,52 @@
 //! Each frame type can hold data in multiple formats. The data type presented
 //! depends on the settings and flags used at runtime on the RealSense device.
 
-use super::pixel::{get_pixel, PixelKind};
+use super::pixel::{get_pixel, row_pixels, PixelKind};
 use super::prelude::{
-    CouldNotGetFrameSensorError, DepthError, DisparityError, FrameCategory, FrameConstructionError,
-    FrameEx, BITS_PER_BYTE,
+    private::RawFrame, CouldNotGetFrameSensorError, DepthError, DisparityError, FrameCategory,
+    FrameConstructionError, FrameEx, BITS_PER_BYTE,
 };
 use crate::{
     check_rs2_error,
-    kind::{Rs2Extension, Rs2FrameMetadata, Rs2Option, Rs2StreamKind, Rs2TimestampDomain},
+    kind::{
+        Rs2Extension, Rs2Format, Rs2FrameMetadata, Rs2Option, Rs2StreamKind, Rs2TimestampDomain,
+    },
     sensor::Sensor,
     stream_profile::StreamProfile,
 };
 use anyhow::Result;
 use num_traits::FromPrimitive;
+#[cfg(feature = "rayon")]
+use rayon::{iter::IndexedParallelIterator, slice::ParallelSlice};
 use realsense_sys as sys;
 use std::{
     convert::{TryFrom, TryInto},
     marker::PhantomData,
     os::raw::c_int,
     ptr::{self, NonNull},
+    slice,
 };
 
+/// Module for sealing [`FrameData`], so that it cannot be implemented outside of this crate.
+mod sealed {
+    /// Supertrait of [`FrameData`](super::FrameData) that is not nameable outside of the crate.
+    pub trait Sealed {}
+}
+
+/// Plain data types that the data of an image frame can be viewed as, see
+/// [`ImageFrame::as_slice`].
+///
+/// This is implemented for `u8`, `u16` and `f32`, for which every bit pattern is a valid value.
+/// It is sealed, since implementing it for any other type could allow creating invalid values.
+pub trait FrameData: Copy + sealed::Sealed {}
+
+impl sealed::Sealed for u8 {}
+impl FrameData for u8 {}
+impl sealed::Sealed for u16 {}
+impl FrameData for u16 {}
+impl sealed::Sealed for f32 {}
+impl FrameData for f32 {}
+
 /// A unit struct defining a Depth frame.
 #[derive(Debug)]
 pub struct Depth;
@@ -196,6 +221,7 @@ impl<K> TryFrom<NonNull<sys::rs2_frame>> for ImageFrame<K> {
     /// - [`CouldNotGetFrameStreamProfile`](FrameConstructionError::CouldNotGetFrameStreamProfile)
     /// - [`CouldNotGetDataSize`](FrameConstructionError::CouldNotGetDataSize)
     /// - [`CouldNotGetData`](FrameConstructionError::CouldNotGetData)
+    /// - [`CorruptFrameData`](FrameConstructionError::CorruptFrameData)
     ///
     /// See [`FrameConstructionError`] documentation for more details.
     fn try_from(frame_ptr: NonNull<sys::rs2_frame>) -> Result<Self, Self::Error> {
@@ -233,7 +259,19 @@ impl<K> TryFrom<NonNull<sys::rs2_frame>> for ImageFrame<K> {
             let size = sys::rs2_get_frame_data_size(frame_ptr.as_ptr(), &mut err);
             check_rs2_error!(err, FrameConstructionError::CouldNotGetDataSize)?;
 
-            debug_assert_eq!(size, width * height * bits_per_pixel / BITS_PER_BYTE);
+            // Compressed formats such as MJPEG and Z16H carry a variable-length bitstream, so only
+            // formats with a fixed per-pixel layout can be checked against their dimensions.
+            if profile.format().bit_depth_per_channel().is_some()
+                && size < width * height * bits_per_pixel / BITS_PER_BYTE
+            {
+                return Err(FrameConstructionError::CorruptFrameData(
+                    width as usize,
+                    height as usize,
+                    bits_per_pixel as usize,
+                    size as usize,
+                )
+                .into());
+            }
 
             let data_ptr = sys::rs2_get_frame_data(frame_ptr.as_ptr(), &mut err);
             check_rs2_error!(err, FrameConstructionError::CouldNotGetData)?;
@@ -343,6 +381,12 @@ impl FrameCategory for ConfidenceFrame {
     }
 }
 
+impl<T> RawFrame for ImageFrame<T> {
+    fn raw_frame_ptr(&self) -> NonNull<sys::rs2_frame> {
+        self.frame_ptr
+    }
+}
+
 impl<T> FrameEx for ImageFrame<T> {
     fn stream_profile(&self) -> &StreamProfile {
         &self.frame_stream_profile
@@ -452,6 +496,136 @@ impl DepthFrame {
         })?;
         Ok(depth_units)
     }
+
+    /// Get an iterator over the rows of raw [`Z16`](Rs2Format::Z16) depth values.
+    ///
+    /// Each row is exactly `width` values long; any padding at the end of a row is skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DepthError::UnsupportedFormat`] if the frame is not in the `Z16` format.
+    pub(crate) fn z16_rows(&self) -> Result<impl Iterator<Item = &[u16]>, DepthError> {
+        let format = self.frame_stream_profile.format();
+        if format != Rs2Format::Z16 {
+            return Err(DepthError::UnsupportedFormat(format));
+        }
+
+        let width = self.width;
+        let stride = self.stride / std::mem::size_of::<u16>();
+
+        Ok(self
+            .as_u16_slice()
+            .chunks(stride)
+            .take(self.height)
+            .map(move |row| &row[..width]))
+    }
+
+    /// Convert the depth values of the frame to metres.
+    ///
+    /// Returns a tightly packed, row-major buffer of `width * height` depth values in metres,
+    /// scaled by [`DepthFrame::depth_units`]. Pixels without a valid depth (raw value of zero) are
+    /// converted to [`f32::NAN`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the depth units cannot be retrieved from the frame's sensor, or
+    /// [`DepthError::UnsupportedFormat`] if the frame is not in the [`Z16`](Rs2Format::Z16)
+    /// format.
+    pub fn to_float_meters(&self) -> Result<Vec<f32>> {
+        let depth_units = self.depth_units()?;
+
+        Ok(self
+            .z16_rows()?
+            .flatten()
+            .map(|&depth| {
+                if depth == 0 {
+                    f32::NAN
+                } else {
+                    depth as f32 * depth_units
+                }
+            })
+            .collect())
+    }
+
+    /// Copy the raw depth values of the frame, zeroing every pixel where `mask` is false.
+    ///
+    /// `mask` must hold one entry per pixel in row-major order, i.e. have a length of
+    /// `width * height`. The output is a tightly packed, row-major buffer of raw
+    /// [`Z16`](Rs2Format::Z16) values of the same length, which can be scaled to metres with
+    /// [`DepthFrame::depth_units`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DepthError::MaskSizeMismatch`] if `mask` does not have one entry per pixel.
+    ///
+    /// Returns [`DepthError::UnsupportedFormat`] if the frame is not in the `Z16` format.
+    pub fn apply_mask(&self, mask: &[bool]) -> Result<Vec<u16>, DepthError> {
+        let pixel_count = self.width * self.height;
+        if mask.len() != pixel_count {
+            return Err(DepthError::MaskSizeMismatch(pixel_count, mask.len()));
+        }
+
+        Ok(self
+            .z16_rows()?
+            .flatten()
+            .zip(mask)
+            .map(|(&depth, &keep)| if keep { depth } else { 0 })
+            .collect())
+    }
+
+    /// Get the median of all valid depth values in the frame, in meters.
+    ///
+    /// `depth_unit` is the scale of the raw depth values in meters, see
+    /// [`DepthFrame::depth_units`]. Pixels without a valid depth (raw value of zero) are ignored.
+    /// The median is found by partial selection rather than a full sort, so this runs in linear
+    /// time, but still copies the valid depth values of the frame.
+    ///
+    /// Returns `None` if the frame has no valid pixels or is not in the [`Z16`](Rs2Format::Z16)
+    /// format.
+    pub fn median_depth(&self, depth_unit: f32) -> Option<f32> {
+        let mut depths: Vec<u16> = self
+            .z16_rows()
+            .ok()?
+            .flatten()
+            .copied()
+            .filter(|&depth| depth != 0)
+            .collect();
+
+        median(&mut depths).map(|depth| depth * depth_unit)
+    }
+
+    /// Smooth the depth values of the frame with a bilateral filter.
+    ///
+    /// Unlike a Gaussian blur, a bilateral filter weighs each neighbouring pixel both by its
+    /// distance to the filtered pixel (`sigma_space`, in pixels) and by the difference in depth
+    /// (`sigma_depth`, in raw depth units, see [`DepthFrame::depth_units`]). This smooths noise on
+    /// surfaces while preserving the edges between objects at different depths.
+    ///
+    /// The filter runs entirely on the CPU, without a processing block. Pixels without a valid
+    /// depth (raw value of zero) stay zero in the output and are never used to filter their
+    /// neighbours. The output is a tightly packed, row-major buffer of `width * height` raw
+    /// [`Z16`](Rs2Format::Z16) values.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DepthError::UnsupportedFormat`] if the frame is not in the `Z16` format.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sigma_space` or `sigma_depth` is not positive.
+    pub fn bilateral_filter(
+        &self,
+        sigma_space: f32,
+        sigma_depth: f32,
+    ) -> Result<Vec<u16>, DepthError> {
+        let rows: Vec<&[u16]> = self.z16_rows()?.collect();
+        Ok(bilateral_filter(
+            &rows,
+            self.width,
+            sigma_space,
+            sigma_depth,
+        ))
+    }
 }
 
 impl DisparityFrame {
@@ -558,6 +732,95 @@ impl<K> ImageFrame<K> {
         self.data.as_ref()
     }
 
+    /// View the data held by this Video frame as a slice of `T`.
+    ///
+    /// The data is not copied, and has to be interpreted according to the format of the frame,
+    /// e.g. as `u16` for [`Z16`](Rs2Format::Z16) depth frames. The slice includes the padding at
+    /// the end of each row, if any, so rows are [`stride`](ImageFrame::stride) bytes apart.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size of the data is not a multiple of the size of `T`, or if the data is not
+    /// aligned for `T`.
+    pub fn as_slice<T: FrameData>(&self) -> &[T] {
+        let element_size = std::mem::size_of::<T>();
+        assert!(
+            self.data_size_in_bytes % element_size == 0,
+            "frame data of {} bytes cannot be viewed as elements of {} bytes",
+            self.data_size_in_bytes,
+            element_size
+        );
+
+        let data = self.data.as_ptr().cast::<T>();
+        assert!(
+            data.align_offset(std::mem::align_of::<T>()) == 0,
+            "frame data is not aligned to {} bytes",
+            std::mem::align_of::<T>()
+        );
+
+        // SAFETY: the data pointer is valid for `data_size_in_bytes` bytes for as long as the
+        // frame is alive, is aligned for `T` and every bit pattern is a valid `T`.
+        unsafe { slice::from_raw_parts(data, self.data_size_in_bytes / element_size) }
+    }
+
+    /// View the data held by this Video frame as bytes, see [`ImageFrame::as_slice`].
+    pub fn as_u8_slice(&self) -> &[u8] {
+        self.as_slice()
+    }
+
+    /// View the data held by this Video frame as `u16` values, see [`ImageFrame::as_slice`].
+    pub fn as_u16_slice(&self) -> &[u16] {
+        self.as_slice()
+    }
+
+    /// View the data held by this Video frame as `f32` values, see [`ImageFrame::as_slice`].
+    pub fn as_f32_slice(&self) -> &[f32] {
+        self.as_slice()
+    }
+
+    /// Copy the data held by this Video frame into an owned `Vec<u8>`.
+    ///
+    /// The copy does not borrow the frame, so it can be sent to other threads or kept after the
+    /// frame is dropped. It is [`get_data_size`](ImageFrame::get_data_size) bytes long and rows
+    /// are [`stride`](ImageFrame::stride) bytes apart, including any padding at the end of each
+    /// row. Pixels are laid out according to the format of the frame, with multi-byte values in
+    /// native (little-endian on all supported platforms) byte order:
+    ///
+    /// - [`Z16`](Rs2Format::Z16), [`Y16`](Rs2Format::Y16) and
+    ///   [`Disparity16`](Rs2Format::Disparity16): one `u16` per pixel. Z16 values are in
+    ///   [depth units](DepthFrame::depth_units).
+    /// - [`Distance`](Rs2Format::Distance) and [`Disparity32`](Rs2Format::Disparity32): one `f32`
+    ///   per pixel.
+    /// - [`Y8`](Rs2Format::Y8) and [`Raw8`](Rs2Format::Raw8): one byte per pixel.
+    /// - [`Rgb8`](Rs2Format::Rgb8) and [`Bgr8`](Rs2Format::Bgr8): three bytes per pixel, in the
+    ///   order of the format's name. [`Rgba8`](Rs2Format::Rgba8) and [`Bgra8`](Rs2Format::Bgra8)
+    ///   add a fourth alpha byte.
+    /// - [`Yuyv`](Rs2Format::Yuyv) and [`Uyvy`](Rs2Format::Uyvy): four bytes per pair of pixels,
+    ///   with both pixels sharing the chroma values.
+    /// - [`Mjpeg`](Rs2Format::Mjpeg): a compressed JPEG image, with no per-pixel layout.
+    pub fn copy_to_vec(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(self.data_size_in_bytes);
+
+        // SAFETY: the data pointer is valid for `data_size_in_bytes` bytes for as long as the
+        // frame is alive, and `data` was allocated with room for as many bytes.
+        unsafe {
+            ptr::copy_nonoverlapping(
+                self.data.as_ptr().cast::<u8>(),
+                data.as_mut_ptr(),
+                self.data_size_in_bytes,
+            );
+            data.set_len(self.data_size_in_bytes);
+        }
+        data
+    }
+
+    /// Copy the data held by this Video frame into an owned, fixed-size slice.
+    ///
+    /// The bytes are laid out as described in [`copy_to_vec`](ImageFrame::copy_to_vec).
+    pub fn copy_to_boxed_slice(&self) -> Box<[u8]> {
+        self.copy_to_vec().into_boxed_slice()
+    }
+
     /// Get the width of this Video frame in pixels
     pub fn width(&self) -> usize {
         self.width
@@ -569,6 +832,9 @@ impl<K> ImageFrame<K> {
     }
 
     /// Given a row and column index, Get a pixel value from this frame.
+    ///
+    /// Returns `None` if the pixel is out of bounds. Pixels of formats without a known layout are
+    /// returned as [`PixelKind::Unsupported`].
     pub fn get(&self, col: usize, row: usize) -> Option<PixelKind<'_>> {
         if col >= self.width || row >= self.height {
             None
@@ -576,6 +842,135 @@ impl<K> ImageFrame<K> {
             Some(self.get_unchecked(col, row))
         }
     }
+
+    /// Iterate over the [pixels](crate::frame::PixelKind) of a single row of this frame.
+    ///
+    /// Returns `None` if `row` is out of bounds. Pixels are yielded from left to right, and each
+    /// is looked up in the same way as with [`ImageFrame::get`]. For raw access to the bytes of a
+    /// row, index [`ImageFrame::as_u8_slice`] by [`stride`](ImageFrame::stride) instead.
+    pub fn row(&self, row: usize) -> Option<impl Iterator<Item = PixelKind<'_>> + '_> {
+        if row >= self.height {
+            None
+        } else {
+            Some(self.row_unchecked(row))
+        }
+    }
+
+    /// Iterate over the rows of this frame, from top to bottom.
+    ///
+    /// Each item is an iterator over the pixels of one row, as returned by [`ImageFrame::row`].
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = PixelKind<'_>> + '_> + '_ {
+        (0..self.height).map(move |row| self.row_unchecked(row))
+    }
+
+    /// Iterate over the pixels of `row`, which must be less than the height of the frame.
+    fn row_unchecked(&self, row: usize) -> impl Iterator<Item = PixelKind<'_>> + '_ {
+        unsafe {
+            row_pixels(
+                self.frame_stream_profile.format(),
+                self.data_size_in_bytes,
+                self.data.as_ptr(),
+                self.stride,
+                self.width,
+                row,
+            )
+        }
+    }
+
+    /// Iterate over the rows of this frame in parallel.
+    ///
+    /// Each item is the raw bytes of a single row, which is [`stride`](ImageFrame::stride) bytes
+    /// long and has to be interpreted according to the format of the frame. Rows are independent,
+    /// so they can be processed on separate threads without contention.
+    ///
+    /// Only available with the `rayon` feature enabled.
+    #[cfg(feature = "rayon")]
+    pub fn par_rows(&self) -> impl IndexedParallelIterator<Item = &[u8]> + '_ {
+        self.as_u8_slice().par_chunks(self.stride).take(self.height)
+    }
+}
+
+/// Bilateral filter over `rows` of raw depth values, each `width` values long.
+///
+/// See [`DepthFrame::bilateral_filter`]. The output is processed one row at a time, so that the
+/// neighbourhood of each pixel is read from the same few rows while they are in cache.
+fn bilateral_filter(rows: &[&[u16]], width: usize, sigma_space: f32, sigma_depth: f32) -> Vec<u16> {
+    assert!(
+        sigma_space > 0.0 && sigma_depth > 0.0,
+        "bilateral filter sigmas must be positive"
+    );
+
+    let radius = (2.0 * sigma_space).ceil() as usize;
+    let kernel_size = 2 * radius + 1;
+    let spatial_weights: Vec<f32> = (0..kernel_size * kernel_size)
+        .map(|i| {
+            let dx = (i % kernel_size) as f32 - radius as f32;
+            let dy = (i / kernel_size) as f32 - radius as f32;
+            (-(dx * dx + dy * dy) / (2.0 * sigma_space * sigma_space)).exp()
+        })
+        .collect();
+    let depth_scale = -1.0 / (2.0 * sigma_depth * sigma_depth);
+
+    let height = rows.len();
+    let mut filtered = Vec::with_capacity(width * height);
+
+    for row in 0..height {
+        let row_range = row.saturating_sub(radius)..(row + radius + 1).min(height);
+
+        for col in 0..width {
+            let center = rows[row][col];
+            if center == 0 {
+                filtered.push(0);
+                continue;
+            }
+
+            let col_range = col.saturating_sub(radius)..(col + radius + 1).min(width);
+            let mut weighted_sum = 0.0;
+            let mut weight_sum = 0.0;
+
+            for neighbour_row in row_range.clone() {
+                let kernel_row = (neighbour_row + radius - row) * kernel_size;
+
+                for neighbour_col in col_range.clone() {
+                    let depth = rows[neighbour_row][neighbour_col];
+                    if depth == 0 {
+                        continue;
+                    }
+
+                    let difference = depth as f32 - center as f32;
+                    let weight = spatial_weights[kernel_row + neighbour_col + radius - col]
+                        * (difference * difference * depth_scale).exp();
+                    weighted_sum += weight * depth as f32;
+                    weight_sum += weight;
+                }
+            }
+
+            // The center pixel always contributes a weight of one, so `weight_sum` is non-zero.
+            filtered.push((weighted_sum / weight_sum).round() as u16);
+        }
+    }
+
+    filtered
+}
+
+/// Computes the median of `values`, reordering them in the process.
+///
+/// For an even number of values this is the mean of the two middle values. Returns `None` if
+/// `values` is empty.
+fn median(values: &mut [u16]) -> Option<f32> {
+    if values.is_empty() {
+        return None;
+    }
+
+    let len = values.len();
+    let (lower, &mut upper, _) = values.select_nth_unstable(len / 2);
+
+    if len % 2 == 1 {
+        Some(upper as f32)
+    } else {
+        let lower = *lower.iter().max().unwrap();
+        Some((lower as f32 + upper as f32) / 2.0)
+    }
 }
 
 #[cfg(test)]
@@ -591,4 +986,27 @@ mod tests {
         assert_eq!(FisheyeFrame::kind(), Rs2StreamKind::Fisheye);
         assert_eq!(ConfidenceFrame::kind(), Rs2StreamKind::Confidence);
     }
+
+    #[test]
+    fn median_of_odd_and_even_counts() {
+        assert_eq!(median(&mut []), None);
+        assert_eq!(median(&mut [7]), Some(7.0));
+        assert_eq!(median(&mut [9, 1, 5]), Some(5.0));
+        assert_eq!(median(&mut [9, 1, 4, 6]), Some(5.0));
+    }
+
+    #[test]
+    fn bilateral_filter_smooths_noise_but_keeps_edges_and_holes() {
+        let near: &[u16] = &[1000, 1002, 998, 0, 3000, 3000];
+        let far: &[u16] = &[1001, 999, 1000, 1000, 3000, 3002];
+        let filtered = bilateral_filter(&[near, far], 6, 1.0, 10.0);
+
+        // Noise on the near surface is smoothed out.
+        assert!(filtered[..3].iter().all(|&d| (999..=1001).contains(&d)));
+        // Invalid pixels stay invalid.
+        assert_eq!(filtered[3], 0);
+        // The far surface is not pulled towards the near one.
+        assert!(filtered[4..6].iter().all(|&d| (3000..=3002).contains(&d)));
+        assert_eq!(filtered[9], 1000);
+    }
 }
@@ -0,0 +1,66 @@
+//! Conversions from depth frames into grayscale [`image`](::image) buffers.
+//!
+//! 16-bit grayscale PNG is the usual way to store depth in datasets such as NYU Depth V2 or TUM
+//! RGB-D, so converting a frame with `ImageBuffer::try_from(&depth_frame)?` and calling `save` on
+//! the result is enough to write a frame in that format.
+//!
+//! This module is only available with the `image` feature enabled.
+
+use super::{image::DepthFrame, prelude::DepthError};
+use ::image::{ImageBuffer, Luma};
+use std::convert::TryFrom;
+
+/// The divisor used to scale raw depth values into 8 bits when converting with `TryFrom`.
+const DEFAULT_LUMA8_DIVISOR: f32 = 255.0;
+
+impl DepthFrame {
+    /// Copy the raw depth values of the frame into an 8-bit grayscale image.
+    ///
+    /// Each raw [`Z16`](crate::kind::Rs2Format::Z16) value is divided by `divisor` and saturated
+    /// to the `u8` range, so e.g. a divisor of 20 maps a range of 0-5100 raw units onto the full
+    /// 8 bits. Converting with `TryFrom` uses a divisor of 255.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DepthError::UnsupportedFormat`] if the frame is not in the `Z16` format.
+    pub fn to_luma8(&self, divisor: f32) -> Result<ImageBuffer<Luma<u8>, Vec<u8>>, DepthError> {
+        let data = self
+            .z16_rows()?
+            .flatten()
+            .map(|&depth| (depth as f32 / divisor).round().min(u8::MAX as f32) as u8)
+            .collect();
+
+        Ok(
+            ImageBuffer::from_raw(self.width() as u32, self.height() as u32, data)
+                .expect("the buffer holds exactly one value per pixel"),
+        )
+    }
+}
+
+impl TryFrom<&DepthFrame> for ImageBuffer<Luma<u16>, Vec<u16>> {
+    type Error = DepthError;
+
+    /// Copy the raw depth values of the frame into a 16-bit grayscale image.
+    ///
+    /// Returns [`DepthError::UnsupportedFormat`] if the frame is not in the
+    /// [`Z16`](crate::kind::Rs2Format::Z16) format.
+    fn try_from(frame: &DepthFrame) -> Result<Self, Self::Error> {
+        let data = frame.z16_rows()?.flatten().copied().collect();
+
+        Ok(
+            ImageBuffer::from_raw(frame.width() as u32, frame.height() as u32, data)
+                .expect("the buffer holds exactly one value per pixel"),
+        )
+    }
+}
+
+impl TryFrom<&DepthFrame> for ImageBuffer<Luma<u8>, Vec<u8>> {
+    type Error = DepthError;
+
+    /// Scale the raw depth values of the frame into an 8-bit grayscale image.
+    ///
+    /// This is [`DepthFrame::to_luma8`] with a divisor of 255.
+    fn try_from(frame: &DepthFrame) -> Result<Self, Self::Error> {
+        frame.to_luma8(DEFAULT_LUMA8_DIVISOR)
+    }
+}
@@ -0,0 +1,136 @@
+//! Decoding support for MJPEG-compressed color frames.
+//!
+//! Frames streamed in [`Rs2Format::Mjpeg`] hold a JPEG bitstream rather than raw pixel data, so
+//! they cannot be indexed with [`ImageFrame::get`](crate::frame::ImageFrame::get). The types in
+//! this module decompress that bitstream into an owned RGB8 buffer.
+//!
+//! This module is only available with the `jpeg` feature enabled.
+
+use super::{image::ColorFrame, pixel::PixelKind, prelude::FrameEx};
+use crate::kind::Rs2Format;
+use jpeg_decoder::{Decoder, PixelFormat};
+use std::{os::raw::c_void, slice};
+use thiserror::Error;
+
+/// Occurs when an MJPEG frame cannot be decoded.
+#[derive(Error, Debug)]
+pub enum MjpegDecodeError {
+    /// The frame was not streamed in the MJPEG format.
+    #[error("Frame is not MJPEG encoded. Format: {0:?}")]
+    NotMjpegFormat(Rs2Format),
+    /// The JPEG bitstream could not be decoded.
+    #[error("Could not decode JPEG data. Reason: {0}")]
+    CouldNotDecode(String),
+    /// The JPEG bitstream decoded to a pixel layout that cannot be represented as RGB8.
+    #[error("Unsupported JPEG pixel format: {0:?}")]
+    UnsupportedPixelFormat(PixelFormat),
+}
+
+/// An owned RGB8 image produced by decoding an MJPEG color frame.
+///
+/// Unlike [`ImageFrame`](crate::frame::ImageFrame), this type does not point into librealsense2
+/// memory and can outlive the frame it was decoded from.
+#[derive(Debug, Clone)]
+pub struct DecodedColorFrame {
+    /// The width of the image in pixels.
+    width: usize,
+    /// The height of the image in pixels.
+    height: usize,
+    /// Tightly packed, row-major RGB8 pixel data.
+    data: Vec<u8>,
+}
+
+impl DecodedColorFrame {
+    /// Get the width of the decoded image in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get the height of the decoded image in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the stride of the decoded image in bytes.
+    pub fn stride(&self) -> usize {
+        self.width * 3
+    }
+
+    /// Get the format of the decoded image, which is always [`Rs2Format::Rgb8`].
+    pub fn format(&self) -> Rs2Format {
+        Rs2Format::Rgb8
+    }
+
+    /// Get the tightly packed, row-major RGB8 pixel data.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Consume the decoded image and return the underlying RGB8 buffer.
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// Given a row and column index, get a pixel value from the decoded image.
+    ///
+    /// Returns `None` if the index is out of bounds.
+    pub fn get(&self, col: usize, row: usize) -> Option<PixelKind<'_>> {
+        if col >= self.width || row >= self.height {
+            return None;
+        }
+
+        let offset = row * self.stride() + col * 3;
+        Some(PixelKind::Rgb8 {
+            r: &self.data[offset],
+            g: &self.data[offset + 1],
+            b: &self.data[offset + 2],
+        })
+    }
+}
+
+impl ColorFrame {
+    /// Decode an MJPEG color frame into an owned RGB8 image.
+    ///
+    /// Grayscale JPEG data is expanded to three identical channels.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MjpegDecodeError::NotMjpegFormat`] if the frame was not streamed as
+    /// [`Rs2Format::Mjpeg`], and [`MjpegDecodeError::CouldNotDecode`] if the frame data is not a
+    /// valid JPEG bitstream.
+    pub fn decode_mjpeg(&self) -> Result<DecodedColorFrame, MjpegDecodeError> {
+        let format = self.stream_profile().format();
+        if format != Rs2Format::Mjpeg {
+            return Err(MjpegDecodeError::NotMjpegFormat(format));
+        }
+
+        // SAFETY: the data pointer is valid for `get_data_size` bytes for as long as the frame is
+        // alive, and the slice does not escape this function.
+        let bytes = unsafe {
+            slice::from_raw_parts(
+                (self.get_data() as *const c_void).cast::<u8>(),
+                self.get_data_size(),
+            )
+        };
+
+        let mut decoder = Decoder::new(bytes);
+        let pixels = decoder
+            .decode()
+            .map_err(|e| MjpegDecodeError::CouldNotDecode(e.to_string()))?;
+        let info = decoder.info().ok_or_else(|| {
+            MjpegDecodeError::CouldNotDecode(String::from("Missing JPEG header information"))
+        })?;
+
+        let data = match info.pixel_format {
+            PixelFormat::RGB24 => pixels,
+            PixelFormat::L8 => pixels.iter().flat_map(|&l| [l, l, l]).collect(),
+            other => return Err(MjpegDecodeError::UnsupportedPixelFormat(other)),
+        };
+
+        Ok(DecodedColorFrame {
+            width: info.width as usize,
+            height: info.height as usize,
+            data,
+        })
+    }
+}
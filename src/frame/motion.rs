@@ -5,7 +5,9 @@
 //!
 //! See the docs for [MotionFrame::motion] for more.
 
-use super::prelude::{CouldNotGetFrameSensorError, FrameCategory, FrameConstructionError, FrameEx};
+use super::prelude::{
+    private::RawFrame, CouldNotGetFrameSensorError, FrameCategory, FrameConstructionError, FrameEx,
+};
 use crate::{
     check_rs2_error,
     kind::{Rs2Extension, Rs2FrameMetadata, Rs2StreamKind, Rs2TimestampDomain},
@@ -168,6 +170,12 @@ impl<K> TryFrom<NonNull<sys::rs2_frame>> for MotionFrame<K> {
     }
 }
 
+impl<K> RawFrame for MotionFrame<K> {
+    fn raw_frame_ptr(&self) -> NonNull<sys::rs2_frame> {
+        self.frame_ptr
+    }
+}
+
 impl<K> FrameEx for MotionFrame<K> {
     fn stream_profile(&self) -> &StreamProfile {
         &self.frame_stream_profile
@@ -244,6 +252,44 @@ impl<K> FrameEx for MotionFrame<K> {
     }
 }
 
+impl<K> MotionFrame<K> {
+    /// Get the frame timestamp expressed in the `target_domain` timestamp domain.
+    ///
+    /// Motion frames are often delivered in a different timestamp domain than the image frames
+    /// they are fused with (e.g. [`HardwareClock`](Rs2TimestampDomain::HardwareClock) for the IMU
+    /// vs. [`GlobalTime`](Rs2TimestampDomain::GlobalTime) for video). This returns the timestamp
+    /// (in milliseconds) in the requested domain when a conversion is available:
+    ///
+    /// - If the frame is already in `target_domain`, the frame timestamp is returned as-is.
+    /// - [`HardwareClock`](Rs2TimestampDomain::HardwareClock) timestamps are taken from the
+    ///   [`FrameTimestamp`](Rs2FrameMetadata::FrameTimestamp) metadata, if it is supported.
+    /// - [`SystemTime`](Rs2TimestampDomain::SystemTime) timestamps are taken from the
+    ///   [`TimeOfArrival`](Rs2FrameMetadata::TimeOfArrival) metadata, if it is supported. This is
+    ///   the host time at which the frame arrived, which is how librealsense2 timestamps frames in
+    ///   the system time domain.
+    ///
+    /// Returns `None` when the offset between the two domains is not known. This is always the
+    /// case for a [`GlobalTime`](Rs2TimestampDomain::GlobalTime) target, since the mapping of the
+    /// device clock onto the host clock is internal to librealsense2.
+    pub fn synchronized_timestamp(&self, target_domain: Rs2TimestampDomain) -> Option<f64> {
+        if self.timestamp_domain == target_domain {
+            return Some(self.timestamp);
+        }
+
+        match target_domain {
+            Rs2TimestampDomain::HardwareClock => self
+                .metadata(Rs2FrameMetadata::FrameTimestamp)
+                // Frame timestamp metadata is reported in microseconds.
+                .map(|usec| usec as f64 / 1000.0),
+            Rs2TimestampDomain::SystemTime => self
+                .metadata(Rs2FrameMetadata::TimeOfArrival)
+                // Time of arrival metadata is reported in milliseconds.
+                .map(|msec| msec as f64),
+            Rs2TimestampDomain::GlobalTime => None,
+        }
+    }
+}
+
 impl AccelFrame {
     /// Returns a 3-item array representing the sensor motion recorded in the Accel frame.
     ///
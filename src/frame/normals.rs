@@ -0,0 +1,233 @@
+//! Estimation of surface normals from depth frames.
+
+use super::{image::DepthFrame, prelude::DepthError};
+use crate::base::Rs2Intrinsics;
+
+/// The window size used by [`DepthFrame::compute_normals`].
+const DEFAULT_WINDOW_SIZE: usize = 3;
+
+/// An owned map of surface normals, with one normal per pixel of the depth frame it was computed
+/// from.
+///
+/// Normals are unit vectors in the coordinate system of the depth camera (x right, y down, z
+/// forward), oriented towards the camera. Pixels for which no normal could be estimated hold
+/// `[NAN, NAN, NAN]`.
+#[derive(Debug, Clone)]
+pub struct NormalMap {
+    /// The width of the map in pixels.
+    width: usize,
+    /// The height of the map in pixels.
+    height: usize,
+    /// Tightly packed, row-major normals.
+    normals: Vec<[f32; 3]>,
+}
+
+impl NormalMap {
+    /// Get the width of the map in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get the height of the map in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the tightly packed, row-major normals.
+    pub fn normals(&self) -> &[[f32; 3]] {
+        &self.normals
+    }
+
+    /// Consume the map and return the underlying normals.
+    pub fn into_normals(self) -> Vec<[f32; 3]> {
+        self.normals
+    }
+
+    /// Given a row and column index, get the normal at that pixel.
+    ///
+    /// Returns `None` if the index is out of bounds.
+    pub fn get(&self, col: usize, row: usize) -> Option<[f32; 3]> {
+        if col >= self.width || row >= self.height {
+            return None;
+        }
+
+        Some(self.normals[row * self.width + col])
+    }
+}
+
+impl DepthFrame {
+    /// Estimate the surface normal at every pixel of the frame, using a 3x3 window.
+    ///
+    /// See [`DepthFrame::compute_normals_with_window`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DepthError::UnsupportedFormat`] if the frame is not in the
+    /// [`Z16`](crate::kind::Rs2Format::Z16) format.
+    pub fn compute_normals(
+        &self,
+        intrinsics: &Rs2Intrinsics,
+        depth_unit: f32,
+    ) -> Result<NormalMap, DepthError> {
+        self.compute_normals_with_window(intrinsics, depth_unit, DEFAULT_WINDOW_SIZE)
+    }
+
+    /// Estimate the surface normal at every pixel of the frame.
+    ///
+    /// Each pixel and the pixels at the edges of a `window_size` x `window_size` window around it
+    /// are deprojected into 3D with `intrinsics` (ignoring distortion) and `depth_unit`, see
+    /// [`DepthFrame::depth_units`]. The normal is the cross product of the vertical and horizontal
+    /// differences between those points. Larger windows are less sensitive to depth noise, but
+    /// blur the normals across edges.
+    ///
+    /// Pixels without a valid depth, with an invalid neighbour, or closer than half a window to
+    /// the border of the frame get a normal of `[NAN, NAN, NAN]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DepthError::UnsupportedFormat`] if the frame is not in the
+    /// [`Z16`](crate::kind::Rs2Format::Z16) format.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window_size` is not an odd number of at least 3.
+    pub fn compute_normals_with_window(
+        &self,
+        intrinsics: &Rs2Intrinsics,
+        depth_unit: f32,
+        window_size: usize,
+    ) -> Result<NormalMap, DepthError> {
+        assert!(
+            window_size >= 3 && window_size % 2 == 1,
+            "normal window size must be odd and at least 3"
+        );
+
+        let rows: Vec<&[u16]> = self.z16_rows()?.collect();
+        Ok(NormalMap {
+            width: self.width(),
+            height: self.height(),
+            normals: compute_normals(&rows, intrinsics, depth_unit, window_size / 2),
+        })
+    }
+}
+
+/// Estimate normals from `rows` of raw depth values, using neighbours `radius` pixels away.
+fn compute_normals(
+    rows: &[&[u16]],
+    intrinsics: &Rs2Intrinsics,
+    depth_unit: f32,
+    radius: usize,
+) -> Vec<[f32; 3]> {
+    let height = rows.len();
+    let width = rows.first().map_or(0, |row| row.len());
+
+    let deproject = |col: usize, row: usize| -> Option<[f32; 3]> {
+        let depth = rows[row][col];
+        if depth == 0 {
+            return None;
+        }
+
+        let z = depth as f32 * depth_unit;
+        Some([
+            (col as f32 - intrinsics.ppx()) / intrinsics.fx() * z,
+            (row as f32 - intrinsics.ppy()) / intrinsics.fy() * z,
+            z,
+        ])
+    };
+
+    let normal_at = |col: usize, row: usize| -> Option<[f32; 3]> {
+        if col < radius || row < radius || col + radius >= width || row + radius >= height {
+            return None;
+        }
+
+        deproject(col, row)?;
+        let left = deproject(col - radius, row)?;
+        let right = deproject(col + radius, row)?;
+        let up = deproject(col, row - radius)?;
+        let down = deproject(col, row + radius)?;
+
+        let horizontal = sub(right, left);
+        let vertical = sub(down, up);
+
+        // With y pointing down, vertical x horizontal points back towards the camera.
+        normalize(cross(vertical, horizontal))
+    };
+
+    let mut normals = Vec::with_capacity(width * height);
+    for row in 0..height {
+        for col in 0..width {
+            normals.push(normal_at(col, row).unwrap_or([f32::NAN; 3]));
+        }
+    }
+    normals
+}
+
+/// Difference `a - b` of two vectors.
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+/// Cross product `a x b` of two vectors.
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/// Scale a vector to unit length, or `None` if it has no length.
+fn normalize(v: [f32; 3]) -> Option<[f32; 3]> {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > f32::EPSILON {
+        Some([v[0] / norm, v[1] / norm, v[2] / norm])
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use realsense_sys as sys;
+
+    fn intrinsics() -> Rs2Intrinsics {
+        Rs2Intrinsics(sys::rs2_intrinsics {
+            width: 5,
+            height: 5,
+            ppx: 2.0,
+            ppy: 2.0,
+            fx: 100.0,
+            fy: 100.0,
+            model: sys::rs2_distortion_RS2_DISTORTION_NONE,
+            coeffs: [0.0; 5],
+        })
+    }
+
+    #[test]
+    fn plane_facing_camera_has_normal_towards_camera() {
+        let row: &[u16] = &[1000; 5];
+        let normals = compute_normals(&[row; 5], &intrinsics(), 0.001, 1);
+
+        let center = normals[2 * 5 + 2];
+        assert!(center[0].abs() < 1e-6);
+        assert!(center[1].abs() < 1e-6);
+        assert!((center[2] + 1.0).abs() < 1e-6);
+
+        // Border pixels do not have a full window.
+        assert!(normals[0].iter().all(|v| v.is_nan()));
+    }
+
+    #[test]
+    fn invalid_depth_has_no_normal() {
+        let row: &[u16] = &[1000; 5];
+        let hole: &[u16] = &[1000, 1000, 0, 1000, 1000];
+        let normals = compute_normals(&[row, row, hole, row, row], &intrinsics(), 0.001, 1);
+
+        // The hole itself and its direct neighbours have no normal.
+        for &(col, row) in [(2, 2), (1, 2), (2, 1)].iter() {
+            assert!(normals[row * 5 + col].iter().all(|v| v.is_nan()));
+        }
+        assert!(!normals[5 + 1][2].is_nan());
+    }
+}
@@ -108,10 +108,132 @@ pub enum PixelKind<'a> {
         /// The Z-coordinate
         z: &'a f32,
     },
+    /// A pixel in a format whose layout is not known to this crate, such as
+    /// [`Raw10`](Rs2Format::Raw10) or [`Inzi`](Rs2Format::Inzi).
+    ///
+    /// The raw frame data can still be read with
+    /// [`ImageFrame::as_u8_slice`](crate::frame::ImageFrame::as_u8_slice).
+    Unsupported {
+        /// The format of the frame the pixel belongs to.
+        format: Rs2Format,
+    },
+}
+
+impl<'a> PixelKind<'a> {
+    /// Get the depth of this pixel in metres.
+    ///
+    /// [`Z16`](PixelKind::Z16) values are scaled by `depth_units`, which can be acquired from
+    /// [`DepthFrame::depth_units`](crate::frame::DepthFrame::depth_units).
+    /// [`Distance`](PixelKind::Distance) values are already in metres and are returned as-is.
+    ///
+    /// Returns `None` for every other pixel kind.
+    pub fn depth_meters(&self, depth_units: f32) -> Option<f32> {
+        match self {
+            PixelKind::Z16 { depth } => Some(**depth as f32 * depth_units),
+            PixelKind::Distance { distance } => Some(**distance),
+            _ => None,
+        }
+    }
+
+    /// Get the luminance of a grayscale pixel as a 16-bit value.
+    ///
+    /// [`Y8`](PixelKind::Y8) values are widened without rescaling.
+    ///
+    /// Returns `None` for anything other than [`Y8`](PixelKind::Y8) or [`Y16`](PixelKind::Y16).
+    pub fn luminance_u16(&self) -> Option<u16> {
+        match self {
+            PixelKind::Y8 { y } => Some(u16::from(**y)),
+            PixelKind::Y16 { y } => Some(**y),
+            _ => None,
+        }
+    }
+
+    /// Get the luminance of a grayscale pixel normalized to the range `[0.0, 1.0]`.
+    ///
+    /// Returns `None` for anything other than [`Y8`](PixelKind::Y8) or [`Y16`](PixelKind::Y16).
+    pub fn luminance_f32(&self) -> Option<f32> {
+        match self {
+            PixelKind::Y8 { y } => Some(f32::from(**y) / f32::from(u8::MAX)),
+            PixelKind::Y16 { y } => Some(f32::from(**y) / f32::from(u16::MAX)),
+            _ => None,
+        }
+    }
+
+    /// Convert the pixel to 8-bit red, green and blue channels.
+    ///
+    /// [`Yuyv`](PixelKind::Yuyv) and [`Uyvy`](PixelKind::Uyvy) pixels are converted with the
+    /// BT.601 full range YUV to RGB conversion. Color channels are reordered and alpha is
+    /// discarded. Grayscale pixels are replicated to every channel, with [`Y16`](PixelKind::Y16)
+    /// values reduced to their most significant byte.
+    ///
+    /// Returns `None` for [`Raw8`](PixelKind::Raw8) and [`Unsupported`](PixelKind::Unsupported)
+    /// pixels, whose color layout is unknown, and for depth, disparity and 3D coordinate pixels,
+    /// which have no meaningful color.
+    #[inline]
+    pub fn to_rgb8(&self) -> Option<[u8; 3]> {
+        match self {
+            PixelKind::Yuyv { y, u, v } | PixelKind::Uyvy { y, u, v } => {
+                Some(yuv_to_rgb8(**y, **u, **v))
+            }
+            PixelKind::Bgr8 { b, g, r } | PixelKind::Bgra8 { b, g, r, .. } => Some([**r, **g, **b]),
+            PixelKind::Rgb8 { r, g, b } | PixelKind::Rgba8 { r, g, b, .. } => Some([**r, **g, **b]),
+            PixelKind::Y8 { y } => Some([**y; 3]),
+            PixelKind::Y16 { y } => Some([(**y >> 8) as u8; 3]),
+            PixelKind::Raw8 { .. }
+            | PixelKind::Z16 { .. }
+            | PixelKind::Distance { .. }
+            | PixelKind::Disparity32 { .. }
+            | PixelKind::Xyz32f { .. }
+            | PixelKind::Unsupported { .. } => None,
+        }
+    }
+
+    /// Get the raw 16-bit value of a [`Z16`](PixelKind::Z16) or [`Y16`](PixelKind::Y16) pixel.
+    ///
+    /// Unlike [`PixelKind::depth_meters`], depth values are not scaled.
+    ///
+    /// Returns `None` for every other pixel kind.
+    #[inline]
+    pub fn to_gray_u16(&self) -> Option<u16> {
+        match self {
+            PixelKind::Z16 { depth } => Some(**depth),
+            PixelKind::Y16 { y } => Some(**y),
+            PixelKind::Yuyv { .. }
+            | PixelKind::Uyvy { .. }
+            | PixelKind::Bgr8 { .. }
+            | PixelKind::Bgra8 { .. }
+            | PixelKind::Rgb8 { .. }
+            | PixelKind::Rgba8 { .. }
+            | PixelKind::Raw8 { .. }
+            | PixelKind::Y8 { .. }
+            | PixelKind::Distance { .. }
+            | PixelKind::Disparity32 { .. }
+            | PixelKind::Xyz32f { .. }
+            | PixelKind::Unsupported { .. } => None,
+        }
+    }
+}
+
+/// Convert a full range BT.601 YUV value into 8-bit red, green and blue channels.
+#[inline]
+fn yuv_to_rgb8(y: u8, u: u8, v: u8) -> [u8; 3] {
+    let y = f32::from(y);
+    let u = f32::from(u) - 128.0;
+    let v = f32::from(v) - 128.0;
+
+    // Float to integer casts saturate, so out of range values are clamped to 0 and 255.
+    [
+        (y + 1.402 * v).round() as u8,
+        (y - 0.344_136 * u - 0.714_136 * v).round() as u8,
+        (y + 1.772 * u).round() as u8,
+    ]
 }
 
 /// Method to retrieve a pixel from a given rs2_frame in the requested Pixel format.
 ///
+/// Formats without a defined pixel layout produce [`PixelKind::Unsupported`], without reading
+/// from `data`.
+///
 /// # Safety
 ///
 /// This method should only be called from the ImageFrame types themselves, as this
@@ -302,8 +424,294 @@ pub(crate) unsafe fn get_pixel<'a>(
                 z: slice.get_unchecked(offset + 2),
             }
         }
-        _ => {
-            panic!("Unsupported video format.");
+        _ => PixelKind::Unsupported { format },
+    }
+}
+
+/// Iterate over the pixels of a single row of a frame that is `width` pixels wide.
+///
+/// Each pixel is looked up with [`get_pixel`], so this is a convenience over indexing the row
+/// column by column rather than a faster way of reading it.
+///
+/// # Safety
+///
+/// The same requirements as for [`get_pixel`] apply, for every column below `width` in `row`.
+pub(crate) unsafe fn row_pixels<'a>(
+    format: Rs2Format,
+    data_size_in_bytes: usize,
+    data: *const c_void,
+    stride_in_bytes: usize,
+    width: usize,
+    row: usize,
+) -> impl Iterator<Item = PixelKind<'a>> {
+    (0..width).map(move |col| unsafe {
+        get_pixel(format, data_size_in_bytes, data, stride_in_bytes, col, row)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unsupported_formats_yield_unsupported_pixels() {
+        // A 4x2 Raw10 image, which packs four 10-bit pixels into five bytes.
+        let (width, height, stride) = (4, 2, 5);
+        let data = vec![0xAAu8; stride * height];
+
+        for row in 0..height {
+            for col in 0..width {
+                let pixel = unsafe {
+                    get_pixel(
+                        Rs2Format::Raw10,
+                        data.len(),
+                        data.as_ptr().cast(),
+                        stride,
+                        col,
+                        row,
+                    )
+                };
+
+                assert!(matches!(
+                    pixel,
+                    PixelKind::Unsupported {
+                        format: Rs2Format::Raw10
+                    }
+                ));
+                assert_eq!(pixel.to_rgb8(), None);
+                assert_eq!(pixel.to_gray_u16(), None);
+            }
+        }
+    }
+
+    #[test]
+    fn row_pixels_skip_row_padding() {
+        // A 3x2 Z16 image, with every row padded to four pixels.
+        let (width, stride) = (3, 4 * std::mem::size_of::<u16>());
+        let data: Vec<u16> = vec![1, 2, 3, 0xFFFF, 4, 5, 6, 0xFFFF];
+
+        let depths = |row| {
+            unsafe {
+                row_pixels(
+                    Rs2Format::Z16,
+                    data.len() * std::mem::size_of::<u16>(),
+                    data.as_ptr().cast(),
+                    stride,
+                    width,
+                    row,
+                )
+            }
+            .map(|pixel| pixel.to_gray_u16().unwrap())
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(depths(0), vec![1, 2, 3]);
+        assert_eq!(depths(1), vec![4, 5, 6]);
+    }
+
+    #[test]
+    fn depth_meters_scales_z16_and_passes_distance() {
+        let depth = 1000u16;
+        let distance = 1.5f32;
+        let y = 10u8;
+
+        assert_eq!(
+            PixelKind::Z16 { depth: &depth }.depth_meters(0.001),
+            Some(1.0)
+        );
+        assert_eq!(
+            PixelKind::Distance {
+                distance: &distance
+            }
+            .depth_meters(0.001),
+            Some(1.5)
+        );
+        assert_eq!(PixelKind::Y8 { y: &y }.depth_meters(0.001), None);
+    }
+
+    #[test]
+    fn luminance_is_extracted_from_grayscale_pixels() {
+        let y8 = u8::MAX;
+        let y16 = u16::MAX;
+        let depth = 7u16;
+
+        assert_eq!(PixelKind::Y8 { y: &y8 }.luminance_u16(), Some(255));
+        assert_eq!(PixelKind::Y16 { y: &y16 }.luminance_u16(), Some(u16::MAX));
+        assert_eq!(PixelKind::Y8 { y: &y8 }.luminance_f32(), Some(1.0));
+        assert_eq!(PixelKind::Y16 { y: &y16 }.luminance_f32(), Some(1.0));
+        assert_eq!(PixelKind::Z16 { depth: &depth }.luminance_u16(), None);
+        assert_eq!(PixelKind::Z16 { depth: &depth }.luminance_f32(), None);
+    }
+
+    #[test]
+    fn to_rgb8_converts_every_color_and_grayscale_kind() {
+        let (r, g, b, a) = (10u8, 20u8, 30u8, 255u8);
+        let (y8, y16) = (42u8, 0x1234u16);
+        let (y, neutral) = (100u8, 128u8);
+
+        assert_eq!(
+            PixelKind::Rgb8 {
+                r: &r,
+                g: &g,
+                b: &b
+            }
+            .to_rgb8(),
+            Some([10, 20, 30])
+        );
+        assert_eq!(
+            PixelKind::Rgba8 {
+                r: &r,
+                g: &g,
+                b: &b,
+                a: &a
+            }
+            .to_rgb8(),
+            Some([10, 20, 30])
+        );
+        assert_eq!(
+            PixelKind::Bgr8 {
+                b: &b,
+                g: &g,
+                r: &r
+            }
+            .to_rgb8(),
+            Some([10, 20, 30])
+        );
+        assert_eq!(
+            PixelKind::Bgra8 {
+                b: &b,
+                g: &g,
+                r: &r,
+                a: &a
+            }
+            .to_rgb8(),
+            Some([10, 20, 30])
+        );
+        assert_eq!(PixelKind::Y8 { y: &y8 }.to_rgb8(), Some([42, 42, 42]));
+        assert_eq!(
+            PixelKind::Y16 { y: &y16 }.to_rgb8(),
+            Some([0x12, 0x12, 0x12])
+        );
+
+        // Neutral chroma leaves only the luma.
+        assert_eq!(
+            PixelKind::Yuyv {
+                y: &y,
+                u: &neutral,
+                v: &neutral
+            }
+            .to_rgb8(),
+            Some([100, 100, 100])
+        );
+        assert_eq!(
+            PixelKind::Uyvy {
+                y: &y,
+                u: &neutral,
+                v: &neutral
+            }
+            .to_rgb8(),
+            Some([100, 100, 100])
+        );
+    }
+
+    #[test]
+    fn yuv_to_rgb8_clamps_saturated_chroma() {
+        assert_eq!(yuv_to_rgb8(255, 0, 255), [255, 208, 28]);
+        assert_eq!(yuv_to_rgb8(0, 255, 0), [0, 48, 225]);
+    }
+
+    #[test]
+    fn to_rgb8_is_none_for_raw_depth_and_coordinate_kinds() {
+        let (raw, depth) = (1u8, 1000u16);
+        let (distance, disparity) = (1.5f32, 2.5f32);
+        let (x, y, z) = (0.1f32, 0.2f32, 0.3f32);
+
+        assert_eq!(PixelKind::Raw8 { val: &raw }.to_rgb8(), None);
+        assert_eq!(PixelKind::Z16 { depth: &depth }.to_rgb8(), None);
+        assert_eq!(
+            PixelKind::Distance {
+                distance: &distance
+            }
+            .to_rgb8(),
+            None
+        );
+        assert_eq!(
+            PixelKind::Disparity32 {
+                disparity: &disparity
+            }
+            .to_rgb8(),
+            None
+        );
+        assert_eq!(
+            PixelKind::Xyz32f {
+                x: &x,
+                y: &y,
+                z: &z
+            }
+            .to_rgb8(),
+            None
+        );
+    }
+
+    #[test]
+    fn to_gray_u16_is_only_some_for_z16_and_y16() {
+        let (c, y8, raw) = (1u8, 2u8, 3u8);
+        let (y16, depth) = (0x1234u16, 1000u16);
+        let (distance, disparity) = (1.5f32, 2.5f32);
+        let (x, y, z) = (0.1f32, 0.2f32, 0.3f32);
+
+        assert_eq!(PixelKind::Z16 { depth: &depth }.to_gray_u16(), Some(1000));
+        assert_eq!(PixelKind::Y16 { y: &y16 }.to_gray_u16(), Some(0x1234));
+
+        let others = [
+            PixelKind::Yuyv {
+                y: &c,
+                u: &c,
+                v: &c,
+            },
+            PixelKind::Uyvy {
+                y: &c,
+                u: &c,
+                v: &c,
+            },
+            PixelKind::Bgr8 {
+                b: &c,
+                g: &c,
+                r: &c,
+            },
+            PixelKind::Bgra8 {
+                b: &c,
+                g: &c,
+                r: &c,
+                a: &c,
+            },
+            PixelKind::Rgb8 {
+                r: &c,
+                g: &c,
+                b: &c,
+            },
+            PixelKind::Rgba8 {
+                r: &c,
+                g: &c,
+                b: &c,
+                a: &c,
+            },
+            PixelKind::Raw8 { val: &raw },
+            PixelKind::Y8 { y: &y8 },
+            PixelKind::Distance {
+                distance: &distance,
+            },
+            PixelKind::Disparity32 {
+                disparity: &disparity,
+            },
+            PixelKind::Xyz32f {
+                x: &x,
+                y: &y,
+                z: &z,
+            },
+        ];
+        for pixel in &others {
+            assert_eq!(pixel.to_gray_u16(), None, "{:?}", pixel);
         }
     }
 }
@@ -2,7 +2,11 @@
 //!
 //! A Points frame is a RealSense point cloud storage class.
 
-use super::prelude::{CouldNotGetFrameSensorError, FrameCategory, FrameConstructionError, FrameEx};
+use super::image::ColorFrame;
+use super::pixel::PixelKind;
+use super::prelude::{
+    private::RawFrame, CouldNotGetFrameSensorError, FrameCategory, FrameConstructionError, FrameEx,
+};
 use crate::{
     check_rs2_error,
     kind::{Rs2Extension, Rs2FrameMetadata, Rs2StreamKind, Rs2TimestampDomain},
@@ -13,11 +17,32 @@ use anyhow::Result;
 use num_traits::FromPrimitive;
 use realsense_sys as sys;
 use std::{
+    collections::HashMap,
     convert::TryInto,
+    fs::File,
+    io::{self, BufWriter, Write},
+    mem,
+    path::Path,
     ptr::{self, NonNull},
     slice,
 };
 
+// `vertices_raw_ptr` hands out vertex data as a flat array of floats, which is only sound if an
+// `rs2_vertex` is exactly three packed `f32`s.
+const _: () = assert!(
+    mem::size_of::<sys::rs2_vertex>() == mem::size_of::<[f32; 3]>()
+        && mem::align_of::<sys::rs2_vertex>() == mem::align_of::<f32>()
+);
+
+/// The encoding of the vertex data in a PLY file written by [`PointsFrame::export_to_ply`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlyFormat {
+    /// Human readable vertices, one per line.
+    Ascii,
+    /// Packed little-endian vertices, which are considerably faster to write and read.
+    BinaryLittleEndian,
+}
+
 /// Holds the raw data pointer and derived data for an RS2 Points frame.
 ///
 /// All fields in this struct are initialized during struct creation (via `try_from`).
@@ -60,6 +85,12 @@ impl FrameCategory for PointsFrame {
     }
 }
 
+impl RawFrame for PointsFrame {
+    fn raw_frame_ptr(&self) -> NonNull<sys::rs2_frame> {
+        self.frame_ptr
+    }
+}
+
 impl FrameEx for PointsFrame {
     fn stream_profile(&self) -> &StreamProfile {
         &self.frame_stream_profile
@@ -229,6 +260,23 @@ impl PointsFrame {
         }
     }
 
+    /// Get a raw pointer to the vertex data, along with the number of `f32` values it points to.
+    ///
+    /// The vertices are laid out as contiguous `[x, y, z]` triples, so the returned count is three
+    /// times [`points_count`](PointsFrame::points_count). This is intended for handing the point
+    /// cloud to C or C++ libraries (PCL, Open3D, OpenCV) without copying it.
+    ///
+    /// # Safety
+    ///
+    /// The pointer is only valid for as long as this frame is alive, and must only be used to
+    /// read data. The caller is responsible for not dereferencing it after the frame is dropped.
+    pub unsafe fn vertices_raw_ptr(&self) -> (*const f32, usize) {
+        (
+            self.vertices_data_ptr.as_ptr().cast::<f32>() as *const f32,
+            self.num_points * 3,
+        )
+    }
+
     /// Retrieve the texture coordinates (uv map) for the point cloud.
     ///
     /// # Safety
@@ -247,10 +295,225 @@ impl PointsFrame {
         }
     }
 
+    /// Get the color of the vertex at `index` by sampling `color_frame` at its texture coordinate.
+    ///
+    /// The texture coordinate is mapped to the nearest pixel of `color_frame`, and the color is
+    /// returned as `[r, g, b]`. This assumes `color_frame` is the frame the point cloud was
+    /// textured with when it was computed.
+    ///
+    /// Returns `None` if `index` is out of bounds, if the vertex has no corresponding pixel in the
+    /// color frame, or if the color frame is not in the [`Rgb8`](crate::kind::Rs2Format::Rgb8) or
+    /// [`Bgr8`](crate::kind::Rs2Format::Bgr8) format.
+    pub fn color_at(&self, index: usize, color_frame: &ColorFrame) -> Option<[u8; 3]> {
+        let uv = self.texture_coordinates().get(index)?;
+        let (col, row) = texture_pixel(*uv, color_frame.width(), color_frame.height())?;
+
+        match color_frame.get(col, row)? {
+            PixelKind::Rgb8 { r, g, b } | PixelKind::Bgr8 { r, g, b } => Some([*r, *g, *b]),
+            _ => None,
+        }
+    }
+
     /// Gets number of points in the point cloud.
     pub fn points_count(&self) -> usize {
         self.num_points
     }
+
+    /// Computes the axis-aligned bounding box of the point cloud.
+    ///
+    /// Returns `(min_xyz, max_xyz)` over all valid vertices. Vertices at the origin `(0, 0, 0)`
+    /// are treated as invalid (librealsense2 emits these for pixels without depth) and are
+    /// ignored. Returns `None` if there are no valid vertices.
+    pub fn bounding_box(&self) -> Option<([f32; 3], [f32; 3])> {
+        bounding_box(self.vertices().iter().map(|v| v.xyz))
+    }
+
+    /// Downsample the point cloud with a voxel grid filter.
+    ///
+    /// Space is partitioned into cubic voxels with sides of `voxel_size_m` meters, and all
+    /// vertices within a voxel are replaced by their centroid. Vertices at the origin are treated
+    /// as invalid and are ignored, as in [`bounding_box`](PointsFrame::bounding_box). Voxels are
+    /// returned in the order in which they are first encountered.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `voxel_size_m` is not positive.
+    pub fn downsample_voxel(&self, voxel_size_m: f32) -> Vec<[f32; 3]> {
+        downsample_voxel(self.vertices().iter().map(|v| v.xyz), voxel_size_m)
+    }
+
+    /// Write the point cloud to a PLY file at `path`.
+    ///
+    /// Vertices with a Z coordinate of zero have no depth and are skipped. If `texture_frame` is
+    /// provided, each vertex is colored by sampling it at the vertex's texture coordinate and
+    /// converting the pixel with [`PixelKind::to_rgb8`]. Unlike
+    /// [`color_at`](PointsFrame::color_at), this accepts any color format `to_rgb8` supports, e.g.
+    /// YUYV or RGBA8. Vertices without a corresponding pixel, or whose pixel cannot be converted,
+    /// are written as black. Otherwise only the XYZ coordinates are written.
+    ///
+    /// The file is written directly, without going through librealsense2, whose PLY export is only
+    /// available from C++.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be created or written to.
+    pub fn export_to_ply<P>(
+        &self,
+        path: P,
+        texture_frame: Option<&ColorFrame>,
+        format: PlyFormat,
+    ) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let textured_points = self
+            .vertices()
+            .iter()
+            .zip(self.texture_coordinates())
+            .filter(|(v, _)| v.xyz[2] != 0.0);
+
+        let (points, colors): (Vec<[f32; 3]>, Option<Vec<[u8; 3]>>) = match texture_frame {
+            Some(frame) => {
+                let (points, colors) = textured_points
+                    .map(|(v, uv)| {
+                        let color = texture_pixel(*uv, frame.width(), frame.height())
+                            .and_then(|(col, row)| frame.get(col, row)?.to_rgb8())
+                            .unwrap_or([0; 3]);
+                        (v.xyz, color)
+                    })
+                    .unzip();
+                (points, Some(colors))
+            }
+            None => (textured_points.map(|(v, _)| v.xyz).collect(), None),
+        };
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        write_ply(&mut writer, &points, colors.as_deref(), format)?;
+        writer.flush()?;
+
+        Ok(())
+    }
+}
+
+/// Writes `points`, and optionally a color per point, to `writer` in the PLY format.
+fn write_ply<W: Write>(
+    writer: &mut W,
+    points: &[[f32; 3]],
+    colors: Option<&[[u8; 3]]>,
+    format: PlyFormat,
+) -> io::Result<()> {
+    let format_name = match format {
+        PlyFormat::Ascii => "ascii",
+        PlyFormat::BinaryLittleEndian => "binary_little_endian",
+    };
+
+    writeln!(writer, "ply")?;
+    writeln!(writer, "format {} 1.0", format_name)?;
+    writeln!(writer, "comment generated by realsense-rust")?;
+    writeln!(writer, "element vertex {}", points.len())?;
+    writeln!(writer, "property float x")?;
+    writeln!(writer, "property float y")?;
+    writeln!(writer, "property float z")?;
+    if colors.is_some() {
+        writeln!(writer, "property uchar red")?;
+        writeln!(writer, "property uchar green")?;
+        writeln!(writer, "property uchar blue")?;
+    }
+    writeln!(writer, "end_header")?;
+
+    for (i, [x, y, z]) in points.iter().enumerate() {
+        let color = colors.map(|colors| colors[i]);
+
+        match format {
+            PlyFormat::Ascii => {
+                write!(writer, "{} {} {}", x, y, z)?;
+                if let Some([r, g, b]) = color {
+                    write!(writer, " {} {} {}", r, g, b)?;
+                }
+                writeln!(writer)?;
+            }
+            PlyFormat::BinaryLittleEndian => {
+                writer.write_all(&x.to_le_bytes())?;
+                writer.write_all(&y.to_le_bytes())?;
+                writer.write_all(&z.to_le_bytes())?;
+                if let Some(rgb) = color {
+                    writer.write_all(&rgb)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Maps a texture coordinate to the nearest pixel of a `width` x `height` image.
+///
+/// Returns `None` if the coordinate lies outside of the image.
+fn texture_pixel(uv: [f32; 2], width: usize, height: usize) -> Option<(usize, usize)> {
+    let [u, v] = uv;
+    if !(0.0..1.0).contains(&u) || !(0.0..1.0).contains(&v) {
+        return None;
+    }
+
+    Some(((u * width as f32) as usize, (v * height as f32) as usize))
+}
+
+/// Computes the axis-aligned bounding box of all points that are not at the origin.
+fn bounding_box<I>(points: I) -> Option<([f32; 3], [f32; 3])>
+where
+    I: IntoIterator<Item = [f32; 3]>,
+{
+    points
+        .into_iter()
+        .filter(|p| *p != [0.0; 3])
+        .fold(None, |bounds, p| match bounds {
+            None => Some((p, p)),
+            Some((mut min, mut max)) => {
+                for i in 0..3 {
+                    min[i] = min[i].min(p[i]);
+                    max[i] = max[i].max(p[i]);
+                }
+                Some((min, max))
+            }
+        })
+}
+
+/// Replaces all points that are not at the origin by the centroids of the voxels they fall in.
+fn downsample_voxel<I>(points: I, voxel_size: f32) -> Vec<[f32; 3]>
+where
+    I: IntoIterator<Item = [f32; 3]>,
+{
+    assert!(voxel_size > 0.0, "Voxel size must be positive");
+
+    let mut voxel_indices = HashMap::<[i32; 3], usize>::new();
+    let mut voxels = Vec::<([f32; 3], usize)>::new();
+
+    for p in points.into_iter().filter(|p| *p != [0.0; 3]) {
+        let key = [
+            (p[0] / voxel_size).floor() as i32,
+            (p[1] / voxel_size).floor() as i32,
+            (p[2] / voxel_size).floor() as i32,
+        ];
+
+        let index = *voxel_indices.entry(key).or_insert_with(|| {
+            voxels.push(([0.0; 3], 0));
+            voxels.len() - 1
+        });
+
+        let (sum, count) = &mut voxels[index];
+        for i in 0..3 {
+            sum[i] += p[i];
+        }
+        *count += 1;
+    }
+
+    voxels
+        .into_iter()
+        .map(|(sum, count)| {
+            let n = count as f32;
+            [sum[0] / n, sum[1] / n, sum[2] / n]
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -261,4 +524,126 @@ mod tests {
     fn frame_has_correct_kind() {
         assert_eq!(PointsFrame::kind(), Rs2StreamKind::Any);
     }
+
+    #[test]
+    fn bounding_box_ignores_invalid_points() {
+        let points = [
+            [0.0, 0.0, 0.0],
+            [1.0, -2.0, 3.0],
+            [-1.0, 2.0, 0.5],
+            [0.0, 0.0, 0.0],
+        ];
+
+        assert_eq!(
+            bounding_box(points.iter().copied()),
+            Some(([-1.0, -2.0, 0.5], [1.0, 2.0, 3.0]))
+        );
+        assert_eq!(bounding_box(vec![[0.0; 3]; 4]), None);
+    }
+
+    #[test]
+    fn texture_pixel_maps_to_nearest_pixel() {
+        assert_eq!(texture_pixel([0.0, 0.0], 640, 480), Some((0, 0)));
+        assert_eq!(texture_pixel([0.5, 0.25], 640, 480), Some((320, 120)));
+        assert_eq!(texture_pixel([0.9999, 0.9999], 640, 480), Some((639, 479)));
+        assert_eq!(texture_pixel([1.0, 0.5], 640, 480), None);
+        assert_eq!(texture_pixel([0.5, -0.1], 640, 480), None);
+    }
+
+    #[test]
+    fn vertex_is_three_packed_floats() {
+        let vertices = [
+            sys::rs2_vertex {
+                xyz: [1.0, 2.0, 3.0],
+            },
+            sys::rs2_vertex {
+                xyz: [4.0, 5.0, 6.0],
+            },
+        ];
+
+        let floats = unsafe { slice::from_raw_parts(vertices.as_ptr().cast::<f32>(), 6) };
+        assert_eq!(floats, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    }
+
+    #[test]
+    fn downsample_voxel_keeps_centroids() {
+        let points = [
+            [0.01, 0.01, 0.01],
+            [0.03, 0.03, 0.05],
+            [0.0, 0.0, 0.0],
+            [0.15, 0.01, 0.01],
+            [-0.01, 0.01, 0.01],
+        ];
+
+        let downsampled = downsample_voxel(points.iter().copied(), 0.1);
+
+        assert_eq!(downsampled.len(), 3);
+        let expected = [[0.02, 0.02, 0.03], [0.15, 0.01, 0.01], [-0.01, 0.01, 0.01]];
+        for (point, expected) in downsampled.iter().zip(&expected) {
+            for i in 0..3 {
+                assert!((point[i] - expected[i]).abs() < 1e-6);
+            }
+        }
+    }
+
+    /// Splits PLY output into its header lines and the bytes following `end_header`.
+    fn split_ply(ply: &[u8]) -> (Vec<String>, &[u8]) {
+        let marker = b"end_header\n";
+        let end = ply.windows(marker.len()).position(|w| w == marker).unwrap() + marker.len();
+
+        let header = String::from_utf8(ply[..end].to_vec()).unwrap();
+        (header.lines().map(String::from).collect(), &ply[end..])
+    }
+
+    #[test]
+    fn ascii_ply_round_trips_vertices_and_colors() {
+        let points = [[1.0, -2.5, 3.0], [0.125, 0.25, 0.5]];
+        let colors = [[255, 0, 16], [1, 2, 3]];
+
+        let mut ply = Vec::new();
+        write_ply(&mut ply, &points, Some(&colors), PlyFormat::Ascii).unwrap();
+        let (header, body) = split_ply(&ply);
+
+        assert_eq!(header[1], "format ascii 1.0");
+        assert!(header.contains(&"element vertex 2".to_string()));
+        assert!(header.contains(&"property uchar red".to_string()));
+
+        let rows: Vec<Vec<f32>> = std::str::from_utf8(body)
+            .unwrap()
+            .lines()
+            .map(|line| line.split(' ').map(|v| v.parse().unwrap()).collect())
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                vec![1.0, -2.5, 3.0, 255.0, 0.0, 16.0],
+                vec![0.125, 0.25, 0.5, 1.0, 2.0, 3.0]
+            ]
+        );
+    }
+
+    #[test]
+    fn binary_ply_round_trips_vertices() {
+        let points = [[1.0, -2.5, 3.0], [0.125, 0.25, 0.5]];
+
+        let mut ply = Vec::new();
+        write_ply(&mut ply, &points, None, PlyFormat::BinaryLittleEndian).unwrap();
+        let (header, body) = split_ply(&ply);
+
+        assert_eq!(header[1], "format binary_little_endian 1.0");
+        assert!(!header.iter().any(|line| line.contains("uchar")));
+        assert_eq!(body.len(), points.len() * 12);
+
+        let decoded: Vec<[f32; 3]> = body
+            .chunks_exact(12)
+            .map(|vertex| {
+                let mut xyz = [0.0; 3];
+                for (i, value) in vertex.chunks_exact(4).enumerate() {
+                    xyz[i] = f32::from_le_bytes(value.try_into().unwrap());
+                }
+                xyz
+            })
+            .collect();
+        assert_eq!(decoded, points);
+    }
 }
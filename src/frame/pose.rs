@@ -4,7 +4,9 @@
 //! at a point in time. See the member and function declarations for how these values are stored
 //! and retrieved.
 
-use super::prelude::{CouldNotGetFrameSensorError, FrameCategory, FrameConstructionError, FrameEx};
+use super::prelude::{
+    private::RawFrame, CouldNotGetFrameSensorError, FrameCategory, FrameConstructionError, FrameEx,
+};
 use crate::{
     check_rs2_error,
     kind::{Rs2Extension, Rs2FrameMetadata, Rs2StreamKind, Rs2TimestampDomain},
@@ -41,6 +43,7 @@ pub struct PoseFrame {
 }
 
 /// Used by the tracker and mapper to estimate the certainty in this pose.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Confidence {
     /// The tracker/mapper has failed. This information is probably not reliable.
     Failed,
@@ -50,6 +53,20 @@ pub enum Confidence {
     Medium,
     /// The tracker/mapper confidence is high.
     High,
+    /// The tracker/mapper reported a confidence value that is not known to this crate.
+    Unknown(u32),
+}
+
+impl From<u32> for Confidence {
+    fn from(value: u32) -> Self {
+        match value {
+            0x0 => Confidence::Failed,
+            0x1 => Confidence::Low,
+            0x2 => Confidence::Medium,
+            0x3 => Confidence::High,
+            _ => Confidence::Unknown(value),
+        }
+    }
 }
 
 impl PoseFrame {
@@ -77,6 +94,31 @@ impl PoseFrame {
         [x, y, z, w]
     }
 
+    /// Translation of the pose as a [`glam::Vec3`], in meters.
+    ///
+    /// Only available with the `glam` feature enabled.
+    #[cfg(feature = "glam")]
+    pub fn translation_glam(&self) -> glam::Vec3 {
+        glam::Vec3::from(self.translation())
+    }
+
+    /// Rotation of the pose as a [`glam::Quat`].
+    ///
+    /// Only available with the `glam` feature enabled.
+    #[cfg(feature = "glam")]
+    pub fn rotation_glam(&self) -> glam::Quat {
+        glam::Quat::from_array(self.rotation())
+    }
+
+    /// The pose as a rigid transform in a [`glam::Mat4`], mapping points from the device frame
+    /// into the frame of the initial position.
+    ///
+    /// Only available with the `glam` feature enabled.
+    #[cfg(feature = "glam")]
+    pub fn to_mat4_glam(&self) -> glam::Mat4 {
+        glam::Mat4::from_rotation_translation(self.rotation_glam(), self.translation_glam())
+    }
+
     /// X, Y, Z values of angular velocity, in radians/sec
     pub fn angular_velocity(&self) -> [f32; 3] {
         let sys::rs2_vector { x, y, z } = self.data.angular_velocity;
@@ -90,25 +132,34 @@ impl PoseFrame {
     }
 
     /// Pose confidence from [`Confidence::Failed`] to [`Confidence::High`]
+    ///
+    /// Values not known to this crate are returned as [`Confidence::Unknown`].
     pub fn tracker_confidence(&self) -> Confidence {
-        match self.data.tracker_confidence {
-            0x0 => Confidence::Failed,
-            0x1 => Confidence::Low,
-            0x2 => Confidence::Medium,
-            0x3 => Confidence::High,
-            _ => panic!("Unknown confidence, please report a bug!"),
-        }
+        Confidence::from(self.data.tracker_confidence)
     }
 
     /// Pose map confidence from [`Confidence::Failed`] to [`Confidence::High`]
+    ///
+    /// Values not known to this crate are returned as [`Confidence::Unknown`].
     pub fn mapper_confidence(&self) -> Confidence {
-        match self.data.tracker_confidence {
-            0x0 => Confidence::Failed,
-            0x1 => Confidence::Low,
-            0x2 => Confidence::Medium,
-            0x3 => Confidence::High,
-            _ => panic!("Unknown confidence, please report a bug!"),
-        }
+        Confidence::from(self.data.mapper_confidence)
+    }
+}
+
+/// Conversions into `nalgebra` types.
+///
+/// Only available with the `nalgebra` feature enabled.
+#[cfg(feature = "nalgebra")]
+impl PoseFrame {
+    /// Rotation of the pose as a [`nalgebra::UnitQuaternion`], relative to the initial position.
+    ///
+    /// The rotation maps vectors from the current device frame into the frame of the initial
+    /// position. The T265 uses a right-handed frame, with x pointing right, y pointing up and z
+    /// pointing backwards, out of the back of the device. This differs from the camera frame of
+    /// D400 devices, where y points down and z points forward.
+    pub fn rotation_quaternion(&self) -> nalgebra::UnitQuaternion<f32> {
+        let sys::rs2_quaternion { x, y, z, w } = self.data.rotation;
+        nalgebra::UnitQuaternion::new_normalize(nalgebra::Quaternion::new(w, x, y, z))
     }
 }
 
@@ -196,6 +247,12 @@ impl TryFrom<NonNull<sys::rs2_frame>> for PoseFrame {
     }
 }
 
+impl RawFrame for PoseFrame {
+    fn raw_frame_ptr(&self) -> NonNull<sys::rs2_frame> {
+        self.frame_ptr
+    }
+}
+
 impl FrameEx for PoseFrame {
     fn stream_profile(&self) -> &StreamProfile {
         &self.frame_stream_profile
@@ -282,4 +339,11 @@ mod tests {
     fn frame_has_correct_kind() {
         assert_eq!(PoseFrame::kind(), Rs2StreamKind::Pose);
     }
+
+    #[test]
+    fn confidence_maps_unknown_values_without_panicking() {
+        assert_eq!(Confidence::from(0x0), Confidence::Failed);
+        assert_eq!(Confidence::from(0x3), Confidence::High);
+        assert_eq!(Confidence::from(0x7), Confidence::Unknown(0x7));
+    }
 }
@@ -5,13 +5,17 @@
 //! with the wildcard describing the specialization that goes with that type.
 
 use crate::{
-    kind::{Rs2Exception, Rs2Extension, Rs2FrameMetadata, Rs2StreamKind, Rs2TimestampDomain},
+    check_rs2_error,
+    kind::{
+        Rs2Exception, Rs2Extension, Rs2Format, Rs2FrameMetadata, Rs2StreamKind, Rs2TimestampDomain,
+    },
     sensor::Sensor,
-    stream_profile::StreamProfile,
+    stream_profile::{StreamConstructionError, StreamProfile},
 };
 use anyhow::Result;
+use num_traits::FromPrimitive;
 use realsense_sys as sys;
-use std::ptr::NonNull;
+use std::{collections::HashMap, convert::TryFrom, ptr::NonNull, time::Duration};
 use thiserror::Error;
 
 /// How many bits are in a byte? Who can truly say.
@@ -54,6 +58,14 @@ pub enum FrameConstructionError {
     /// Could not get the number of points in a Points frame.
     #[error("Could not get number of points: Type: {0}; Reason: {1}")]
     CouldNotGetPointCount(Rs2Exception, String),
+    /// Could not add a reference to the frame when cloning it.
+    #[error("Could not add frame reference. Type: {0}; Reason: {1}")]
+    CouldNotAddFrameReference(Rs2Exception, String),
+    /// The frame reported a data size too small to hold an image of its dimensions.
+    ///
+    /// Holds the width, height, bits per pixel and data size (in bytes) that were reported.
+    #[error("Frame data is corrupt. Width: {0}; Height: {1}; Bits per pixel: {2}; Data size: {3}")]
+    CorruptFrameData(usize, usize, usize, usize),
 }
 
 /// Occurs when certain data cannot be derived from a Depth frame.
@@ -65,6 +77,12 @@ pub enum DepthError {
     /// Cannot derive the depth units used.
     #[error("Could not get depth units. Type: {0}; Reason: {1}")]
     CouldNotGetDepthUnits(Rs2Exception, String),
+    /// The depth frame data is not in a format that can be read directly.
+    #[error("Depth frame data is in an unsupported format: {0:?}")]
+    UnsupportedFormat(Rs2Format),
+    /// A mask did not have one entry per pixel of the depth frame.
+    #[error("Mask has {1} entries but the frame has {0} pixels.")]
+    MaskSizeMismatch(usize, usize),
 }
 
 /// Occurs when a baseline cannot be derived from a Disparity frame.
@@ -77,8 +95,26 @@ pub struct DisparityError(pub Rs2Exception, pub String);
 #[error("Could not get frame sensor. Type: {0}; Reason: {1}")]
 pub struct CouldNotGetFrameSensorError(pub Rs2Exception, pub String);
 
+pub(crate) mod private {
+    //! Supertrait of [`FrameEx`](super::FrameEx) that is private to the crate.
+    //!
+    //! The trait is public so that it can bound `FrameEx`, but it cannot be named outside of the
+    //! crate, so `FrameEx` can only be implemented by the frame types defined here.
+
+    use realsense_sys as sys;
+    use std::ptr::NonNull;
+
+    /// Access to the frame pointer underlying a frame, for the provided methods of `FrameEx`.
+    pub trait RawFrame {
+        /// Get the frame pointer, which remains owned by the frame.
+        fn raw_frame_ptr(&self) -> NonNull<sys::rs2_frame>;
+    }
+}
+
 /// Describes common functionality across frame types.
-pub trait FrameEx {
+///
+/// This trait is sealed, and is only implemented by the frame types of this crate.
+pub trait FrameEx: private::RawFrame {
     /// Get the stream profile associated with the frame.
     fn stream_profile(&self) -> &StreamProfile;
 
@@ -86,6 +122,11 @@ pub trait FrameEx {
     fn sensor(&self) -> Result<Sensor>;
 
     /// Get the frame number.
+    ///
+    /// Frame numbers increase monotonically within a single stream, so a gap between consecutive
+    /// frames of the same stream indicates that frames were dropped. This is often more reliable
+    /// than comparing timestamps. The value is read when the frame is constructed, so calling this
+    /// does not cross the FFI boundary.
     fn frame_number(&self) -> u64;
 
     /// Get the frame timestamp.
@@ -102,6 +143,54 @@ pub trait FrameEx {
     /// Test whether the metadata arguemnt is supported by the frame.
     fn supports_metadata(&self, metadata_kind: Rs2FrameMetadata) -> bool;
 
+    /// Get all metadata supported by the frame.
+    ///
+    /// Returns a map from every [`Rs2FrameMetadata`] key supported by the frame to its value. This
+    /// is primarily useful for logging or for documenting which metadata a particular camera and
+    /// firmware combination provides.
+    fn all_metadata(&self) -> HashMap<Rs2FrameMetadata, std::os::raw::c_longlong> {
+        (0..sys::rs2_frame_metadata_value_RS2_FRAME_METADATA_COUNT as i32)
+            .filter_map(Rs2FrameMetadata::from_i32)
+            .filter_map(|kind| self.metadata(kind).map(|value| (kind, value)))
+            .collect()
+    }
+
+    /// Get the actual exposure time of the frame.
+    ///
+    /// Reads [`Rs2FrameMetadata::ActualExposure`], which is reported in microseconds. Returns
+    /// `None` if the metadata is not supported by the frame, or if it reports a negative exposure.
+    fn actual_exposure(&self) -> Option<Duration> {
+        self.metadata(Rs2FrameMetadata::ActualExposure)
+            .and_then(|micros| u64::try_from(micros).ok())
+            .map(Duration::from_micros)
+    }
+
+    /// Get whether auto-exposure was enabled when the frame was captured.
+    ///
+    /// Reads [`Rs2FrameMetadata::AutoExposure`]. Returns `None` if the metadata is not supported
+    /// by the frame.
+    fn auto_exposure_enabled(&self) -> Option<bool> {
+        self.metadata(Rs2FrameMetadata::AutoExposure)
+            .map(|enabled| enabled != 0)
+    }
+
+    /// Get the sensor gain level the frame was captured with.
+    ///
+    /// Reads [`Rs2FrameMetadata::GainLevel`]. Returns `None` if the metadata is not supported by
+    /// the frame.
+    fn gain_level(&self) -> Option<i64> {
+        self.metadata(Rs2FrameMetadata::GainLevel)
+    }
+
+    /// Get the white balance the frame was captured with, in Kelvin.
+    ///
+    /// Reads [`Rs2FrameMetadata::WhiteBalance`]. Returns `None` if the metadata is not supported
+    /// by the frame, or if its value is negative or too large to be a color temperature.
+    fn white_balance_kelvin(&self) -> Option<u32> {
+        self.metadata(Rs2FrameMetadata::WhiteBalance)
+            .and_then(|kelvin| u32::try_from(kelvin).ok())
+    }
+
     /// Get (and own) the underlying frame pointer for this frame.
     ///
     /// This is primarily useful for passing this frame forward to a processing block or blocks
@@ -113,6 +202,77 @@ pub trait FrameEx {
     /// goes out of scope. Instead, the program expects that whatever
     /// object was assigned to by this function now manages the lifetime.
     unsafe fn get_owned_raw(self) -> NonNull<sys::rs2_frame>;
+
+    /// Get the raw `rs2_frame` pointer underlying this frame.
+    ///
+    /// This allows calling into [`realsense_sys`] for functionality that is not yet wrapped by
+    /// this crate. The frame retains ownership of the pointer, so it must not be released, and it
+    /// must not be used after the frame is dropped.
+    ///
+    /// Only available with the `raw-access` feature enabled.
+    #[cfg(feature = "raw-access")]
+    fn frame_ptr(&self) -> NonNull<sys::rs2_frame> {
+        self.raw_frame_ptr()
+    }
+
+    /// Create another handle to the same frame, without copying the frame data.
+    ///
+    /// The underlying librealsense2 frame is reference counted. Each clone holds its own
+    /// reference, and that reference is released when the clone is dropped. This is useful for
+    /// holding on to a frame beyond the lifetime of the composite frame it was extracted from.
+    ///
+    /// Note that librealsense2 allocates frames from a fixed-size pool per stream. Holding onto
+    /// many frames at once can exhaust this pool, at which point new frames are dropped until
+    /// older ones are released.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrameConstructionError::CouldNotAddFrameReference`] if librealsense2 cannot add
+    /// a reference to the frame.
+    ///
+    /// Returns any other [`FrameConstructionError`] if the clone cannot be constructed from the
+    /// frame, in which case the added reference is released again.
+    fn clone_frame(&self) -> Result<Self, FrameConstructionError>
+    where
+        Self: Sized + TryFrom<NonNull<sys::rs2_frame>, Error = anyhow::Error>,
+    {
+        let frame_ptr = self.raw_frame_ptr();
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_frame_add_ref(frame_ptr.as_ptr(), &mut err);
+            check_rs2_error!(err, FrameConstructionError::CouldNotAddFrameReference)?;
+
+            Self::try_from(frame_ptr).map_err(|e| {
+                sys::rs2_release_frame(frame_ptr.as_ptr());
+                into_construction_error(e)
+            })
+        }
+    }
+}
+
+/// Recover the [`FrameConstructionError`] from an error returned by constructing a frame.
+///
+/// Besides a [`FrameConstructionError`], frame construction can only fail while reading the
+/// stream profile of the frame.
+fn into_construction_error(error: anyhow::Error) -> FrameConstructionError {
+    let error = match error.downcast::<FrameConstructionError>() {
+        Ok(error) => return error,
+        Err(error) => error,
+    };
+
+    match error.downcast::<StreamConstructionError>() {
+        Ok(StreamConstructionError::CouldNotRetrieveStreamData(kind, reason))
+        | Ok(StreamConstructionError::CouldNotDetermineIsDefault(kind, reason))
+        | Ok(StreamConstructionError::CouldNotGetProfileFromList(kind, reason))
+        | Ok(StreamConstructionError::CouldNotCloneProfile(kind, reason)) => {
+            FrameConstructionError::CouldNotGetFrameStreamProfile(kind, reason)
+        }
+        Err(error) => FrameConstructionError::CouldNotGetFrameStreamProfile(
+            Rs2Exception::Unknown,
+            error.to_string(),
+        ),
+    }
 }
 
 /// A trait for specifying which runtime stream kinds can be held within a frame type
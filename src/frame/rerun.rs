@@ -0,0 +1,109 @@
+//! Conversions from frames into [rerun](https://rerun.io) archetypes.
+//!
+//! These allow logging frames to a rerun recording directly, e.g.
+//! `rec.log("camera/rgb", &color_frame.to_rerun().unwrap())?`, without having to copy the pixel
+//! data by hand.
+//!
+//! This module is only available with the `rerun` feature enabled.
+
+use super::{
+    image::{ColorFrame, DepthFrame, ImageFrame},
+    points::PointsFrame,
+    pose::PoseFrame,
+    prelude::FrameEx,
+};
+use crate::kind::Rs2Format;
+use std::{os::raw::c_void, slice};
+
+impl<K> ImageFrame<K> {
+    /// Copy the pixel data of the frame into a tightly packed, row-major buffer, dropping any
+    /// padding at the end of each row.
+    fn packed_bytes(&self, bytes_per_pixel: usize) -> Vec<u8> {
+        // SAFETY: the data pointer is valid for `get_data_size` bytes for as long as the frame is
+        // alive, and the slice does not escape this function.
+        let data = unsafe {
+            slice::from_raw_parts(
+                (self.get_data() as *const c_void).cast::<u8>(),
+                self.get_data_size(),
+            )
+        };
+
+        let row_size = self.width() * bytes_per_pixel;
+        data.chunks(self.stride())
+            .take(self.height())
+            .flat_map(|row| &row[..row_size])
+            .copied()
+            .collect()
+    }
+
+    /// The resolution of the frame, in the `[width, height]` order that rerun expects.
+    fn rerun_resolution(&self) -> [u32; 2] {
+        [self.width() as u32, self.height() as u32]
+    }
+}
+
+impl ColorFrame {
+    /// Convert the frame into a rerun [`Image`](::rerun::Image).
+    ///
+    /// BGR(A) data is reordered into RGB(A). Returns `None` if the frame is not in one of the
+    /// [`Rs2Format::Rgb8`], [`Rs2Format::Bgr8`], [`Rs2Format::Rgba8`] or [`Rs2Format::Bgra8`]
+    /// formats.
+    pub fn to_rerun(&self) -> Option<::rerun::Image> {
+        let resolution = self.rerun_resolution();
+
+        match self.stream_profile().format() {
+            Rs2Format::Rgb8 => Some(::rerun::Image::from_rgb24(self.packed_bytes(3), resolution)),
+            Rs2Format::Bgr8 => {
+                let mut bytes = self.packed_bytes(3);
+                bytes.chunks_exact_mut(3).for_each(|pixel| pixel.swap(0, 2));
+                Some(::rerun::Image::from_rgb24(bytes, resolution))
+            }
+            Rs2Format::Rgba8 => Some(::rerun::Image::from_rgba32(
+                self.packed_bytes(4),
+                resolution,
+            )),
+            Rs2Format::Bgra8 => {
+                let mut bytes = self.packed_bytes(4);
+                bytes.chunks_exact_mut(4).for_each(|pixel| pixel.swap(0, 2));
+                Some(::rerun::Image::from_rgba32(bytes, resolution))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl DepthFrame {
+    /// Convert the frame into a rerun [`DepthImage`](::rerun::DepthImage).
+    ///
+    /// `depth_units` is the size of one depth unit in meters, as returned by
+    /// [`DepthFrame::depth_units`], and is used so that rerun displays the depth in meters.
+    /// Returns `None` if the frame is not in the [`Rs2Format::Z16`] format.
+    pub fn to_rerun(&self, depth_units: f32) -> Option<::rerun::DepthImage> {
+        if self.stream_profile().format() != Rs2Format::Z16 {
+            return None;
+        }
+
+        Some(
+            ::rerun::DepthImage::from_gray16(self.packed_bytes(2), self.rerun_resolution())
+                .with_meter(1.0 / depth_units),
+        )
+    }
+}
+
+impl PoseFrame {
+    /// Convert the pose into a rerun [`Transform3D`](::rerun::Transform3D), relative to the
+    /// initial position.
+    pub fn to_rerun(&self) -> ::rerun::Transform3D {
+        ::rerun::Transform3D::from_translation_rotation(
+            self.translation(),
+            ::rerun::Quaternion::from_xyzw(self.rotation()),
+        )
+    }
+}
+
+impl PointsFrame {
+    /// Convert the vertices of the point cloud into rerun [`Points3D`](::rerun::Points3D).
+    pub fn to_rerun(&self) -> ::rerun::Points3D {
+        ::rerun::Points3D::new(self.vertices().iter().map(|v| v.xyz))
+    }
+}
@@ -1,141 +1,160 @@
-//! Defines the queue type of frames.
+//! Type for passing frames between threads through a librealsense2 frame queue.
+//!
+//! A [`FrameQueue`] decouples producing frames from consuming them: frames can be enqueued from a
+//! frame callback or the thread driving a pipeline, and dequeued elsewhere without blocking the
+//! producer. Once the queue is full, the oldest frames are dropped to make room for new ones.
 
 use crate::{
-    base::DEFAULT_TIMEOUT,
-    error::{Error as RsError, ErrorChecker, Result},
-    frame::{AnyFrame, Frame, GenericFrameEx},
-    frame_kind::FrameKind,
+    check_rs2_error,
+    frame::{AnyFrame, FrameEx},
+    kind::Rs2Exception,
 };
+use realsense_sys as sys;
+use std::{convert::TryInto, ptr::NonNull, time::Duration};
+use thiserror::Error;
+
+/// Type describing errors that can occur when creating or using a frame queue.
+///
+/// Follows the standard pattern of errors where the enum variant describes what the low-level code
+/// was attempting to do while the string carried alongside describes the underlying error message
+/// from any C++ exceptions that occur.
+#[derive(Error, Debug)]
+pub enum FrameQueueError {
+    /// The frame queue could not be created.
+    #[error("Could not create frame queue. Type: {0}; Reason: {1}")]
+    CouldNotCreateFrameQueue(Rs2Exception, String),
+    /// An internal error occurred while waiting for a frame from the queue.
+    #[error("Could not dequeue frame. Type: {0}; Reason: {1}")]
+    CouldNotDequeueFrame(Rs2Exception, String),
+}
 
-/// The queue of frames.
+/// A queue of frames with a fixed capacity.
+///
+/// The queue can be shared between threads, so that one thread enqueues frames while another
+/// dequeues them.
 #[derive(Debug)]
 pub struct FrameQueue {
-    pub(crate) ptr: NonNull<sys::rs2_frame_queue>,
+    /// The maximum number of frames held by the queue.
+    capacity: usize,
+    /// A (non-null) pointer to the frame queue.
+    ptr: NonNull<sys::rs2_frame_queue>,
 }
 
-impl FrameQueue {
-    /// Creates an instance with given capacity.
-    pub fn with_capacity(capacity: usize) -> Result<Self> {
-        let queue = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_frame_queue(capacity as c_int, checker.inner_mut_ptr());
-            checker.check()?;
-            Self::from_raw(ptr)
-        };
-        Ok(queue)
-    }
-
-    /// Push a frame to the queue.
-    pub fn enqueue<Kind>(&mut self, frame: Frame<Kind>)
-    where
-        Kind: FrameKind,
-    {
+impl Drop for FrameQueue {
+    fn drop(&mut self) {
         unsafe {
-            sys::rs2_enqueue_frame(frame.ptr.as_ptr(), self.ptr.cast::<c_void>().as_ptr());
+            sys::rs2_delete_frame_queue(self.ptr.as_ptr());
         }
     }
+}
+
+unsafe impl Send for FrameQueue {}
+
+// SAFETY: librealsense2 frame queues are thread-safe, and are meant to pass frames from the
+// thread that enqueues them to the thread that dequeues them. Every method only takes `&self`
+// and goes through the queue's own synchronization, so it can be shared, e.g. through an `Arc`.
+unsafe impl Sync for FrameQueue {}
 
-    /// Pops a frame from queue.
+impl FrameQueue {
+    /// Create a new frame queue that holds at most `capacity` frames.
     ///
-    /// The method blocks until a frame is available.
-    pub fn wait(&mut self, timeout: Option<Duration>) -> Result<AnyFrame> {
-        let timeout_ms = timeout.unwrap_or(DEFAULT_TIMEOUT).as_millis() as c_uint;
-
-        let frame = loop {
-            let mut checker = ErrorChecker::new();
-            let ptr = unsafe {
-                sys::rs2_wait_for_frame(self.ptr.as_ptr(), timeout_ms, checker.inner_mut_ptr())
-            };
-
-            match (timeout, checker.check()) {
-                (None, Err(RsError::Timeout(..))) => continue,
-                tuple => {
-                    let (_, result) = tuple;
-                    result?;
-                }
-            }
+    /// # Errors
+    ///
+    /// Returns [`FrameQueueError::CouldNotCreateFrameQueue`] if the queue cannot be created, e.g.
+    /// if `capacity` does not fit in a C `int`.
+    pub fn with_capacity(capacity: usize) -> Result<Self, FrameQueueError> {
+        let c_capacity = capacity.try_into().map_err(|_| {
+            FrameQueueError::CouldNotCreateFrameQueue(
+                Rs2Exception::InvalidValue,
+                format!("Capacity {} is too large for a frame queue.", capacity),
+            )
+        })?;
 
-            let frame = unsafe { Frame::from_raw(ptr) };
-            break frame;
-        };
-        Ok(frame)
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let ptr = sys::rs2_create_frame_queue(c_capacity, &mut err);
+            check_rs2_error!(err, FrameQueueError::CouldNotCreateFrameQueue)?;
+
+            Ok(Self {
+                capacity,
+                ptr: NonNull::new(ptr).unwrap(),
+            })
+        }
     }
 
-    /// Wait for frame asynchronously. It is analogous to [FrameQueue::wait]
-    pub async fn wait_async(&mut self, timeout: Option<Duration>) -> Result<AnyFrame> {
-        let timeout_ms = timeout
-            .map(|duration| duration.as_millis() as c_uint)
-            .unwrap_or(sys::RS2_DEFAULT_TIMEOUT as c_uint);
-        let (tx, rx) = futures::channel::oneshot::channel();
-        let queue_ptr = AtomicPtr::new(self.ptr.as_ptr());
-
-        thread::spawn(move || {
-            let result = unsafe {
-                loop {
-                    let mut checker = ErrorChecker::new();
-                    let ptr = sys::rs2_wait_for_frame(
-                        queue_ptr.load(Ordering::Relaxed),
-                        timeout_ms,
-                        checker.inner_mut_ptr(),
-                    );
-                    let result = match (timeout, checker.check()) {
-                        (None, Err(RsError::Timeout(..))) => continue,
-                        (_, result) => result.map(|_| Frame::from_raw(ptr)),
-                    };
-                    break result;
-                }
-            };
-            let _ = tx.send(result);
-        });
-
-        let frame = rx.await.unwrap()?;
-        Ok(frame)
+    /// The maximum number of frames held by the queue.
+    pub fn capacity(&self) -> usize {
+        self.capacity
     }
 
-    /// Try to pop a frame and returns immediately.
-    pub fn try_wait(&mut self) -> Result<Option<AnyFrame>> {
+    /// Push `frame` onto the queue.
+    ///
+    /// The queue takes ownership of the frame. If the queue is full, the oldest frame in the queue
+    /// is dropped.
+    pub fn enqueue(&self, frame: impl FrameEx) {
         unsafe {
-            let mut checker = ErrorChecker::new();
-            let mut ptr: *mut sys::rs2_frame = ptr::null_mut();
-            let ret = sys::rs2_poll_for_frame(
-                self.ptr.as_ptr(),
-                &mut ptr as *mut _,
-                checker.inner_mut_ptr(),
+            sys::rs2_enqueue_frame(
+                frame.get_owned_raw().as_ptr(),
+                self.ptr.as_ptr().cast::<std::os::raw::c_void>(),
             );
-            checker.check()?;
-
-            if ret != 0 {
-                let frame = Frame::from_raw(ptr);
-                Ok(Some(frame))
-            } else {
-                Ok(None)
-            }
         }
     }
 
-    pub fn into_raw(self) -> *mut sys::rs2_frame_queue {
-        let ptr = self.ptr;
-        mem::forget(self);
-        ptr.as_ptr()
-    }
+    /// Pop the oldest frame from the queue, blocking the calling thread until one is available.
+    ///
+    /// Frames are returned as an [`AnyFrame`], which can be converted back into the type of frame
+    /// that was enqueued with [`TryFrom`](std::convert::TryFrom).
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - The maximum time to wait for a frame. If `None` is passed in,
+    /// [`RS2_DEFAULT_TIMEOUT`](realsense_sys::RS2_DEFAULT_TIMEOUT) is applied.
+    ///
+    /// Returns `None` if no frame became available within the timeout.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrameQueueError::CouldNotDequeueFrame`] if an internal error occurs while waiting
+    /// for a frame.
+    pub fn dequeue(&self, timeout: Option<Duration>) -> Result<Option<AnyFrame>, FrameQueueError> {
+        let timeout_ms = match timeout {
+            Some(d) => d.as_millis() as u32,
+            None => sys::RS2_DEFAULT_TIMEOUT,
+        };
 
-    pub unsafe fn from_raw(ptr: *mut sys::rs2_frame_queue) -> Self {
-        Self {
-            ptr: NonNull::new(ptr).unwrap(),
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let mut frame = std::ptr::null_mut::<sys::rs2_frame>();
+            let did_get_frame =
+                sys::rs2_try_wait_for_frame(self.ptr.as_ptr(), timeout_ms, &mut frame, &mut err);
+            check_rs2_error!(err, FrameQueueError::CouldNotDequeueFrame)?;
+
+            if did_get_frame != 0 {
+                Ok(Some(AnyFrame::from(NonNull::new(frame).unwrap())))
+            } else {
+                Ok(None)
+            }
         }
     }
 
-    pub(crate) unsafe fn unsafe_clone(&self) -> Self {
-        Self { ptr: self.ptr }
-    }
-}
-
-impl Drop for FrameQueue {
-    fn drop(&mut self) {
+    /// Drop all frames currently held by the queue.
+    pub fn flush(&self) {
         unsafe {
-            sys::rs2_delete_frame_queue(self.ptr.as_ptr());
+            loop {
+                let mut err = std::ptr::null_mut::<sys::rs2_error>();
+                let mut frame = std::ptr::null_mut::<sys::rs2_frame>();
+                let did_get_frame =
+                    sys::rs2_poll_for_frame(self.ptr.as_ptr(), &mut frame, &mut err);
+
+                if err.as_ref().is_some() {
+                    sys::rs2_free_error(err);
+                    break;
+                }
+                if did_get_frame == 0 {
+                    break;
+                }
+                sys::rs2_release_frame(frame);
+            }
         }
     }
 }
-
-unsafe impl Send for FrameQueue {}
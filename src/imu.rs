@@ -0,0 +1,15 @@
+//! Utilities for processing data from the inertial measurement unit (IMU) of a device.
+//!
+//! Devices such as the D435i or D455 provide [accelerometer](crate::frame::AccelFrame) and
+//! [gyroscope](crate::frame::GyroFrame) frames at a much higher rate than image frames. The types
+//! in this module combine those measurements into higher-level estimates of the device motion.
+
+#[cfg(feature = "nalgebra")]
+mod complementary_filter;
+mod integrator;
+mod time_calibrator;
+
+#[cfg(feature = "nalgebra")]
+pub use complementary_filter::ComplementaryFilter;
+pub use integrator::MotionIntegrator;
+pub use time_calibrator::ImuTimeCalibrator;
@@ -0,0 +1,210 @@
+//! Defines a complementary filter for estimating orientation from IMU measurements.
+
+use crate::frame::{AccelFrame, GyroFrame};
+use nalgebra::{UnitQuaternion, Vector3};
+use std::f32::consts::PI;
+
+/// The direction opposite to gravity in the world frame of the filter.
+///
+/// The accelerometer of a device at rest measures the reaction to gravity, which points up. Since
+/// the y-axis of RealSense devices points down, a level device measures acceleration along -y.
+fn up() -> Vector3<f32> {
+    -Vector3::y()
+}
+
+/// Fuses accelerometer and gyroscope measurements into an estimate of the device orientation.
+///
+/// Integrating gyroscope measurements gives a smooth estimate of orientation, which slowly drifts
+/// over time. The accelerometer measures gravity while the device is not accelerating, which gives
+/// an absolute but noisy estimate of which way is up. A complementary filter combines the two by
+/// integrating every [`GyroFrame`], and pulling the estimate towards the gravity vector of every
+/// [`AccelFrame`] by a small amount.
+///
+/// `alpha` is the weight of the gyroscope, between 0 and 1. On each accelerometer update, the
+/// orientation keeps `alpha` of the gyroscope estimate and is corrected by `1 - alpha` of the
+/// difference to the accelerometer estimate. Values close to 1, such as 0.98, work well for the
+/// IMUs found in RealSense devices.
+///
+/// The orientation rotates vectors from the device frame into a world frame whose y-axis points
+/// down, like that of a level RealSense device. The first accelerometer measurement sets the
+/// orientation directly. Gravity does not constrain rotation about the vertical axis, so the yaw
+/// of the estimate drifts with the gyroscope and starts out at zero.
+///
+/// # Examples
+///
+/// ```no_run
+/// use realsense_rust::{
+///     config::Config,
+///     context::Context,
+///     frame::{AccelFrame, FrameEx, GyroFrame},
+///     imu::ComplementaryFilter,
+///     kind::{Rs2Format, Rs2StreamKind},
+///     pipeline::InactivePipeline,
+/// };
+/// use std::convert::TryFrom;
+///
+/// let context = Context::new()?;
+/// let mut config = Config::new();
+/// config
+///     .enable_stream(Rs2StreamKind::Accel, None, 0, 0, Rs2Format::Any, 0)?
+///     .enable_stream(Rs2StreamKind::Gyro, None, 0, 0, Rs2Format::Any, 0)?;
+/// let mut pipeline = InactivePipeline::try_from(&context)?.start(Some(config))?;
+///
+/// let mut filter = ComplementaryFilter::new(0.98);
+/// let mut last_gyro_timestamp = None;
+///
+/// loop {
+///     let frames = pipeline.wait(None)?;
+///
+///     for accel in frames.frames_of_type::<AccelFrame>() {
+///         filter.update_accel(&accel);
+///     }
+///     for gyro in frames.frames_of_type::<GyroFrame>() {
+///         // Frame timestamps are in milliseconds.
+///         if let Some(last) = last_gyro_timestamp {
+///             filter.update_gyro(&gyro, (gyro.timestamp() - last) / 1000.0);
+///         }
+///         last_gyro_timestamp = Some(gyro.timestamp());
+///     }
+///
+///     println!("{:?}", filter.orientation());
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// Only available with the `nalgebra` feature enabled.
+#[derive(Debug, Clone)]
+pub struct ComplementaryFilter {
+    /// Weight of the gyroscope estimate, between 0 and 1.
+    alpha: f32,
+    /// Rotation from the device frame into the world frame.
+    orientation: UnitQuaternion<f32>,
+    /// Whether an accelerometer measurement has set the initial orientation yet.
+    initialized: bool,
+}
+
+impl ComplementaryFilter {
+    /// Construct a new filter that weighs the gyroscope by `alpha`.
+    ///
+    /// `alpha` is clamped to the range `[0, 1]`. The orientation starts out as the identity until
+    /// the first accelerometer measurement is received.
+    pub fn new(alpha: f32) -> Self {
+        Self {
+            alpha: alpha.clamp(0.0, 1.0),
+            orientation: UnitQuaternion::identity(),
+            initialized: false,
+        }
+    }
+
+    /// Correct the orientation towards the gravity vector measured by an accelerometer frame.
+    ///
+    /// Measurements of zero acceleration, such as during free fall, carry no information about
+    /// gravity and are ignored.
+    pub fn update_accel(&mut self, accel: &AccelFrame) {
+        self.correct(Vector3::from(*accel.acceleration()));
+    }
+
+    /// Rotate the orientation by the angular velocity of a gyroscope frame over `dt` seconds.
+    ///
+    /// `dt` is usually the time since the previous gyroscope frame. Note that frame timestamps
+    /// are in milliseconds.
+    pub fn update_gyro(&mut self, gyro: &GyroFrame, dt: f64) {
+        self.rotate(Vector3::from(*gyro.rotational_velocity()), dt as f32);
+    }
+
+    /// The estimated orientation, as a unit quaternion in `[x, y, z, w]` order.
+    ///
+    /// This matches the order of [`PoseFrame::rotation`](crate::frame::PoseFrame::rotation).
+    pub fn orientation(&self) -> [f32; 4] {
+        let q = self.orientation.quaternion();
+        [q.i, q.j, q.k, q.w]
+    }
+
+    /// Pull the orientation towards aligning `acceleration` (m/s^2) in the device frame with up.
+    fn correct(&mut self, acceleration: Vector3<f32>) {
+        let measured_up = match acceleration.try_normalize(f32::EPSILON) {
+            Some(direction) => self.orientation * direction,
+            None => return,
+        };
+
+        // Vectors pointing in opposite directions have no unique rotation between them, so any
+        // half turn about a horizontal axis will do.
+        let correction = UnitQuaternion::rotation_between(&measured_up, &up())
+            .unwrap_or_else(|| UnitQuaternion::from_axis_angle(&Vector3::x_axis(), PI));
+
+        let weight = if self.initialized {
+            1.0 - self.alpha
+        } else {
+            1.0
+        };
+        self.orientation = correction.powf(weight) * self.orientation;
+        self.initialized = true;
+    }
+
+    /// Rotate the orientation by `angular_velocity` (rad/s) in the device frame over `dt` seconds.
+    fn rotate(&mut self, angular_velocity: Vector3<f32>, dt: f32) {
+        self.orientation *= UnitQuaternion::from_scaled_axis(angular_velocity * dt);
+        self.orientation.renormalize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    fn assert_close(a: [f32; 4], b: [f32; 4]) {
+        for i in 0..4 {
+            assert!((a[i] - b[i]).abs() < 1e-3, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn level_device_at_rest_keeps_identity() {
+        let mut filter = ComplementaryFilter::new(0.98);
+        filter.correct(Vector3::new(0.0, -9.81, 0.0));
+
+        assert_close(filter.orientation(), [0.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn first_accel_sets_orientation() {
+        let mut filter = ComplementaryFilter::new(0.98);
+        // Rolled a quarter turn about z, so that up is along +x in the device frame.
+        filter.correct(Vector3::new(9.81, 0.0, 0.0));
+
+        let rolled = filter.orientation * Vector3::x();
+        assert!((rolled - up()).norm() < 1e-3, "{:?}", rolled);
+    }
+
+    #[test]
+    fn gyro_is_integrated() {
+        let mut filter = ComplementaryFilter::new(0.98);
+
+        // A quarter turn about z over one second, sampled at 100Hz.
+        for _ in 0..100 {
+            filter.rotate(Vector3::new(0.0, 0.0, FRAC_PI_2), 0.01);
+        }
+
+        let half_angle = FRAC_PI_2 / 2.0;
+        assert_close(
+            filter.orientation(),
+            [0.0, 0.0, half_angle.sin(), half_angle.cos()],
+        );
+    }
+
+    #[test]
+    fn accel_corrects_gyro_drift() {
+        let mut filter = ComplementaryFilter::new(0.9);
+        filter.correct(Vector3::new(0.0, -9.81, 0.0));
+
+        // A biased gyroscope on a device at rest.
+        for _ in 0..1000 {
+            filter.rotate(Vector3::new(0.0, 0.0, 0.05), 0.01);
+            filter.correct(Vector3::new(0.0, -9.81, 0.0));
+        }
+
+        let tilt = (filter.orientation * Vector3::y()).angle(&Vector3::y());
+        assert!(tilt < 0.01, "drifted by {} rad", tilt);
+    }
+}
@@ -0,0 +1,232 @@
+//! Defines a type for integrating IMU measurements into a change in pose.
+
+use crate::frame::{AccelFrame, FrameEx, GyroFrame};
+
+/// A unit quaternion, stored as `[x, y, z, w]` to match
+/// [`PoseFrame::rotation`](crate::frame::PoseFrame::rotation).
+type Quaternion = [f32; 4];
+
+/// The identity rotation.
+const IDENTITY: Quaternion = [0.0, 0.0, 0.0, 1.0];
+
+/// Accumulates accelerometer and gyroscope measurements into an estimated change in pose.
+///
+/// IMU frames typically arrive several times faster than image frames. Feeding every
+/// [`AccelFrame`] and [`GyroFrame`] received between two image frames into an integrator gives an
+/// estimate of how the device moved in the meantime, which can be used to produce pose estimates
+/// at a higher rate than the camera.
+///
+/// Consecutive measurements are integrated with the midpoint rule. Rotation is integrated from
+/// gyroscope measurements, and translation is integrated from accelerometer measurements rotated
+/// into the frame of the device at the time of the last [`reset`](MotionIntegrator::reset).
+///
+/// # Gravity and initial velocity
+///
+/// Following the usual definition of IMU preintegration, the integrated translation assumes the
+/// device was at rest when integration started, and does not remove gravity from the
+/// accelerometer measurements. To recover the true displacement over an interval of `dt`
+/// seconds, add `v * dt + 0.5 * g * dt^2`, where `v` is the velocity at the start of the interval
+/// and `g` is gravity, both expressed in the frame of the device at the start of the interval.
+#[derive(Debug, Clone)]
+pub struct MotionIntegrator {
+    /// Rotation of the device relative to its orientation at the last reset.
+    rotation: Quaternion,
+    /// Integrated velocity, in m/s.
+    velocity: [f32; 3],
+    /// Integrated translation, in meters.
+    translation: [f32; 3],
+    /// Timestamp (ms) and angular velocity of the last gyroscope measurement.
+    last_gyro: Option<(f64, [f32; 3])>,
+    /// Timestamp (ms) and rotated acceleration of the last accelerometer measurement.
+    last_accel: Option<(f64, [f32; 3])>,
+}
+
+impl Default for MotionIntegrator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MotionIntegrator {
+    /// Construct a new integrator with no accumulated motion.
+    pub fn new() -> Self {
+        Self {
+            rotation: IDENTITY,
+            velocity: [0.0; 3],
+            translation: [0.0; 3],
+            last_gyro: None,
+            last_accel: None,
+        }
+    }
+
+    /// Add an accelerometer measurement to the integrator.
+    ///
+    /// Frames must be fed in timestamp order. Frames with a timestamp that is not later than the
+    /// previous accelerometer frame are ignored.
+    pub fn feed_accel(&mut self, frame: &AccelFrame) {
+        self.integrate_accel(frame.timestamp(), *frame.acceleration());
+    }
+
+    /// Add a gyroscope measurement to the integrator.
+    ///
+    /// Frames must be fed in timestamp order. Frames with a timestamp that is not later than the
+    /// previous gyroscope frame are ignored.
+    pub fn feed_gyro(&mut self, frame: &GyroFrame) {
+        self.integrate_gyro(frame.timestamp(), *frame.rotational_velocity());
+    }
+
+    /// Get the change in pose accumulated since the integrator was constructed or last reset.
+    ///
+    /// Returns `(translation, rotation)`, where translation is in meters and rotation is a unit
+    /// quaternion in `[x, y, z, w]` order. See the [type documentation](MotionIntegrator) for how
+    /// gravity is handled.
+    pub fn integrated_delta_pose(&self) -> ([f32; 3], [f32; 4]) {
+        (self.translation, self.rotation)
+    }
+
+    /// Clear all accumulated motion.
+    ///
+    /// The next measurements fed to the integrator start a new interval.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Integrate an angular velocity (rad/s) measured at `timestamp` (ms).
+    fn integrate_gyro(&mut self, timestamp: f64, angular_velocity: [f32; 3]) {
+        if let Some((last_timestamp, last_angular_velocity)) = self.last_gyro {
+            if timestamp <= last_timestamp {
+                return;
+            }
+
+            let dt = ((timestamp - last_timestamp) / 1000.0) as f32;
+            let mut rotation_vector = [0.0; 3];
+            for i in 0..3 {
+                rotation_vector[i] = 0.5 * (last_angular_velocity[i] + angular_velocity[i]) * dt;
+            }
+
+            self.rotation = normalize(multiply(
+                self.rotation,
+                from_rotation_vector(rotation_vector),
+            ));
+        }
+
+        self.last_gyro = Some((timestamp, angular_velocity));
+    }
+
+    /// Integrate an acceleration (m/s^2) measured at `timestamp` (ms).
+    fn integrate_accel(&mut self, timestamp: f64, acceleration: [f32; 3]) {
+        let acceleration = rotate(self.rotation, acceleration);
+
+        if let Some((last_timestamp, last_acceleration)) = self.last_accel {
+            if timestamp <= last_timestamp {
+                return;
+            }
+
+            let dt = ((timestamp - last_timestamp) / 1000.0) as f32;
+            for i in 0..3 {
+                let midpoint = 0.5 * (last_acceleration[i] + acceleration[i]);
+                self.translation[i] += self.velocity[i] * dt + 0.5 * midpoint * dt * dt;
+                self.velocity[i] += midpoint * dt;
+            }
+        }
+
+        self.last_accel = Some((timestamp, acceleration));
+    }
+}
+
+/// Hamilton product `a * b` of two quaternions.
+fn multiply(a: Quaternion, b: Quaternion) -> Quaternion {
+    let [ax, ay, az, aw] = a;
+    let [bx, by, bz, bw] = b;
+
+    [
+        aw * bx + ax * bw + ay * bz - az * by,
+        aw * by - ax * bz + ay * bw + az * bx,
+        aw * bz + ax * by - ay * bx + az * bw,
+        aw * bw - ax * bx - ay * by - az * bz,
+    ]
+}
+
+/// Scale a quaternion to unit length, to counter the accumulation of rounding errors.
+fn normalize(q: Quaternion) -> Quaternion {
+    let norm = q.iter().map(|v| v * v).sum::<f32>().sqrt();
+    [q[0] / norm, q[1] / norm, q[2] / norm, q[3] / norm]
+}
+
+/// Quaternion for a rotation of `|v|` radians about the axis `v`.
+fn from_rotation_vector(v: [f32; 3]) -> Quaternion {
+    let angle = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if angle < f32::EPSILON {
+        return IDENTITY;
+    }
+
+    let scale = (0.5 * angle).sin() / angle;
+    [
+        v[0] * scale,
+        v[1] * scale,
+        v[2] * scale,
+        (0.5 * angle).cos(),
+    ]
+}
+
+/// Rotate the vector `v` by the unit quaternion `q`.
+fn rotate(q: Quaternion, v: [f32; 3]) -> [f32; 3] {
+    let conjugate = [-q[0], -q[1], -q[2], q[3]];
+    let [x, y, z, _] = multiply(multiply(q, [v[0], v[1], v[2], 0.0]), conjugate);
+    [x, y, z]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    fn assert_close(a: &[f32], b: &[f32]) {
+        for (x, y) in a.iter().zip(b) {
+            assert!((x - y).abs() < 1e-4, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn constant_rotation_is_integrated() {
+        let mut integrator = MotionIntegrator::new();
+
+        // A quarter turn about z over one second, sampled at 100Hz.
+        for i in 0..=100 {
+            integrator.integrate_gyro(i as f64 * 10.0, [0.0, 0.0, FRAC_PI_2]);
+        }
+
+        let (_, rotation) = integrator.integrated_delta_pose();
+        let half_angle = FRAC_PI_2 / 2.0;
+        assert_close(&rotation, &[0.0, 0.0, half_angle.sin(), half_angle.cos()]);
+    }
+
+    #[test]
+    fn constant_acceleration_is_integrated() {
+        let mut integrator = MotionIntegrator::new();
+
+        // 2 m/s^2 along x for one second should move the device 1m.
+        for i in 0..=100 {
+            integrator.integrate_accel(i as f64 * 10.0, [2.0, 0.0, 0.0]);
+        }
+
+        let (translation, rotation) = integrator.integrated_delta_pose();
+        assert_close(&translation, &[1.0, 0.0, 0.0]);
+        assert_close(&rotation, &IDENTITY);
+    }
+
+    #[test]
+    fn reset_clears_motion() {
+        let mut integrator = MotionIntegrator::new();
+        integrator.integrate_accel(0.0, [1.0, 0.0, 0.0]);
+        integrator.integrate_accel(10.0, [1.0, 0.0, 0.0]);
+        integrator.integrate_gyro(0.0, [1.0, 0.0, 0.0]);
+        integrator.integrate_gyro(10.0, [1.0, 0.0, 0.0]);
+
+        integrator.reset();
+
+        let (translation, rotation) = integrator.integrated_delta_pose();
+        assert_eq!(translation, [0.0; 3]);
+        assert_eq!(rotation, IDENTITY);
+    }
+}
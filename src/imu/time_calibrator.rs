@@ -0,0 +1,136 @@
+//! Defines a type for estimating the offset between IMU and depth timestamps.
+
+use crate::frame::FrameEx;
+
+/// Estimates the systematic offset between the timestamps of IMU frames and depth frames.
+///
+/// On devices such as the D435i, the accelerometer and gyroscope are timestamped separately from
+/// the depth sensor, so measurements taken at the same instant can carry slightly different
+/// timestamps. Fusing IMU data with depth data, e.g. with a
+/// [`MotionIntegrator`](crate::imu::MotionIntegrator), is more accurate once the IMU timestamps
+/// are shifted onto the depth clock.
+///
+/// Pairs of timestamps that were captured at the same instant are collected with
+/// [`add_pair`](ImuTimeCalibrator::add_pair) or [`add_frames`](ImuTimeCalibrator::add_frames),
+/// e.g. the depth frame and the closest IMU frame from the same synchronized frameset. The offset
+/// is then estimated with [`calibrate`](ImuTimeCalibrator::calibrate) as the median difference
+/// between the timestamps of each pair, which is robust to the occasional pair that was not
+/// captured at the same instant.
+///
+/// All timestamps are in milliseconds, as returned by [`FrameEx::timestamp`].
+#[derive(Debug, Clone, Default)]
+pub struct ImuTimeCalibrator {
+    /// The difference `depth_timestamp - imu_timestamp` of every pair added so far, in ms.
+    differences: Vec<f64>,
+    /// The offset estimated by the last call to `calibrate`, in ms.
+    offset: f64,
+}
+
+impl ImuTimeCalibrator {
+    /// Construct a new calibrator with no pairs and no offset.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a pair of depth and IMU timestamps (ms) that were captured at the same instant.
+    ///
+    /// Pairs whose difference is not finite, e.g. because either timestamp is NaN, are ignored.
+    pub fn add_pair(&mut self, depth_timestamp: f64, imu_timestamp: f64) {
+        let difference = depth_timestamp - imu_timestamp;
+        if difference.is_finite() {
+            self.differences.push(difference);
+        }
+    }
+
+    /// Add the timestamps of a depth frame and an IMU frame that were captured at the same
+    /// instant.
+    ///
+    /// This is shorthand for [`add_pair`](ImuTimeCalibrator::add_pair) with the
+    /// [timestamps](FrameEx::timestamp) of both frames.
+    pub fn add_frames<D, I>(&mut self, depth_frame: &D, imu_frame: &I)
+    where
+        D: FrameEx,
+        I: FrameEx,
+    {
+        self.add_pair(depth_frame.timestamp(), imu_frame.timestamp());
+    }
+
+    /// Estimate the offset from the pairs added so far.
+    ///
+    /// Returns the offset in ms that is added to IMU timestamps to map them onto the depth clock,
+    /// or `None` if no pairs have been added. The estimate is also stored and applied by
+    /// [`align_imu_timestamp`](ImuTimeCalibrator::align_imu_timestamp) from then on.
+    pub fn calibrate(&mut self) -> Option<f64> {
+        if self.differences.is_empty() {
+            return None;
+        }
+
+        let mut differences = self.differences.clone();
+        differences.sort_by(f64::total_cmp);
+
+        let mid = differences.len() / 2;
+        self.offset = if differences.len() % 2 == 1 {
+            differences[mid]
+        } else {
+            (differences[mid - 1] + differences[mid]) / 2.0
+        };
+        Some(self.offset)
+    }
+
+    /// Map an IMU timestamp (ms) onto the depth clock.
+    ///
+    /// Returns `imu_timestamp` unchanged until [`calibrate`](ImuTimeCalibrator::calibrate) has
+    /// estimated an offset.
+    pub fn align_imu_timestamp(&self, imu_timestamp: f64) -> f64 {
+        imu_timestamp + self.offset
+    }
+
+    /// Discard all pairs and the estimated offset.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offset_is_median_of_differences() {
+        let mut calibrator = ImuTimeCalibrator::new();
+        assert_eq!(calibrator.calibrate(), None);
+
+        calibrator.add_pair(100.0, 98.0);
+        calibrator.add_pair(133.0, 131.0);
+        calibrator.add_pair(166.0, 164.5);
+        // An outlier, e.g. from a dropped IMU frame.
+        calibrator.add_pair(200.0, 150.0);
+
+        assert_eq!(calibrator.calibrate(), Some(2.0));
+        assert_eq!(calibrator.align_imu_timestamp(231.0), 233.0);
+    }
+
+    #[test]
+    fn non_finite_timestamps_are_ignored() {
+        let mut calibrator = ImuTimeCalibrator::new();
+        calibrator.add_pair(f64::NAN, 98.0);
+        calibrator.add_pair(100.0, f64::INFINITY);
+        assert_eq!(calibrator.calibrate(), None);
+
+        calibrator.add_pair(100.0, 98.0);
+        assert_eq!(calibrator.calibrate(), Some(2.0));
+    }
+
+    #[test]
+    fn timestamps_are_unchanged_until_calibrated() {
+        let mut calibrator = ImuTimeCalibrator::new();
+        calibrator.add_pair(100.0, 90.0);
+
+        assert_eq!(calibrator.align_imu_timestamp(50.0), 50.0);
+        calibrator.calibrate();
+        assert_eq!(calibrator.align_imu_timestamp(50.0), 60.0);
+
+        calibrator.reset();
+        assert_eq!(calibrator.align_imu_timestamp(50.0), 50.0);
+    }
+}
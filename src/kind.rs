@@ -57,21 +57,29 @@
 
 mod camera_info;
 mod color_scheme;
+mod depth_auto_exposure_mode;
+mod digital_gain;
 mod distortion_model;
+mod emitter_mode;
 mod exception;
 mod extension;
 mod format;
 mod frame_metadata;
 mod hole_filling;
+mod log_severity;
 mod option;
 mod persistence_control;
 mod product_line;
+mod sensor_mode;
 mod stream_kind;
 mod timestamp_domain;
 
 pub use camera_info::Rs2CameraInfo;
 pub use color_scheme::ColorScheme;
+pub use depth_auto_exposure_mode::Rs2DepthAutoExposureMode;
+pub use digital_gain::Rs2DigitalGain;
 pub use distortion_model::Rs2DistortionModel;
+pub use emitter_mode::EmitterMode;
 pub use exception::Rs2Exception;
 pub use extension::{
     Rs2Extension, DEVICE_EXTENSIONS, FILTER_EXTENSIONS, FRAME_EXTENSIONS, MISC_EXTENSIONS,
@@ -80,8 +88,10 @@ pub use extension::{
 pub use format::Rs2Format;
 pub use frame_metadata::Rs2FrameMetadata;
 pub use hole_filling::HoleFillingMode;
+pub use log_severity::Rs2LogSeverity;
 pub use option::{OptionSetError, Rs2Option, Rs2OptionRange};
 pub use persistence_control::PersistenceControl;
 pub use product_line::Rs2ProductLine;
+pub use sensor_mode::Rs2SensorMode;
 pub use stream_kind::Rs2StreamKind;
-pub use timestamp_domain::Rs2TimestampDomain;
+pub use timestamp_domain::{Rs2TimestampDomain, TimestampPrecision};
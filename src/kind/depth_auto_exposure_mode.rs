@@ -0,0 +1,37 @@
+//! Enumeration of the auto exposure algorithms available to depth sensors.
+
+use num_derive::{FromPrimitive, ToPrimitive};
+use realsense_sys as sys;
+
+/// The auto exposure algorithm used by a depth sensor.
+///
+/// These are the values of the [`Rs2Option::DepthAutoExposureMode`](crate::kind::Rs2Option)
+/// option. See [`Sensor::set_depth_auto_exposure_mode`](crate::sensor::Sensor::set_depth_auto_exposure_mode).
+#[repr(i32)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rs2DepthAutoExposureMode {
+    /// The regular auto exposure algorithm.
+    Regular = sys::rs2_depth_auto_exposure_mode_RS2_DEPTH_AUTO_EXPOSURE_REGULAR as i32,
+    /// The accelerated auto exposure algorithm, which converges faster.
+    Accelerated = sys::rs2_depth_auto_exposure_mode_RS2_DEPTH_AUTO_EXPOSURE_ACCELERATED as i32,
+    // Not included since this just tells us the total number of modes
+    //
+    // Count = sys::rs2_depth_auto_exposure_mode_RS2_DEPTH_AUTO_EXPOSURE_COUNT as i32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::FromPrimitive;
+
+    #[test]
+    fn all_variants_exist() {
+        for i in 0..sys::rs2_depth_auto_exposure_mode_RS2_DEPTH_AUTO_EXPOSURE_COUNT as i32 {
+            assert!(
+                Rs2DepthAutoExposureMode::from_i32(i).is_some(),
+                "Rs2DepthAutoExposureMode variant for ordinal {} does not exist.",
+                i,
+            );
+        }
+    }
+}
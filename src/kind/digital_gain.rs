@@ -0,0 +1,36 @@
+//! Enumeration of the digital gain settings available to depth sensors.
+
+use num_derive::{FromPrimitive, ToPrimitive};
+use realsense_sys as sys;
+
+/// The digital gain applied by a depth sensor.
+///
+/// These are the values of the [`Rs2Option::DigitalGain`](crate::kind::Rs2Option) option. See
+/// [`Sensor::set_digital_gain`](crate::sensor::Sensor::set_digital_gain).
+#[repr(i32)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rs2DigitalGain {
+    /// Let the device choose the gain.
+    Auto = sys::rs2_digital_gain_RS2_DIGITAL_GAIN_AUTO as i32,
+    /// High gain, for low ambient light.
+    High = sys::rs2_digital_gain_RS2_DIGITAL_GAIN_HIGH as i32,
+    /// Low gain, for high ambient light.
+    Low = sys::rs2_digital_gain_RS2_DIGITAL_GAIN_LOW as i32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::FromPrimitive;
+
+    #[test]
+    fn all_variants_exist() {
+        for i in 0..=sys::rs2_digital_gain_RS2_DIGITAL_GAIN_LOW as i32 {
+            assert!(
+                Rs2DigitalGain::from_i32(i).is_some(),
+                "Rs2DigitalGain variant for ordinal {} does not exist.",
+                i,
+            );
+        }
+    }
+}
@@ -0,0 +1,22 @@
+//! Enumeration of the emitter selections available to depth sensors.
+
+use num_derive::{FromPrimitive, ToPrimitive};
+
+/// The emitter (projector) selection of a depth sensor.
+///
+/// The name of this type is not preceded with `Rs2` because librealsense2 does not define a type
+/// for these values. They are the documented values of the
+/// [`Rs2Option::EmitterEnabled`](crate::kind::Rs2Option::EmitterEnabled) option. See
+/// [`Sensor::set_emitter_mode`](crate::sensor::Sensor::set_emitter_mode).
+#[repr(usize)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EmitterMode {
+    /// All emitters are disabled.
+    Off = 0,
+    /// The laser emitter is enabled.
+    Laser = 1,
+    /// The laser emitter is turned on and off automatically.
+    AutoLaser = 2,
+    /// The LED emitter is enabled.
+    Led = 3,
+}
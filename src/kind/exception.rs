@@ -1,8 +1,12 @@
 //! Possible exception / error types that librealsense2 can produce
 
 use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::FromPrimitive as _;
 use realsense_sys as sys;
-use std::fmt::{Display, Formatter, Result};
+use std::{
+    ffi::CStr,
+    fmt::{Display, Formatter, Result},
+};
 
 /// Enumeration of possible exception types that can be returned via `rs2_error`
 ///
@@ -48,20 +52,40 @@ pub enum Rs2Exception {
     // Count = sys::rs2_exception_type_RS2_EXCEPTION_TYPE_COUNT,
 }
 
+impl Rs2Exception {
+    /// Get the exception type as a CStr.
+    ///
+    /// This is the description librealsense2 uses for the exception type, e.g. `"io"` for
+    /// [`Rs2Exception::IoDeviceFailure`].
+    pub fn to_cstr(self) -> &'static CStr {
+        unsafe {
+            let ptr = sys::rs2_exception_type_to_string(self as sys::rs2_exception_type);
+            CStr::from_ptr(ptr)
+        }
+    }
+
+    /// Get the exception type as a str.
+    pub fn to_str(self) -> &'static str {
+        self.to_cstr().to_str().unwrap()
+    }
+
+    /// Parse an exception type from its librealsense2 description.
+    ///
+    /// This is the inverse of [`Rs2Exception::to_str`], e.g. `"io"` parses to
+    /// [`Rs2Exception::IoDeviceFailure`]. Returns `None` if `s` does not describe any exception
+    /// type.
+    // `FromStr` would require an error type that carries no more information than `None`.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        (0..sys::rs2_exception_type_RS2_EXCEPTION_TYPE_COUNT as i32)
+            .filter_map(Self::from_i32)
+            .find(|exception| exception.to_str() == s)
+    }
+}
+
 impl Display for Rs2Exception {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        let s = match self {
-            Rs2Exception::Unknown => "Unknown",
-            Rs2Exception::CameraDisconnected => "CameraDisconnected",
-            Rs2Exception::Backend => "Backend",
-            Rs2Exception::InvalidValue => "InvalidValue",
-            Rs2Exception::WrongApiCallSequence => "WrongAPICallSequence",
-            Rs2Exception::NotImplemented => "NotImplemented",
-            Rs2Exception::DeviceInRecoveryMode => "DeviceInRecoveryMode",
-            Rs2Exception::IoDeviceFailure => "IODeviceFailure",
-        };
-
-        write!(f, "{}", s)
+        write!(f, "{}", self.to_str())
     }
 }
 
@@ -115,6 +115,138 @@ pub enum Rs2Format {
     // Count = sys::rs2_format_RS2_FORMAT_COUNT,
 }
 
+impl Rs2Format {
+    /// Predicate for whether this format carries depth (or disparity) data.
+    pub fn is_depth_format(self) -> bool {
+        matches!(
+            self,
+            Rs2Format::Z16
+                | Rs2Format::Z16H
+                | Rs2Format::Distance
+                | Rs2Format::Disparity16
+                | Rs2Format::Disparity32
+        )
+    }
+
+    /// Predicate for whether this format carries color or grayscale image data.
+    ///
+    /// Grayscale formats (`Y8`, `Y16`) are included since these are used by the infrared streams.
+    pub fn is_color_format(self) -> bool {
+        matches!(
+            self,
+            Rs2Format::Yuyv
+                | Rs2Format::Uyvy
+                | Rs2Format::Bgr8
+                | Rs2Format::Bgra8
+                | Rs2Format::Rgb8
+                | Rs2Format::Rgba8
+                | Rs2Format::Mjpeg
+                | Rs2Format::Y8
+                | Rs2Format::Y16
+        )
+    }
+
+    /// Predicate for whether this format carries motion (IMU) or pose data.
+    pub fn is_motion_format(self) -> bool {
+        matches!(
+            self,
+            Rs2Format::MotionXyz32F | Rs2Format::MotionRaw | Rs2Format::_6Dof
+        )
+    }
+
+    /// The number of significant bits in each channel of a pixel.
+    ///
+    /// This is the range of the values, rather than the storage size:
+    /// [`Y10Bpack`](Rs2Format::Y10Bpack) values are stored in 16 bits but only range up to
+    /// `2^10 - 1`, so they are normalized by dividing by `(1 << 10) - 1`. Combined with
+    /// [`channel_count`](Rs2Format::channel_count) this describes the layout of one pixel.
+    ///
+    /// Raw image sensor formats ([`Raw8`](Rs2Format::Raw8), [`Raw10`](Rs2Format::Raw10),
+    /// [`Raw16`](Rs2Format::Raw16)) hold one Bayer sample per pixel, with the bit depth of their
+    /// name.
+    ///
+    /// Returns `None` for formats without a fixed per-pixel layout: compressed
+    /// ([`Mjpeg`](Rs2Format::Mjpeg), [`Z16H`](Rs2Format::Z16H)) and multi-planar
+    /// ([`Inzi`](Rs2Format::Inzi)) formats, raw motion and GPIO data, and
+    /// [`Any`](Rs2Format::Any).
+    pub fn bit_depth_per_channel(self) -> Option<u32> {
+        match self {
+            Rs2Format::Yuyv
+            | Rs2Format::Uyvy
+            | Rs2Format::Invi
+            | Rs2Format::Bgr8
+            | Rs2Format::Bgra8
+            | Rs2Format::Raw8
+            | Rs2Format::Rgb8
+            | Rs2Format::Rgba8
+            | Rs2Format::Y8
+            | Rs2Format::Y8I
+            | Rs2Format::Y411 => Some(8),
+            Rs2Format::Raw10 | Rs2Format::W10 | Rs2Format::Y10Bpack => Some(10),
+            Rs2Format::Y12I => Some(12),
+            Rs2Format::Disparity16
+            | Rs2Format::Raw16
+            | Rs2Format::Y16
+            | Rs2Format::Z16
+            | Rs2Format::Fg => Some(16),
+            Rs2Format::Distance
+            | Rs2Format::_6Dof
+            | Rs2Format::Disparity32
+            | Rs2Format::MotionXyz32F
+            | Rs2Format::Xyz32F => Some(32),
+            Rs2Format::Any
+            | Rs2Format::MotionRaw
+            | Rs2Format::GpioRaw
+            | Rs2Format::Mjpeg
+            | Rs2Format::Inzi
+            | Rs2Format::Z16H => None,
+        }
+    }
+
+    /// The number of channels in each pixel.
+    ///
+    /// Grayscale, depth and disparity formats have one channel, and RGB(A) formats have three
+    /// (four). Packed YUV formats are counted as two channels, luma and alternating chroma, and
+    /// interleaved stereo formats such as [`Y8I`](Rs2Format::Y8I) as two channels, left and right.
+    ///
+    /// Raw image sensor formats ([`Raw8`](Rs2Format::Raw8), [`Raw10`](Rs2Format::Raw10),
+    /// [`Raw16`](Rs2Format::Raw16)) have one channel, since each pixel holds a single Bayer sample.
+    ///
+    /// Returns `None` for formats without a fixed per-pixel layout: compressed and multi-planar
+    /// formats (including [`Y411`](Rs2Format::Y411)), raw motion and GPIO data, pose data and
+    /// [`Any`](Rs2Format::Any).
+    pub fn channel_count(self) -> Option<u32> {
+        match self {
+            Rs2Format::Distance
+            | Rs2Format::Invi
+            | Rs2Format::Disparity16
+            | Rs2Format::Disparity32
+            | Rs2Format::Raw8
+            | Rs2Format::Raw10
+            | Rs2Format::Raw16
+            | Rs2Format::W10
+            | Rs2Format::Y8
+            | Rs2Format::Y10Bpack
+            | Rs2Format::Y16
+            | Rs2Format::Z16
+            | Rs2Format::Fg => Some(1),
+            Rs2Format::Yuyv | Rs2Format::Uyvy | Rs2Format::Y8I | Rs2Format::Y12I => Some(2),
+            Rs2Format::Bgr8 | Rs2Format::MotionXyz32F | Rs2Format::Rgb8 | Rs2Format::Xyz32F => {
+                Some(3)
+            }
+            Rs2Format::Bgra8 | Rs2Format::Rgba8 => Some(4),
+            Rs2Format::Any
+            | Rs2Format::MotionRaw
+            | Rs2Format::GpioRaw
+            | Rs2Format::Mjpeg
+            | Rs2Format::Inzi
+            | Rs2Format::_6Dof
+            | Rs2Format::Z16H
+            | Rs2Format::Y411 => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,4 +262,40 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn format_categories_are_disjoint() {
+        for i in 0..sys::rs2_format_RS2_FORMAT_COUNT as i32 {
+            let format = Rs2Format::from_i32(i).unwrap();
+            let categories = [
+                format.is_depth_format(),
+                format.is_color_format(),
+                format.is_motion_format(),
+            ];
+
+            assert!(
+                categories.iter().filter(|&&c| c).count() <= 1,
+                "Rs2Format {:?} belongs to more than one category.",
+                format
+            );
+        }
+    }
+
+    #[test]
+    fn channel_count_implies_bit_depth() {
+        for i in 0..sys::rs2_format_RS2_FORMAT_COUNT as i32 {
+            let format = Rs2Format::from_i32(i).unwrap();
+
+            if format.channel_count().is_some() {
+                assert!(
+                    format.bit_depth_per_channel().is_some(),
+                    "Rs2Format {:?} has channels but no bit depth.",
+                    format
+                );
+            }
+        }
+
+        assert_eq!(Rs2Format::Y10Bpack.bit_depth_per_channel(), Some(10));
+        assert_eq!(Rs2Format::Rgba8.channel_count(), Some(4));
+    }
 }
@@ -0,0 +1,75 @@
+//! Enumeration of the severities of messages logged by librealsense2.
+
+use num_derive::{FromPrimitive, ToPrimitive};
+use realsense_sys as sys;
+use std::ffi::CStr;
+
+/// Enumeration of the severities of librealsense2 log messages.
+///
+/// When configuring logging, the severity acts as a minimum: messages of the given severity and
+/// anything more severe are logged. librealsense2 also defines an `ALL` severity, which is the same
+/// value as [`Debug`](Rs2LogSeverity::Debug).
+#[repr(i32)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Rs2LogSeverity {
+    /// Detailed information about the internal workings of librealsense2.
+    Debug = sys::rs2_log_severity_RS2_LOG_SEVERITY_DEBUG as i32,
+    /// General information about the state of librealsense2 and connected devices.
+    Info = sys::rs2_log_severity_RS2_LOG_SEVERITY_INFO as i32,
+    /// Indication of a possible problem that librealsense2 could recover from.
+    Warn = sys::rs2_log_severity_RS2_LOG_SEVERITY_WARN as i32,
+    /// Indication of a failed operation.
+    Error = sys::rs2_log_severity_RS2_LOG_SEVERITY_ERROR as i32,
+    /// Indication of a failure that librealsense2 cannot recover from.
+    Fatal = sys::rs2_log_severity_RS2_LOG_SEVERITY_FATAL as i32,
+    /// No messages. Used as a minimum severity, this disables logging.
+    None = sys::rs2_log_severity_RS2_LOG_SEVERITY_NONE as i32,
+    /* Not included since this just tells us the total number of severities
+     *
+     * Count = sys::rs2_log_severity_RS2_LOG_SEVERITY_COUNT, */
+}
+
+impl Rs2LogSeverity {
+    /// Get the log severity variant as a `&CStr`
+    pub fn as_cstr(&self) -> &'static CStr {
+        unsafe {
+            let ptr = sys::rs2_log_severity_to_string(*self as sys::rs2_log_severity);
+            CStr::from_ptr(ptr)
+        }
+    }
+
+    /// Get the log severity variant as a `&str`
+    pub fn as_str(&self) -> &'static str {
+        self.as_cstr().to_str().unwrap()
+    }
+}
+
+impl ToString for Rs2LogSeverity {
+    fn to_string(&self) -> String {
+        self.as_str().to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::FromPrimitive;
+
+    #[test]
+    fn all_variants_exist() {
+        for i in 0..sys::rs2_log_severity_RS2_LOG_SEVERITY_COUNT as i32 {
+            assert!(
+                Rs2LogSeverity::from_i32(i).is_some(),
+                "Rs2LogSeverity variant for ordinal {} does not exist.",
+                i,
+            );
+        }
+    }
+
+    #[test]
+    fn severities_are_ordered_by_importance() {
+        assert!(Rs2LogSeverity::Debug < Rs2LogSeverity::Info);
+        assert!(Rs2LogSeverity::Warn < Rs2LogSeverity::Error);
+        assert!(Rs2LogSeverity::Fatal < Rs2LogSeverity::None);
+    }
+}
@@ -235,7 +235,7 @@ pub enum Rs2Option {
     /// Change the depth digital gain see rs2_digital_gain for values.
     DigitalGain = sys::rs2_option_RS2_OPTION_DIGITAL_GAIN as i32,
     /// The resolution mode: see rs2_sensor_mode for values.
-    SensoeMode = sys::rs2_option_RS2_OPTION_SENSOR_MODE as i32,
+    SensorMode = sys::rs2_option_RS2_OPTION_SENSOR_MODE as i32,
     /// Enable/disable Laser On constantly (GS SKU Only).
     EmitterAlwaysOn = sys::rs2_option_RS2_OPTION_EMITTER_ALWAYS_ON as i32,
     /// Depth Thermal Compensation for selected D400 SKUs.
@@ -298,6 +298,11 @@ pub enum Rs2Option {
 }
 
 impl Rs2Option {
+    /// The resolution mode: see rs2_sensor_mode for values.
+    #[deprecated(note = "misspelling of `Rs2Option::SensorMode`, use that instead")]
+    #[allow(non_upper_case_globals)]
+    pub const SensoeMode: Rs2Option = Rs2Option::SensorMode;
+
     /// Get the option as a CStr.
     pub fn to_cstr(self) -> &'static CStr {
         unsafe {
@@ -319,6 +324,7 @@ impl ToString for Rs2Option {
 }
 
 /// The range of available values of a supported option.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Rs2OptionRange {
     /// The minimum value which will be accepted for this option
     pub min: f32,
@@ -0,0 +1,39 @@
+//! Enumeration of the resolution modes of a sensor.
+
+use num_derive::{FromPrimitive, ToPrimitive};
+use realsense_sys as sys;
+
+/// The resolution mode of a sensor.
+///
+/// These are the values of the [`Rs2Option::SensorMode`](crate::kind::Rs2Option::SensorMode)
+/// option. See [`Sensor::set_sensor_mode`](crate::sensor::Sensor::set_sensor_mode).
+#[repr(i32)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rs2SensorMode {
+    /// 640x480 resolution.
+    Vga = sys::rs2_sensor_mode_RS2_SENSOR_MODE_VGA as i32,
+    /// 1024x768 resolution.
+    Xga = sys::rs2_sensor_mode_RS2_SENSOR_MODE_XGA as i32,
+    /// 320x240 resolution.
+    Qvga = sys::rs2_sensor_mode_RS2_SENSOR_MODE_QVGA as i32,
+    // Not included since this just tells us the total number of modes
+    //
+    // Count = sys::rs2_sensor_mode_RS2_SENSOR_MODE_COUNT as i32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::FromPrimitive;
+
+    #[test]
+    fn all_variants_exist() {
+        for i in 0..sys::rs2_sensor_mode_RS2_SENSOR_MODE_COUNT as i32 {
+            assert!(
+                Rs2SensorMode::from_i32(i).is_some(),
+                "Rs2SensorMode variant for ordinal {} does not exist.",
+                i,
+            );
+        }
+    }
+}
@@ -41,6 +41,45 @@ pub enum Rs2StreamKind {
      * Count = sys::rs2_stream_RS2_STREAM_COUNT, */
 }
 
+impl Rs2StreamKind {
+    /// Whether streams of this kind carry video intrinsics.
+    ///
+    /// See [`StreamProfile::intrinsics`](crate::stream_profile::StreamProfile::intrinsics).
+    pub fn supports_video_intrinsics(self) -> bool {
+        // Every variant is listed so that adding a stream kind forces a decision here.
+        match self {
+            Rs2StreamKind::Depth
+            | Rs2StreamKind::Color
+            | Rs2StreamKind::Infrared
+            | Rs2StreamKind::Fisheye => true,
+            Rs2StreamKind::Any
+            | Rs2StreamKind::Gyro
+            | Rs2StreamKind::Accel
+            | Rs2StreamKind::Gpio
+            | Rs2StreamKind::Pose
+            | Rs2StreamKind::Confidence => false,
+        }
+    }
+
+    /// Whether streams of this kind carry motion device intrinsics.
+    ///
+    /// See
+    /// [`StreamProfile::motion_intrinsics`](crate::stream_profile::StreamProfile::motion_intrinsics).
+    pub fn supports_motion_intrinsics(self) -> bool {
+        match self {
+            Rs2StreamKind::Gyro | Rs2StreamKind::Accel => true,
+            Rs2StreamKind::Any
+            | Rs2StreamKind::Depth
+            | Rs2StreamKind::Color
+            | Rs2StreamKind::Infrared
+            | Rs2StreamKind::Fisheye
+            | Rs2StreamKind::Gpio
+            | Rs2StreamKind::Pose
+            | Rs2StreamKind::Confidence => false,
+        }
+    }
+}
+
 impl std::fmt::Display for Rs2StreamKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let string = match self {
@@ -74,4 +113,16 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn intrinsics_support_is_exclusive() {
+        for i in 0..sys::rs2_stream_RS2_STREAM_COUNT as i32 {
+            let kind = Rs2StreamKind::from_i32(i).unwrap();
+            assert!(
+                !(kind.supports_video_intrinsics() && kind.supports_motion_intrinsics()),
+                "{} claims to support both video and motion intrinsics.",
+                kind,
+            );
+        }
+    }
 }
@@ -25,7 +25,49 @@ pub enum Rs2TimestampDomain {
      * Count = sys::rs2_timestamp_domain_RS2_TIMESTAMP_DOMAIN_COUNT, */
 }
 
+/// The likely precision of timestamps in a given [`Rs2TimestampDomain`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimestampPrecision {
+    /// Timestamps are taken from the device clock and are precise to within microseconds.
+    Microseconds,
+    /// Timestamps are taken by the host when the frame arrives and are subject to USB transfer
+    /// and OS scheduler jitter, making them precise to within milliseconds at best.
+    Milliseconds,
+}
+
 impl Rs2TimestampDomain {
+    /// Predicate for whether timestamps are measured by the device's internal clock.
+    pub fn is_hardware(self) -> bool {
+        self == Rs2TimestampDomain::HardwareClock
+    }
+
+    /// Predicate for whether timestamps are measured by the host system clock.
+    pub fn is_system(self) -> bool {
+        self == Rs2TimestampDomain::SystemTime
+    }
+
+    /// Predicate for whether timestamps are measured by the device's clock and converted to the
+    /// host system clock.
+    pub fn is_global(self) -> bool {
+        self == Rs2TimestampDomain::GlobalTime
+    }
+
+    /// Get the likely precision of timestamps in this domain.
+    ///
+    /// Timestamps in the [`HardwareClock`](Rs2TimestampDomain::HardwareClock) and
+    /// [`GlobalTime`](Rs2TimestampDomain::GlobalTime) domains come from the device clock, whereas
+    /// [`SystemTime`](Rs2TimestampDomain::SystemTime) timestamps are taken by the host on arrival.
+    /// Applications that need tight synchronization, e.g. between IMU and image frames, can use
+    /// this to warn when frames are only timestamped by the host.
+    pub fn precision(self) -> TimestampPrecision {
+        match self {
+            Rs2TimestampDomain::HardwareClock | Rs2TimestampDomain::GlobalTime => {
+                TimestampPrecision::Microseconds
+            }
+            Rs2TimestampDomain::SystemTime => TimestampPrecision::Milliseconds,
+        }
+    }
+
     /// Get the timestamp domain variant as a `&CStr`
     pub fn as_cstr(&self) -> &'static CStr {
         unsafe {
@@ -1,32 +1,40 @@
 #![doc = include_str!("../README.md")]
 
+pub mod advanced_mode;
 pub mod base;
 pub mod config;
 pub mod context;
+pub mod depth_pipeline;
 pub mod device;
 pub mod device_hub;
+pub mod device_monitor;
 pub mod docs;
 mod error;
+pub mod firmware_logger;
 pub mod frame;
+pub mod frame_queue;
+pub mod imu;
 pub mod kind;
+pub mod log_monitor;
 pub mod pipeline;
+pub mod processing_block;
+pub mod processing_block_kind;
+mod processing_block_list;
 pub mod sensor;
+mod sensor_list;
+pub mod software_device;
 pub mod stream_profile;
 
-// pub mod frame_queue;
-// pub mod processing_block;
-// pub mod processing_block_kind;
-// pub mod processing_block_list;
+/// Raw FFI bindings to librealsense2.
+///
+/// This is the same version of `realsense-sys` that this crate is built against, so that types
+/// such as `sys::rs2_frame` can be passed between the two without version mismatches.
+#[cfg(feature = "raw-access")]
+pub use realsense_sys as sys;
 
 /// The module collects common used traits from this crate.
 pub mod prelude {
     pub use crate::frame::{FrameCategory, FrameEx};
 }
 
-// pub use frame_queue::FrameQueue;
-// pub use processing_block::{
-//     Align, AnyProcessingBlock, Colorizer, DecimationFilter, DisparityFilter, HoleFillingFilter,
-//     HuffmanDepthDecompress, PointCloud, ProcessingBlock, RatesPrinter, SpatialFilter, Syncer,
-//     TemporalFilter, ThresholdFilter, UnitsTransform, YuyDecoder, ZeroOrderFilter,
-// };
 // pub use processing_block_list::{ProcessingBlockList, ProcessingBlockListIntoIter};
@@ -0,0 +1,121 @@
+//! Type for receiving librealsense2 log messages in a callback.
+//!
+//! A [`LogMonitor`] is returned by
+//! [`Context::set_log_callback`](crate::context::Context::set_log_callback). librealsense2 calls
+//! the callback from its own threads with every message at or above the requested severity.
+//!
+//! librealsense2 has no way of unregistering a single log callback. Dropping the monitor releases
+//! the user callback, after which messages delivered to it are discarded. Only a small slot that
+//! librealsense2 still refers to is kept alive for the remainder of the program.
+
+use crate::{check_rs2_error, context::CouldNotSetLoggerError, kind::Rs2LogSeverity};
+use num_traits::FromPrimitive;
+use realsense_sys as sys;
+use std::{
+    ffi::CStr,
+    os::raw::c_void,
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Mutex},
+};
+
+/// Callback invoked with the severity and text of a log message.
+///
+/// The callback is reference counted so that it can be invoked without holding the lock on its
+/// slot, which allows the callback to drop its own [`LogMonitor`].
+type LogCallback = Arc<dyn Fn(Rs2LogSeverity, &str) + Send + Sync + 'static>;
+
+/// Slot holding the user callback, shared with librealsense2.
+type CallbackSlot = Mutex<Option<LogCallback>>;
+
+/// Handle to a callback registered with the librealsense2 logger.
+///
+/// Dropping the handle releases the callback, so that it is no longer called for new messages.
+pub struct LogMonitor {
+    /// The slot librealsense2 reads the callback from.
+    ///
+    /// This is leaked on registration, since librealsense2 keeps referring to it for as long as
+    /// the program runs.
+    slot: &'static CallbackSlot,
+}
+
+impl std::fmt::Debug for LogMonitor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LogMonitor").finish()
+    }
+}
+
+impl Drop for LogMonitor {
+    fn drop(&mut self) {
+        // A poisoned slot only means a callback panicked, which already aborted the process.
+        if let Ok(mut callback) = self.slot.lock() {
+            callback.take();
+        }
+    }
+}
+
+impl LogMonitor {
+    /// Register `callback` with the librealsense2 logger for messages of at least `severity`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CouldNotSetLoggerError`] if the callback cannot be registered.
+    pub(crate) fn start<F>(
+        severity: Rs2LogSeverity,
+        callback: F,
+    ) -> Result<Self, CouldNotSetLoggerError>
+    where
+        F: Fn(Rs2LogSeverity, &str) + Send + Sync + 'static,
+    {
+        let callback: LogCallback = Arc::new(callback);
+        let slot: &'static CallbackSlot = Box::leak(Box::new(Mutex::new(Some(callback))));
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_log_to_callback(
+                severity as sys::rs2_log_severity,
+                Some(log_trampoline),
+                slot as *const CallbackSlot as *mut c_void,
+                &mut err,
+            );
+            check_rs2_error!(err, CouldNotSetLoggerError)?;
+        }
+
+        Ok(Self { slot })
+    }
+}
+
+/// Log callback passed to `rs2_log_to_callback`.
+///
+/// `data` is the [`CallbackSlot`] of the [`LogMonitor`] the callback was registered for.
+unsafe extern "C" fn log_trampoline(
+    severity: sys::rs2_log_severity,
+    message: *const sys::rs2_log_message,
+    data: *mut c_void,
+) {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let slot = &*data.cast::<CallbackSlot>();
+        let callback = match slot.lock() {
+            Ok(callback) => callback.clone(),
+            Err(_) => return,
+        };
+        let callback = match callback {
+            Some(callback) => callback,
+            None => return,
+        };
+
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        let text = sys::rs2_get_raw_log_message(message, &mut err);
+        if err.as_ref().is_some() {
+            sys::rs2_free_error(err);
+            return;
+        }
+
+        let severity = Rs2LogSeverity::from_u32(severity).unwrap_or(Rs2LogSeverity::None);
+        callback(severity, &CStr::from_ptr(text).to_string_lossy());
+    }));
+
+    if result.is_err() {
+        eprintln!("Log callback panicked");
+        std::process::abort();
+    }
+}
@@ -15,12 +15,21 @@
 //! [`InactivePipeline`] type, while interfaces that are only valid with an active (started)
 //! pipeline are only provided alongside the [`ActivePipeline`] type.
 //!
+//! A pipeline can alternatively be started with a callback, in which case it becomes a
+//! [`StreamingPipeline`] that pushes every frameset to the callback instead of being waited or
+//! polled for frames.
+//!
 
 mod active;
+#[cfg(feature = "async")]
+mod async_active;
 mod inactive;
 mod profile;
+mod statistics;
 mod streaming;
 
-pub use active::{ActivePipeline, FrameWaitError};
+pub use active::{ActivePipeline, FrameWaitError, PipelineStopError};
 pub use inactive::{InactivePipeline, PipelineActivationError, PipelineConstructionError};
 pub use profile::{PipelineProfile, PipelineProfileConstructionError};
+pub use statistics::{LatencyHistogram, PipelineStatistics};
+pub use streaming::StreamingPipeline;
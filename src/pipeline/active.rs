@@ -1,10 +1,20 @@
 //! Defines the pipeline type.
 
+#[cfg(feature = "async")]
+use super::async_active::InFlightWaits;
 use super::{inactive::InactivePipeline, profile::PipelineProfile};
-use crate::{check_rs2_error, frame::CompositeFrame, kind::Rs2Exception};
+use crate::{
+    check_rs2_error,
+    device::{Device, DeviceConstructionError},
+    frame::CompositeFrame,
+    kind::Rs2Exception,
+    stream_profile::StreamProfile,
+};
 use anyhow::Result;
 use realsense_sys as sys;
-use std::{ptr::NonNull, task::Poll, time::Duration};
+#[cfg(feature = "async")]
+use std::sync::Arc;
+use std::{collections::HashMap, ptr::NonNull, task::Poll, time::Duration};
 use thiserror::Error;
 
 /// Enumeration over possible errors that can occur when waiting for a frame.
@@ -19,20 +29,46 @@ pub enum FrameWaitError {
     /// The associated function timed out while waiting for frames.
     #[error("Timed out while waiting for frame.")]
     DidTimeoutBeforeFrameArrival,
+    /// The tokio runtime shut down before the wait returned.
+    ///
+    /// Only returned by `ActivePipeline::wait_async`, which requires the `async` feature.
+    #[error("The tokio runtime shut down while waiting for frames.")]
+    RuntimeShutDown,
 }
 
+/// An error type describing failure to stop a pipeline.
+#[derive(Error, Debug)]
+#[error("Could not stop the pipeline. Type: {0}; Reason: {1}")]
+pub struct PipelineStopError(pub Rs2Exception, pub String);
+
 /// Type representing an "active" pipeline which is configured and can acquire frames.
 #[derive(Debug)]
 pub struct ActivePipeline {
     /// A (non-null) pointer to the pipeline.
-    pipeline_ptr: NonNull<sys::rs2_pipeline>,
+    pub(super) pipeline_ptr: NonNull<sys::rs2_pipeline>,
     /// The pipeline's profile, which contains the device the pipeline is configured for alongside
     /// the stream profiles for streams in the pipeline.
     profile: PipelineProfile,
+    /// The timeout used by [`ActivePipeline::wait`] when no timeout is passed.
+    default_timeout: Duration,
+    /// The last frame number received on each stream, keyed by stream unique ID.
+    last_frame_numbers: HashMap<i32, u64>,
+    /// The number of frames dropped before the last frameset was received.
+    dropped_frames: u64,
+    /// Waits started by `wait_async` that may still be running in the background.
+    #[cfg(feature = "async")]
+    pub(super) waits: Arc<InFlightWaits>,
 }
 
 impl Drop for ActivePipeline {
     fn drop(&mut self) {
+        #[cfg(feature = "async")]
+        {
+            if self.waits.defer_delete(self.pipeline_ptr) {
+                return;
+            }
+        }
+
         unsafe {
             sys::rs2_delete_pipeline(self.pipeline_ptr.as_ptr());
         }
@@ -45,39 +81,123 @@ impl ActivePipeline {
     /// Constructs a new active pipeline from the constituent components
     ///
     /// This is only to be used / called from the [`InactivePipeline`] type.
-    pub(crate) fn new(pipeline_ptr: NonNull<sys::rs2_pipeline>, profile: PipelineProfile) -> Self {
+    pub(crate) fn new(
+        pipeline_ptr: NonNull<sys::rs2_pipeline>,
+        profile: PipelineProfile,
+        default_timeout: Duration,
+    ) -> Self {
         Self {
             pipeline_ptr,
             profile,
+            default_timeout,
+            last_frame_numbers: HashMap::new(),
+            dropped_frames: 0,
+            #[cfg(feature = "async")]
+            waits: Arc::default(),
         }
     }
 
+    /// Set the timeout used by [`ActivePipeline::wait`] when it is called without a timeout.
+    ///
+    /// See [`InactivePipeline::with_timeout`] for setting this before the pipeline is started.
+    pub fn set_default_timeout(&mut self, timeout: Duration) {
+        self.default_timeout = timeout;
+    }
+
     /// Gets the active profile of pipeline.
     pub fn profile(&self) -> &PipelineProfile {
         &self.profile
     }
 
+    /// Gets the stream profiles of all streams that are active on the pipeline.
+    ///
+    /// This is a convenience for [`PipelineProfile::streams`] on the active profile.
+    pub fn active_streams(&self) -> &[StreamProfile] {
+        self.profile.streams()
+    }
+
+    /// Gets the device that the pipeline is currently streaming from.
+    ///
+    /// Unlike [`PipelineProfile::device`], this queries librealsense2 for the active profile and
+    /// returns a new, owned handle to the device.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DeviceConstructionError::CouldNotGetDeviceFromPipeline`] if the active profile or
+    /// its device cannot be retrieved from the pipeline.
+    pub fn active_device(&self) -> Result<Device, DeviceConstructionError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let profile_ptr =
+                sys::rs2_pipeline_get_active_profile(self.pipeline_ptr.as_ptr(), &mut err);
+            check_rs2_error!(err, DeviceConstructionError::CouldNotGetDeviceFromPipeline)?;
+
+            let device_ptr = sys::rs2_pipeline_profile_get_device(profile_ptr, &mut err);
+            sys::rs2_delete_pipeline_profile(profile_ptr);
+            check_rs2_error!(err, DeviceConstructionError::CouldNotGetDeviceFromPipeline)?;
+
+            Ok(Device::from(NonNull::new(device_ptr).unwrap()))
+        }
+    }
+
     /// Stop the pipeline.
     ///
     /// This method consumes the pipeline instance and returns pipeline markered inactive.
-    pub fn stop(self) -> InactivePipeline {
+    ///
+    /// With the `async` feature, if a future returned by `wait_async` was dropped before its wait
+    /// returned, this first blocks until the wait returns.
+    ///
+    /// # Errors
+    ///
+    /// Returns the pipeline alongside a [`PipelineStopError`] if librealsense2 fails to stop the
+    /// pipeline. The pipeline is still active in that case, so stopping it can be retried, or it
+    /// can be dropped to release it regardless.
+    #[allow(clippy::result_large_err)]
+    pub fn stop(self) -> Result<InactivePipeline, (Self, PipelineStopError)> {
+        // A wait left running by a dropped `wait_async` future still uses the pipeline, which the
+        // returned inactive pipeline may delete or restart.
+        #[cfg(feature = "async")]
+        self.waits.wait_idle();
+
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
-
-            // The only "error" that can occur here is if the pipeline pointer is null.
-            //
-            // We know it is not (state is managed so that this isn't a possibility, and we use
-            // `NonNull` to try and guarantee that even beyond our state management), so there
-            // dealing with the error (and thus returning a result type) is superfluous here.
             sys::rs2_pipeline_stop(self.pipeline_ptr.as_ptr(), &mut err);
+            if let Err(stop_error) = check_rs2_error!(err, PipelineStopError) {
+                return Err((self, stop_error));
+            }
 
-            let inactive = InactivePipeline::new(self.pipeline_ptr);
+            let inactive =
+                InactivePipeline::new(self.pipeline_ptr).with_timeout(self.default_timeout);
 
             std::mem::forget(self);
-            inactive
+            Ok(inactive)
         }
     }
 
+    /// Gets the number of frames that were dropped before the last frameset was received.
+    ///
+    /// librealsense2 queues a limited number of framesets (see
+    /// [`Rs2Option::FramesQueueSize`](crate::kind::Rs2Option::FramesQueueSize)) and silently
+    /// drops the oldest ones if [`ActivePipeline::wait`] or [`ActivePipeline::poll`] is not called
+    /// often enough. Since librealsense2 does not report these drops, they are detected from gaps
+    /// in the frame numbers of each stream between the last two framesets received. The count is
+    /// summed over all streams and updated every time a frameset is received.
+    pub fn dropped_since_last_wait(&self) -> u64 {
+        self.dropped_frames
+    }
+
+    /// The timeout to wait for, in milliseconds, falling back to the default timeout if `timeout`
+    /// is `None`.
+    pub(super) fn timeout_ms(&self, timeout: Option<Duration>) -> u32 {
+        timeout.unwrap_or(self.default_timeout).as_millis() as u32
+    }
+
+    /// Update the dropped frame count from a newly received frameset.
+    pub(super) fn track_dropped_frames(&mut self, frame: &CompositeFrame) {
+        self.dropped_frames =
+            count_dropped_frames(&mut self.last_frame_numbers, &frame.frame_numbers());
+    }
+
     /// Waits to get a new composite frame, blocking the calling thread.
     ///
     /// Returns a composite frame from the pipeline, blocking the calling thread until a frame is
@@ -88,7 +208,9 @@ impl ActivePipeline {
     ///
     /// * `timeout_ms` - The timeout in milliseconds. If the thread blocks for longer than this
     /// duration, it will exit early with a [`FrameWaitError::DidTimeoutBeforeFrameArrival`]. If
-    /// `None` is passed in, the [default timeout](realsense_sys::RS2_DEFAULT_TIMEOUT) is applied.
+    /// `None` is passed in, the default timeout is applied. This is
+    /// [`RS2_DEFAULT_TIMEOUT`](realsense_sys::RS2_DEFAULT_TIMEOUT) unless changed with
+    /// [`InactivePipeline::with_timeout`] or [`ActivePipeline::set_default_timeout`].
     ///
     /// # Errors
     ///
@@ -98,36 +220,13 @@ impl ActivePipeline {
     /// Returns [`FrameWaitError::DidTimeoutBeforeFrameArrival`] if the thread waits more than
     /// `timeout_ms` (in milliseconds) without returning a frame.
     pub fn wait(&mut self, timeout_ms: Option<Duration>) -> Result<CompositeFrame, FrameWaitError> {
-        let timeout_ms = match timeout_ms {
-            Some(d) => d.as_millis() as u32,
-            None => sys::RS2_DEFAULT_TIMEOUT,
-        };
+        let timeout_ms = self.timeout_ms(timeout_ms);
 
         unsafe {
-            let mut err = std::ptr::null_mut::<sys::rs2_error>();
-            let mut frame = std::ptr::null_mut::<sys::rs2_frame>();
-
-            // NOTE: You may notice that there is a `sys::rs2_pipeline_wait_for_frames` and you
-            // might wonder why we only use this variant. Primarily, they do the same thing, but
-            // this API is a bit cleaner since it makes it easy to detect if a timeout occurred.
-            // If you use `rs2_pipeline_wait_for_frames` instead of
-            // `rs2_pipeline_try_wait_for_frames` then you need to parse the returned `rs2_error`
-            // message to determine if a timeout occurred. Here, we can just check if
-            // `did_get_frame` is false (0), and provided no other errors occurred, then that is
-            // indicative of a timeout.
-            let did_get_frame = sys::rs2_pipeline_try_wait_for_frames(
-                self.pipeline_ptr.as_ptr(),
-                &mut frame,
-                timeout_ms,
-                &mut err,
-            );
-            check_rs2_error!(err, FrameWaitError::DidErrorDuringFrameWait)?;
-
-            if did_get_frame != 0 {
-                Ok(CompositeFrame::from(NonNull::new(frame).unwrap()))
-            } else {
-                Err(FrameWaitError::DidTimeoutBeforeFrameArrival)
-            }
+            let frame = try_wait_for_frames(self.pipeline_ptr.as_ptr(), timeout_ms)?;
+            let frame = CompositeFrame::from(frame);
+            self.track_dropped_frames(&frame);
+            Ok(frame)
         }
     }
 
@@ -153,12 +252,89 @@ impl ActivePipeline {
             check_rs2_error!(err, FrameWaitError::DidErrorDuringFramePoll)?;
 
             if did_get_frame != 0 {
-                Ok(Poll::Ready(CompositeFrame::from(
-                    NonNull::new(frame_ptr).unwrap(),
-                )))
+                let frame = CompositeFrame::from(NonNull::new(frame_ptr).unwrap());
+                self.track_dropped_frames(&frame);
+                Ok(Poll::Ready(frame))
             } else {
                 Ok(Poll::Pending)
             }
         }
     }
 }
+
+/// Wait for the next frameset on the pipeline pointed to by `pipeline_ptr`.
+///
+/// # Safety
+///
+/// `pipeline_ptr` must point to a started pipeline that stays alive until this returns.
+pub(super) unsafe fn try_wait_for_frames(
+    pipeline_ptr: *mut sys::rs2_pipeline,
+    timeout_ms: u32,
+) -> Result<NonNull<sys::rs2_frame>, FrameWaitError> {
+    let mut err = std::ptr::null_mut::<sys::rs2_error>();
+    let mut frame = std::ptr::null_mut::<sys::rs2_frame>();
+
+    // NOTE: You may notice that there is a `sys::rs2_pipeline_wait_for_frames` and you
+    // might wonder why we only use this variant. Primarily, they do the same thing, but
+    // this API is a bit cleaner since it makes it easy to detect if a timeout occurred.
+    // If you use `rs2_pipeline_wait_for_frames` instead of
+    // `rs2_pipeline_try_wait_for_frames` then you need to parse the returned `rs2_error`
+    // message to determine if a timeout occurred. Here, we can just check if
+    // `did_get_frame` is false (0), and provided no other errors occurred, then that is
+    // indicative of a timeout.
+    let did_get_frame =
+        sys::rs2_pipeline_try_wait_for_frames(pipeline_ptr, &mut frame, timeout_ms, &mut err);
+    check_rs2_error!(err, FrameWaitError::DidErrorDuringFrameWait)?;
+
+    if did_get_frame != 0 {
+        Ok(NonNull::new(frame).unwrap())
+    } else {
+        Err(FrameWaitError::DidTimeoutBeforeFrameArrival)
+    }
+}
+
+/// Count the frames missing between the last frame numbers seen on each stream and
+/// `frame_numbers`, then record `frame_numbers` as the last ones seen.
+///
+/// Each entry of `frame_numbers` is a `(stream unique ID, frame number)` pair. Streams that have
+/// not been seen before, or whose frame numbers went backwards (e.g. after a reset), count no
+/// dropped frames.
+fn count_dropped_frames(
+    last_frame_numbers: &mut HashMap<i32, u64>,
+    frame_numbers: &[(i32, u64)],
+) -> u64 {
+    frame_numbers
+        .iter()
+        .map(|&(unique_id, frame_number)| {
+            match last_frame_numbers.insert(unique_id, frame_number) {
+                Some(last) if frame_number > last => frame_number - last - 1,
+                _ => 0,
+            }
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dropped_frames_are_counted_per_stream() {
+        let mut last_frame_numbers = HashMap::new();
+
+        assert_eq!(
+            count_dropped_frames(&mut last_frame_numbers, &[(1, 10), (2, 5)]),
+            0
+        );
+        assert_eq!(
+            count_dropped_frames(&mut last_frame_numbers, &[(1, 11), (2, 8)]),
+            2
+        );
+        assert_eq!(count_dropped_frames(&mut last_frame_numbers, &[(1, 14)]), 2);
+        // Frame numbers that reset are not counted as drops.
+        assert_eq!(
+            count_dropped_frames(&mut last_frame_numbers, &[(1, 0), (2, 9)]),
+            0
+        );
+    }
+}
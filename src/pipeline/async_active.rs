@@ -0,0 +1,235 @@
+//! Asynchronous frame waiting for [`ActivePipeline`].
+//!
+//! This module is only available with the `async` feature enabled.
+
+use super::active::{try_wait_for_frames, ActivePipeline, FrameWaitError};
+use crate::frame::CompositeFrame;
+use realsense_sys as sys;
+use std::{
+    ptr::NonNull,
+    sync::{Arc, Condvar, Mutex},
+    time::Duration,
+};
+
+/// A raw pointer that can be moved onto tokio's blocking thread pool.
+struct SendPtr<T>(NonNull<T>);
+
+// SAFETY: librealsense2 allows pipelines and frames to be used from any thread, as long as they
+// are only used by one thread at a time. `wait_async` holds a mutable borrow of the pipeline for
+// as long as the future is alive, and the pipeline is not deleted before the wait has returned.
+unsafe impl<T> Send for SendPtr<T> {}
+
+/// A frame waited for on tokio's blocking thread pool.
+///
+/// If the future returned by [`ActivePipeline::wait_async`] is dropped before the wait returns,
+/// nothing receives the frame. Tokio then drops it along with the result of the blocking task,
+/// which releases it.
+struct PendingFrame(NonNull<sys::rs2_frame>);
+
+// SAFETY: see `SendPtr`. The frame is only ever owned by one thread at a time.
+unsafe impl Send for PendingFrame {}
+
+impl Drop for PendingFrame {
+    fn drop(&mut self) {
+        unsafe {
+            sys::rs2_release_frame(self.0.as_ptr());
+        }
+    }
+}
+
+impl PendingFrame {
+    /// Take ownership of the frame as a composite frame.
+    fn into_frame(self) -> CompositeFrame {
+        let frame = CompositeFrame::from(self.0);
+        std::mem::forget(self);
+        frame
+    }
+}
+
+/// Tracks the blocking waits of [`ActivePipeline::wait_async`] that are still running.
+///
+/// A future returned by `wait_async` can be dropped before its blocking wait returns. The wait
+/// then keeps using the pipeline on tokio's blocking thread pool, so the pipeline must not be
+/// deleted until it returns. Rather than blocking the thread that drops the future or the
+/// pipeline, the pipeline is handed over to the running waits, and the last of them to return
+/// deletes it.
+#[derive(Debug, Default)]
+pub(super) struct InFlightWaits {
+    /// The running waits, and the pipeline if it was dropped while they were running.
+    state: Mutex<InFlightState>,
+    /// Notified whenever the last running wait returns.
+    idle: Condvar,
+}
+
+/// The state guarded by [`InFlightWaits`].
+#[derive(Debug, Default)]
+struct InFlightState {
+    /// The number of blocking waits that have not returned yet.
+    running: usize,
+    /// The pipeline, if it was dropped while waits were still running.
+    orphaned: Option<NonNull<sys::rs2_pipeline>>,
+}
+
+// SAFETY: the orphaned pipeline is only ever deleted once, by the last wait to return.
+unsafe impl Send for InFlightState {}
+
+impl InFlightWaits {
+    /// Record that a blocking wait is about to start.
+    fn begin(&self) {
+        self.state.lock().unwrap().running += 1;
+    }
+
+    /// Record that a blocking wait has returned, or was cancelled before it started.
+    ///
+    /// Returns the pipeline to delete if it was dropped while this was the last running wait.
+    fn end(&self) -> Option<NonNull<sys::rs2_pipeline>> {
+        let mut state = self.state.lock().unwrap();
+        state.running -= 1;
+
+        if state.running == 0 {
+            self.idle.notify_all();
+            state.orphaned.take()
+        } else {
+            None
+        }
+    }
+
+    /// Hand `pipeline` over to the running waits, to be deleted once the last of them returns.
+    ///
+    /// Returns `false` if no wait is running, in which case the caller has to delete the pipeline
+    /// itself.
+    pub(super) fn defer_delete(&self, pipeline: NonNull<sys::rs2_pipeline>) -> bool {
+        let mut state = self.state.lock().unwrap();
+
+        if state.running == 0 {
+            false
+        } else {
+            state.orphaned = Some(pipeline);
+            true
+        }
+    }
+
+    /// Block the calling thread until every running wait has returned.
+    pub(super) fn wait_idle(&self) {
+        let mut state = self.state.lock().unwrap();
+        while state.running > 0 {
+            state = self.idle.wait(state).unwrap();
+        }
+    }
+}
+
+/// A blocking wait that is registered with [`InFlightWaits`].
+///
+/// This is owned by the closure run on tokio's blocking thread pool, so it is dropped once the
+/// wait returns, or when the closure is dropped without running because the runtime shut down.
+struct RunningWait(Arc<InFlightWaits>);
+
+impl RunningWait {
+    /// Register a new blocking wait with `waits`.
+    fn begin(waits: &Arc<InFlightWaits>) -> Self {
+        waits.begin();
+        Self(Arc::clone(waits))
+    }
+}
+
+impl Drop for RunningWait {
+    fn drop(&mut self) {
+        if let Some(pipeline) = self.0.end() {
+            unsafe {
+                sys::rs2_delete_pipeline(pipeline.as_ptr());
+            }
+        }
+    }
+}
+
+impl ActivePipeline {
+    /// Waits to get a new composite frame without blocking the async executor.
+    ///
+    /// This is analogous to [`ActivePipeline::wait`], but performs the wait on tokio's blocking
+    /// thread pool (see [`tokio::task::spawn_blocking`]). It must therefore be awaited from within
+    /// a tokio runtime.
+    ///
+    /// The returned future can be dropped at any time without blocking. If it is dropped before
+    /// the underlying wait returns, the wait keeps running in the background for at most
+    /// `timeout`, and any frame it returns is released. [`ActivePipeline::stop`] blocks until such
+    /// a wait has returned, while dropping the pipeline defers deleting it until then.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrameWaitError::DidErrorDuringFrameWait`] if an internal error occurs while
+    /// waiting for next frame(s).
+    ///
+    /// Returns [`FrameWaitError::DidTimeoutBeforeFrameArrival`] if no frame arrives within
+    /// `timeout`, or within the default timeout if `timeout` is `None`.
+    ///
+    /// Returns [`FrameWaitError::RuntimeShutDown`] if the tokio runtime shuts down before the
+    /// wait returns.
+    pub async fn wait_async(
+        &mut self,
+        timeout: Option<Duration>,
+    ) -> Result<CompositeFrame, FrameWaitError> {
+        let timeout_ms = self.timeout_ms(timeout);
+        let pipeline = SendPtr(self.pipeline_ptr);
+        let running = RunningWait::begin(&self.waits);
+
+        let result = tokio::task::spawn_blocking(move || {
+            let _running = running;
+
+            // SAFETY: the pipeline is not deleted before `_running` is dropped, which does not
+            // happen before this closure has returned.
+            unsafe { try_wait_for_frames(pipeline.0.as_ptr(), timeout_ms).map(PendingFrame) }
+        })
+        .await;
+
+        let frame = match result {
+            Ok(frame) => frame?.into_frame(),
+            Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+            Err(_) => return Err(FrameWaitError::RuntimeShutDown),
+        };
+
+        self.track_dropped_frames(&frame);
+        Ok(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn pipeline_is_deleted_by_the_last_running_wait() {
+        let waits = InFlightWaits::default();
+        let pipeline = NonNull::dangling();
+
+        waits.begin();
+        waits.begin();
+        assert!(waits.defer_delete(pipeline));
+
+        assert_eq!(waits.end(), None);
+        assert_eq!(waits.end(), Some(pipeline));
+    }
+
+    #[test]
+    fn pipeline_is_not_deferred_without_running_waits() {
+        let waits = InFlightWaits::default();
+
+        waits.begin();
+        assert_eq!(waits.end(), None);
+        assert!(!waits.defer_delete(NonNull::dangling()));
+    }
+
+    #[test]
+    fn wait_idle_returns_once_running_waits_end() {
+        let waits = Arc::new(InFlightWaits::default());
+        waits.begin();
+
+        let ending = {
+            let waits = Arc::clone(&waits);
+            thread::spawn(move || assert_eq!(waits.end(), None))
+        };
+
+        waits.wait_idle();
+        ending.join().unwrap();
+    }
+}
@@ -1,11 +1,20 @@
 //! Type for representing an "inactive" pipeline which is unconfigured and cannot acquire frames.
 
-use super::{active::ActivePipeline, profile::PipelineProfile, streaming::{StreamingPipeline, trampoline}};
-use crate::{check_rs2_error, config::Config, context::Context, kind::Rs2Exception};
-use crate::frame::FrameCategory;
+use super::{
+    active::ActivePipeline,
+    profile::PipelineProfile,
+    streaming::{trampoline, StreamingPipeline},
+};
+use crate::{
+    check_rs2_error,
+    config::Config,
+    context::{Context, SharedContext},
+    frame::CompositeFrame,
+    kind::{Rs2Exception, Rs2Format, Rs2StreamKind},
+};
 use anyhow::Result;
 use realsense_sys as sys;
-use std::{convert::TryFrom, ptr::NonNull};
+use std::{convert::TryFrom, ffi::CStr, os::raw::c_void, ptr::NonNull, time::Duration};
 use thiserror::Error;
 
 /// Enumeration of possible errors that can occur during pipeline construction.
@@ -34,6 +43,8 @@ pub enum PipelineActivationError {
 pub struct InactivePipeline {
     /// A (non-null) pointer to the pipeline.
     pipeline_ptr: NonNull<sys::rs2_pipeline>,
+    /// The timeout used by [`ActivePipeline::wait`] when no timeout is passed, once started.
+    default_timeout: Duration,
 }
 
 impl Drop for InactivePipeline {
@@ -65,12 +76,36 @@ impl TryFrom<&Context> for InactivePipeline {
     }
 }
 
+impl TryFrom<&SharedContext> for InactivePipeline {
+    type Error = anyhow::Error;
+
+    /// Create a pipeline from a context that is shared with other pipelines or device hubs.
+    fn try_from(context: &SharedContext) -> Result<Self, Self::Error> {
+        Self::try_from(context.as_ref())
+    }
+}
+
 impl InactivePipeline {
     /// Constructs a new inactive pipeline from the constituent components
     ///
     /// This is only to be used / called from the [`ActivePipeline`] type.
     pub(crate) fn new(pipeline_ptr: NonNull<sys::rs2_pipeline>) -> Self {
-        Self { pipeline_ptr }
+        Self {
+            pipeline_ptr,
+            default_timeout: Duration::from_millis(sys::RS2_DEFAULT_TIMEOUT as u64),
+        }
+    }
+
+    /// Set the timeout that the started pipeline uses when [`ActivePipeline::wait`] is called
+    /// without a timeout.
+    ///
+    /// Defaults to [`RS2_DEFAULT_TIMEOUT`](realsense_sys::RS2_DEFAULT_TIMEOUT) (5 seconds). Longer
+    /// timeouts can help over high-latency USB connections, while shorter ones detect a stalled
+    /// device sooner. The timeout can be changed after starting with
+    /// [`ActivePipeline::set_default_timeout`].
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.default_timeout = timeout;
+        self
     }
 
     /// Start the pipeline with an optional config.
@@ -97,28 +132,144 @@ impl InactivePipeline {
             check_rs2_error!(err, PipelineActivationError::CouldNotStartPipelineError)?;
 
             let profile = PipelineProfile::try_from(NonNull::new(profile_ptr).unwrap())?;
-            let active = ActivePipeline::new(self.pipeline_ptr, profile);
+            let active = ActivePipeline::new(self.pipeline_ptr, profile, self.default_timeout);
 
             std::mem::forget(self);
             Ok(active)
         }
     }
 
-    pub fn start_streaming<F>(self, f: F) -> Result<StreamingPipeline> 
+    /// Create a pipeline from `context` and start it with the default configuration.
+    ///
+    /// This is equivalent to constructing an [`InactivePipeline`] from the context and calling
+    /// [`start`](InactivePipeline::start) with no config, which streams the default streams of the
+    /// first available device.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PipelineConstructionError::CouldNotCreatePipelineFromContext`] if the pipeline
+    /// could not be created, or any of the errors that [`InactivePipeline::start`] can return.
+    pub fn with_default_config(context: &Context) -> Result<ActivePipeline> {
+        Self::try_from(context)?.start(None)
+    }
+
+    /// Create a pipeline from `context` and start it streaming from the device with the given
+    /// serial number.
+    ///
+    /// Each entry of `streams` is a `(kind, format, framerate)` tuple describing a stream to
+    /// enable. The resolution of each stream is left for librealsense2 to pick.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PipelineConstructionError::CouldNotCreatePipelineFromContext`] if the pipeline
+    /// could not be created, a [`ConfigurationError`](crate::config::ConfigurationError) if the
+    /// device or any of the streams could not be enabled, or any of the errors that
+    /// [`InactivePipeline::start`] can return.
+    pub fn start_with_device_serial(
+        context: &Context,
+        serial: &CStr,
+        streams: &[(Rs2StreamKind, Rs2Format, usize)],
+    ) -> Result<ActivePipeline> {
+        let mut config = Config::with_device_serial(serial)?;
+        config.disable_all_streams()?;
+        for &(kind, format, framerate) in streams {
+            config.enable_stream(kind, None, 0, 0, format, framerate)?;
+        }
+
+        Self::try_from(context)?.start(Some(config))
+    }
+
+    /// Start the pipeline with the default configuration, calling `callback` with every
+    /// frameset.
+    ///
+    /// Unlike [`InactivePipeline::start`], framesets are pushed to the callback from a
+    /// librealsense2 thread as soon as they arrive, rather than waited or polled for. The callback
+    /// must not panic. If it does, the process is aborted, since unwinding across the FFI boundary
+    /// is undefined behaviour.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PipelineActivationError::CouldNotStartPipelineError`] if the pipeline could not
+    /// be started, or an error if the active profile could not be retrieved.
+    pub fn start_streaming<F>(self, callback: F) -> Result<StreamingPipeline<F>>
     where
-        F: FnMut(&impl IntoFrame) + Send + 'static
+        F: FnMut(CompositeFrame) + Send + 'static,
+    {
+        self.start_streaming_impl(None, callback)
+    }
+
+    /// Start the pipeline with `config`, calling `callback` with every frameset.
+    ///
+    /// This is the same as [`InactivePipeline::start_streaming`], but streams the device and
+    /// streams requested by `config`. It uses `rs2_pipeline_start_with_config_and_callback`, which
+    /// is available in every librealsense2 version supported by `realsense-sys` (2.54 and later).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PipelineActivationError::ConfigCannotBeResolved`] if the config cannot be
+    /// resolved, [`PipelineActivationError::CouldNotStartPipelineError`] if the pipeline could not
+    /// be started, or an error if the active profile could not be retrieved.
+    pub fn start_streaming_with_config<F>(
+        self,
+        config: Config,
+        callback: F,
+    ) -> Result<StreamingPipeline<F>>
+    where
+        F: FnMut(CompositeFrame) + Send + 'static,
+    {
+        if !self.can_resolve(&config) {
+            return Err(anyhow::anyhow!(
+                PipelineActivationError::ConfigCannotBeResolved
+            ));
+        }
+
+        self.start_streaming_impl(Some(config), callback)
+    }
+
+    /// Start the pipeline with an optional config, calling `callback` with every frameset.
+    fn start_streaming_impl<F>(
+        self,
+        config: Option<Config>,
+        callback: F,
+    ) -> Result<StreamingPipeline<F>>
+    where
+        F: FnMut(CompositeFrame) + Send + 'static,
     {
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let callback_ptr = NonNull::new(Box::into_raw(Box::new(callback))).unwrap();
 
-            let f = Box::into_raw(Box::new(f));
-            let profile_ptr = sys::rs2_pipeline_start_with_callback(self.pipeline_ptr.as_ptr(), Some(trampoline::<F>), f, &mut err);
+            let profile_ptr = if let Some(conf) = config {
+                sys::rs2_pipeline_start_with_config_and_callback(
+                    self.pipeline_ptr.as_ptr(),
+                    conf.get_raw().as_ptr(),
+                    Some(trampoline::<F>),
+                    callback_ptr.as_ptr().cast::<c_void>(),
+                    &mut err,
+                )
+            } else {
+                sys::rs2_pipeline_start_with_callback(
+                    self.pipeline_ptr.as_ptr(),
+                    Some(trampoline::<F>),
+                    callback_ptr.as_ptr().cast::<c_void>(),
+                    &mut err,
+                )
+            };
 
-            check_rs2_error!(err, PipelineActivationError::CouldNotStartPipelineError)?;
+            if let Err(e) =
+                check_rs2_error!(err, PipelineActivationError::CouldNotStartPipelineError)
+            {
+                drop(Box::from_raw(callback_ptr.as_ptr()));
+                return Err(e.into());
+            }
 
             let profile = PipelineProfile::try_from(NonNull::new(profile_ptr).unwrap())?;
-
-            let streaming = StreamingPipeline::new(self.pipeline_ptr, profile, f);
+            let streaming = StreamingPipeline::new(
+                self.pipeline_ptr,
+                profile,
+                self.default_timeout,
+                callback_ptr,
+            );
 
             std::mem::forget(self);
             Ok(streaming)
@@ -179,14 +330,3 @@ impl InactivePipeline {
         }
     }
 }
-
-impl IntoFrame for NonNull<sys::rs2_frame> { }
-
-pub(crate) trait IntoFrame {
-    fn of_type<F>(self) -> Option<F>
-    where
-        F: TryFrom<Self> + FrameCategory
-    {
-        F::try_from(self).ok()
-    }
-}
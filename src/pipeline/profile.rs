@@ -1,9 +1,15 @@
 //! Defines a type which holds the device & streams associated with an `ActivePipeline`.
 
-use crate::{check_rs2_error, device::Device, kind::Rs2Exception, stream_profile::StreamProfile};
+use crate::{
+    check_rs2_error,
+    context::Context,
+    device::Device,
+    kind::{Rs2CameraInfo, Rs2Exception, Rs2StreamKind},
+    stream_profile::StreamProfile,
+};
 use anyhow::Result;
 use realsense_sys as sys;
-use std::{convert::TryFrom, ptr::NonNull};
+use std::{collections::HashSet, convert::TryFrom, ptr::NonNull};
 use thiserror::Error;
 
 /// Type representing the device and streams associated with a pipeline.
@@ -100,4 +106,49 @@ impl PipelineProfile {
     pub fn streams(&self) -> &Vec<StreamProfile> {
         &self.streams
     }
+
+    /// Gets the stream of the given kind associated with a pipeline.
+    ///
+    /// If `index` is `None`, the first stream of that kind is returned. This is the same stream
+    /// `rs2_pipeline_profile_get_stream` would return with an index of -1; the lookup is done on
+    /// the streams that were retrieved when the profile was constructed.
+    ///
+    /// Returns `None` if no stream of the given kind and index is active.
+    pub fn stream(&self, kind: Rs2StreamKind, index: Option<usize>) -> Option<&StreamProfile> {
+        self.streams.iter().find(|stream| {
+            stream.kind() == kind && index.is_none_or(|index| stream.index() == index)
+        })
+    }
+
+    /// Consumes the profile and returns the streams associated with the pipeline.
+    ///
+    /// The stream profiles are owned and remain valid after the profile itself is dropped.
+    pub fn into_streams(self) -> Vec<StreamProfile> {
+        self.streams
+    }
+
+    /// Predicate for checking if the device this profile was resolved for is still available.
+    ///
+    /// A profile obtained from [`InactivePipeline::resolve`](super::InactivePipeline::resolve)
+    /// only tells you that the configuration could be satisfied at the time it was resolved. This
+    /// re-enumerates the devices of `context`, which should be the context the pipeline was created
+    /// from, and checks that the profile's device (identified by its serial number) is still among
+    /// them.
+    ///
+    /// This is not atomic with starting the pipeline: the device can still be disconnected
+    /// between this check and the call to `start`. Using `resolve` and `can_start` together does
+    /// however give higher confidence that `start` will succeed.
+    ///
+    /// Returns false if the device has no serial number.
+    pub fn can_start(&self, context: &Context) -> bool {
+        let serial = match self.device.info(Rs2CameraInfo::SerialNumber) {
+            Some(serial) => serial,
+            None => return false,
+        };
+
+        context
+            .query_devices(HashSet::new())
+            .iter()
+            .any(|d| d.info(Rs2CameraInfo::SerialNumber) == Some(serial))
+    }
 }
@@ -0,0 +1,237 @@
+//! Types for profiling the frame delivery of a pipeline.
+
+use crate::{
+    frame::FrameEx,
+    kind::{Rs2FrameMetadata, Rs2TimestampDomain},
+};
+use std::time::Duration;
+
+/// The bucket boundaries used by [`PipelineStatistics::new`], in milliseconds.
+const DEFAULT_LATENCY_BUCKETS_MS: [u64; 8] = [0, 10, 20, 30, 50, 100, 200, 500];
+
+/// A histogram of frame delivery latencies.
+///
+/// Latencies are counted into buckets delimited by a sorted list of boundaries. A latency falls
+/// into the first bucket whose boundary is greater than or equal to it; latencies beyond the last
+/// boundary are counted in an extra overflow bucket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    /// The upper (inclusive) boundary of each bucket, in ascending order.
+    boundaries: Vec<Duration>,
+    /// The number of latencies in each bucket, with the overflow bucket last.
+    counts: Vec<u64>,
+    /// The largest latency recorded so far.
+    max: Duration,
+}
+
+impl LatencyHistogram {
+    /// Construct an empty histogram with the given bucket boundaries.
+    ///
+    /// The boundaries are sorted and deduplicated.
+    pub fn new(boundaries: &[Duration]) -> Self {
+        let mut boundaries = boundaries.to_vec();
+        boundaries.sort();
+        boundaries.dedup();
+
+        Self {
+            counts: vec![0; boundaries.len() + 1],
+            boundaries,
+            max: Duration::from_secs(0),
+        }
+    }
+
+    /// Add a latency to the histogram.
+    pub fn record(&mut self, latency: Duration) {
+        let bucket = self
+            .boundaries
+            .iter()
+            .position(|&boundary| latency <= boundary)
+            .unwrap_or(self.boundaries.len());
+
+        self.counts[bucket] += 1;
+        self.max = self.max.max(latency);
+    }
+
+    /// Get the upper boundary of each bucket, in ascending order.
+    pub fn boundaries(&self) -> &[Duration] {
+        &self.boundaries
+    }
+
+    /// Get the number of latencies in each bucket.
+    ///
+    /// There is one more count than there are [boundaries](LatencyHistogram::boundaries); the
+    /// last count is the number of latencies beyond the last boundary.
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// Get the total number of latencies recorded.
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Estimate the `p`th percentile latency, e.g. `p = 99.0` for the 99th percentile.
+    ///
+    /// The estimate is the upper boundary of the bucket that the percentile falls into, or the
+    /// largest recorded latency if it falls into the overflow bucket. Returns `None` if no
+    /// latencies have been recorded or if `p` is not within `0..=100`.
+    pub fn percentile(&self, p: f64) -> Option<Duration> {
+        let total = self.total();
+        if total == 0 || !(0.0..=100.0).contains(&p) {
+            return None;
+        }
+
+        let rank = ((p / 100.0 * total as f64).ceil() as u64).max(1);
+        let mut cumulative = 0;
+        for (bucket, &count) in self.counts.iter().enumerate() {
+            cumulative += count;
+            if cumulative >= rank {
+                return Some(self.boundaries.get(bucket).copied().unwrap_or(self.max));
+            }
+        }
+        Some(self.max)
+    }
+
+    /// Clear all recorded latencies, keeping the bucket boundaries.
+    pub fn reset(&mut self) {
+        self.counts.iter_mut().for_each(|count| *count = 0);
+        self.max = Duration::from_secs(0);
+    }
+}
+
+/// Statistics about the frames delivered by a pipeline.
+///
+/// Frames are added with [`record_frame`](PipelineStatistics::record_frame) as they are received,
+/// e.g. after each call to [`ActivePipeline::wait`](crate::pipeline::ActivePipeline::wait). The
+/// delivery latency of each frame is the time between the frame timestamp and the
+/// [time of arrival](Rs2FrameMetadata::TimeOfArrival) on the host, and is tracked in a
+/// [`LatencyHistogram`] to tell occasional latency spikes apart from systemic ones.
+#[derive(Debug, Clone)]
+pub struct PipelineStatistics {
+    /// The number of frames recorded, whether or not their latency could be measured.
+    frame_count: u64,
+    /// The distribution of frame delivery latencies.
+    histogram: LatencyHistogram,
+}
+
+impl Default for PipelineStatistics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PipelineStatistics {
+    /// Construct empty statistics with latency buckets at 0, 10, 20, 30, 50, 100, 200 and 500 ms.
+    pub fn new() -> Self {
+        let boundaries: Vec<Duration> = DEFAULT_LATENCY_BUCKETS_MS
+            .iter()
+            .map(|&ms| Duration::from_millis(ms))
+            .collect();
+        Self::with_latency_buckets(&boundaries)
+    }
+
+    /// Construct empty statistics with the given latency bucket boundaries.
+    pub fn with_latency_buckets(boundaries: &[Duration]) -> Self {
+        Self {
+            frame_count: 0,
+            histogram: LatencyHistogram::new(boundaries),
+        }
+    }
+
+    /// Add a frame to the statistics.
+    ///
+    /// The latency of the frame is only recorded if the frame supports
+    /// [`Rs2FrameMetadata::TimeOfArrival`] and its timestamp is not in the
+    /// [`HardwareClock`](Rs2TimestampDomain::HardwareClock) domain, since the device clock cannot
+    /// be compared against the arrival time on the host.
+    pub fn record_frame<F: FrameEx>(&mut self, frame: &F) {
+        self.frame_count += 1;
+
+        if frame.timestamp_domain() == Rs2TimestampDomain::HardwareClock {
+            return;
+        }
+
+        if let Some(arrived_at) = frame.metadata(Rs2FrameMetadata::TimeOfArrival) {
+            let latency_ms = (arrived_at as f64 - frame.timestamp()).max(0.0);
+            self.histogram
+                .record(Duration::from_secs_f64(latency_ms / 1000.0));
+        }
+    }
+
+    /// Get the number of frames recorded.
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    /// Get the histogram of frame delivery latencies.
+    pub fn latency_histogram(&self) -> &LatencyHistogram {
+        &self.histogram
+    }
+
+    /// Estimate the `p`th percentile frame delivery latency, e.g. `p = 95.0` for the 95th
+    /// percentile.
+    ///
+    /// See [`LatencyHistogram::percentile`].
+    pub fn latency_percentile(&self, p: f64) -> Option<Duration> {
+        self.histogram.percentile(p)
+    }
+
+    /// Clear the latency histogram, keeping the frame count.
+    pub fn reset_histogram(&mut self) {
+        self.histogram.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latencies_are_counted_into_buckets() {
+        let mut histogram =
+            LatencyHistogram::new(&[Duration::from_millis(20), Duration::from_millis(10)]);
+
+        histogram.record(Duration::from_millis(5));
+        histogram.record(Duration::from_millis(10));
+        histogram.record(Duration::from_millis(15));
+        histogram.record(Duration::from_millis(40));
+
+        assert_eq!(
+            histogram.boundaries(),
+            &[Duration::from_millis(10), Duration::from_millis(20)]
+        );
+        assert_eq!(histogram.counts(), &[2, 1, 1]);
+
+        histogram.reset();
+        assert_eq!(histogram.total(), 0);
+    }
+
+    #[test]
+    fn percentiles_use_bucket_boundaries() {
+        let mut statistics = PipelineStatistics::new();
+        assert_eq!(statistics.latency_percentile(50.0), None);
+
+        for _ in 0..98 {
+            statistics.histogram.record(Duration::from_millis(8));
+        }
+        statistics.histogram.record(Duration::from_millis(150));
+        statistics.histogram.record(Duration::from_millis(800));
+
+        assert_eq!(
+            statistics.latency_percentile(50.0),
+            Some(Duration::from_millis(10))
+        );
+        assert_eq!(
+            statistics.latency_percentile(99.0),
+            Some(Duration::from_millis(200))
+        );
+        assert_eq!(
+            statistics.latency_percentile(100.0),
+            Some(Duration::from_millis(800))
+        );
+        assert_eq!(statistics.latency_percentile(101.0), None);
+
+        statistics.reset_histogram();
+        assert_eq!(statistics.latency_percentile(50.0), None);
+    }
+}
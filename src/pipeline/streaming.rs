@@ -1,61 +1,97 @@
-use super::{inactive::InactivePipeline, profile::PipelineProfile};
-use crate::{check_rs2_error, frame::CompositeFrame, kind::Rs2Exception};
-use anyhow::Result;
+//! Type for representing a "streaming" pipeline which delivers frames to a callback.
+
+use super::{active::PipelineStopError, inactive::InactivePipeline, profile::PipelineProfile};
+use crate::{check_rs2_error, frame::CompositeFrame};
 use realsense_sys as sys;
-use std::{ptr::NonNull, task::Poll, time::Duration};
-use thiserror::Error;
-use std::os::raw::c_void;
-use crate::frame::FrameCategory;
-use super::inactive::IntoFrame;
+use std::{
+    os::raw::c_void,
+    panic::{self, AssertUnwindSafe},
+    ptr::NonNull,
+    time::Duration,
+};
 
-pub(crate) unsafe extern "C" fn trampoline<F>(frame: *mut sys::rs2_frame, data: *mut c_void) 
+/// Frame callback passed to `rs2_pipeline_start_with_callback` and
+/// `rs2_pipeline_start_with_config_and_callback`.
+///
+/// `data` is the boxed user callback of type `F`. Frames passed to this function are owned by us
+/// and handed off to the user callback as a [`CompositeFrame`], which releases them once dropped.
+/// Unwinding across the FFI boundary is undefined behaviour, so the callback is run inside
+/// `catch_unwind` and the process is aborted if it panics.
+pub(crate) unsafe extern "C" fn trampoline<F>(frame: *mut sys::rs2_frame, data: *mut c_void)
 where
-    F: FnMut(&impl IntoFrame) + Send + 'static,
+    F: FnMut(CompositeFrame) + Send + 'static,
 {
-    let panic = std::panic::catch_unwind(|| {
-        if frame.is_null() {
-            panic!("null frame");
-        }
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let frame_ptr = match NonNull::new(frame) {
+            Some(ptr) => ptr,
+            None => return,
+        };
+        let callback = &mut *data.cast::<F>();
 
-        let frame = core::mem::ManuallyDrop::new(NonNull::new_unchecked(frame));
-
-        if data.is_null() {
-            panic!("empty data");
-        }
+        callback(CompositeFrame::from(frame_ptr));
+    }));
 
-        let f = &mut *(data as *mut F);
-
-        f(&frame);
-    });
-
-    if panic.is_err() {
-        eprintln!("Callback function panicked");
+    if result.is_err() {
+        eprintln!("Pipeline frame callback panicked");
         std::process::abort();
     }
 }
 
-pub struct StreamingPipeline {
-    /// A pointer to the callback function for the pipeline.
-    callback: *mut dyn FnMut(&impl IntoFrame),
+/// Type representing a "streaming" pipeline, which delivers every frameset to a callback.
+///
+/// Unlike an [`ActivePipeline`](super::ActivePipeline), framesets are not waited or polled for,
+/// but pushed to the callback from a librealsense2 thread as soon as they arrive. The callback is
+/// owned by the streaming pipeline. Streaming is stopped, and the callback dropped, with
+/// [`StreamingPipeline::stop`] or when the streaming pipeline is dropped.
+#[derive(Debug)]
+pub struct StreamingPipeline<F> {
     /// A (non-null) pointer to the pipeline.
     pipeline_ptr: NonNull<sys::rs2_pipeline>,
     /// The pipeline's profile, which contains the device the pipeline is configured for alongside
     /// the stream profiles for streams in the pipeline.
     profile: PipelineProfile,
+    /// The timeout carried over to the [`InactivePipeline`] once stopped.
+    default_timeout: Duration,
+    /// The boxed user callback that librealsense2 calls with every frameset.
+    callback: NonNull<F>,
+}
+
+impl<F> Drop for StreamingPipeline<F> {
+    fn drop(&mut self) {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_pipeline_stop(self.pipeline_ptr.as_ptr(), &mut err);
+            if err.as_ref().is_some() {
+                sys::rs2_free_error(err);
+            }
+
+            sys::rs2_delete_pipeline(self.pipeline_ptr.as_ptr());
+
+            // SAFETY: librealsense2 no longer calls the callback once the pipeline is stopped and
+            // deleted.
+            drop(Box::from_raw(self.callback.as_ptr()));
+        }
+    }
 }
 
-impl StreamingPipeline {
-    /// Constructs a new streaming pipeline from the constituent components
+unsafe impl<F: Send> Send for StreamingPipeline<F> {}
+
+impl<F> StreamingPipeline<F> {
+    /// Constructs a new streaming pipeline from the constituent components.
     ///
-    /// This is only to be used / called from the [`InactivePipeline`] type.
-    pub(crate) fn new<F>(pipeline_ptr: NonNull<sys::rs2_pipeline>, profile: PipelineProfile, callback: F) -> Self 
-        where
-            F: FnMut(&impl IntoFrame) + Send + 'static
-    {
+    /// This is only to be used / called from the [`InactivePipeline`] type, after the pipeline
+    /// has been started with `callback`.
+    pub(crate) fn new(
+        pipeline_ptr: NonNull<sys::rs2_pipeline>,
+        profile: PipelineProfile,
+        default_timeout: Duration,
+        callback: NonNull<F>,
+    ) -> Self {
         Self {
             pipeline_ptr,
-            callback,
             profile,
+            default_timeout,
+            callback,
         }
     }
 
@@ -66,30 +102,31 @@ impl StreamingPipeline {
 
     /// Stop the pipeline.
     ///
-    /// This method consumes the pipeline instance and returns pipeline markered inactive.
-    pub fn stop(self) -> InactivePipeline {
+    /// This method consumes the pipeline instance, drops the callback, and returns pipeline
+    /// markered inactive.
+    ///
+    /// # Errors
+    ///
+    /// Returns the pipeline alongside a [`PipelineStopError`] if librealsense2 fails to stop the
+    /// pipeline. The pipeline is still streaming in that case, so stopping it can be retried, or
+    /// it can be dropped to release it regardless.
+    #[allow(clippy::result_large_err)]
+    pub fn stop(self) -> Result<InactivePipeline, (Self, PipelineStopError)> {
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
-
-            // The only "error" that can occur here is if the pipeline pointer is null.
-            //
-            // We know it is not (state is managed so that this isn't a possibility, and we use
-            // `NonNull` to try and guarantee that even beyond our state management), so there
-            // dealing with the error (and thus returning a result type) is superfluous here.
             sys::rs2_pipeline_stop(self.pipeline_ptr.as_ptr(), &mut err);
+            if let Err(stop_error) = check_rs2_error!(err, PipelineStopError) {
+                return Err((self, stop_error));
+            }
+
+            // SAFETY: librealsense2 no longer calls the callback once the pipeline is stopped.
+            drop(Box::from_raw(self.callback.as_ptr()));
 
-            let inactive = InactivePipeline::new(self.pipeline_ptr);
+            let inactive =
+                InactivePipeline::new(self.pipeline_ptr).with_timeout(self.default_timeout);
 
             std::mem::forget(self);
-            inactive
+            Ok(inactive)
         }
     }
-
-}
-
-impl Drop for StreamingPipeline {
-    fn drop(&mut self) {
-        let boxed = unsafe { Box::from_raw(self.pipeline_ptr.as_ptr()) };
-        unsafe { sys::rs2_delete_pipeline(self.pipeline_ptr.as_ptr()) };
-    }
 }
@@ -1,595 +1,447 @@
-//! Defines the processing block type.
+//! Type for filtering frames on the host through librealsense2 processing blocks.
+//!
+//! Processing blocks take a frame, e.g. a [`DepthFrame`](crate::frame::DepthFrame) returned from
+//! a pipeline, and produce a new, processed frame of the same type. Each kind of block is
+//! configured through the same option interface as a [`Sensor`](crate::sensor::Sensor):
+//!
+//! ```no_run
+//! use realsense_rust::{kind::Rs2Option, processing_block::DecimationFilter};
+//!
+//! let mut decimation = DecimationFilter::new()?;
+//! decimation.set_option(Rs2Option::FilterMagnitude, 4.0)?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! Frames are then passed through the block with [`ProcessingBlock::process`].
 
 use crate::{
-    base::StreamProfileData,
-    common::*,
-    error::{ErrorChecker, Result},
-    frame::{AnyFrame, DepthFrame, ExtendedFrame, Frame, GenericFrameEx, PointsFrame, VideoFrame},
-    frame_kind::FrameKind,
-    frame_queue::FrameQueue,
-    kind::{ColorScheme, HoleFillingMode, PersistenceControl, Rs2Option, Rs2StreamKind},
-    options::ToOptions,
-    processing_block_kind,
+    check_rs2_error,
+    frame::FrameEx,
+    kind::{OptionSetError, Rs2Exception, Rs2Option, Rs2OptionRange},
+    processing_block_kind::{
+        DecimationFilterKind, DisparityTransformKind, HoleFillingFilterKind, ProcessingBlockKind,
+        SpatialFilterKind, TemporalFilterKind,
+    },
 };
+use anyhow::Result;
+use realsense_sys as sys;
+use std::{
+    convert::{TryFrom, TryInto},
+    marker::PhantomData,
+    mem::MaybeUninit,
+    ptr::NonNull,
+};
+use thiserror::Error;
 
-/// The type returned by [ProcessingBlock::<Any>::try_extend](ProcessingBlock::try_extend).
+/// Type describing errors that can occur when creating or using a processing block.
 ///
-/// It enumerates all possible frame extensions. If the frame failed to
-/// extend any one of the kind, it falls back to [ExtendedProcessingBlock::Other](ExtendedProcessingBlock::Other) variant.
-#[derive(Debug)]
-pub enum ExtendedProcessingBlock {
-    DecimationFilter(DecimationFilter),
-    ThresholdFilter(ThresholdFilter),
-    DisparityFilter(DisparityFilter),
-    SpatialFilter(SpatialFilter),
-    TemporalFilter(TemporalFilter),
-    HoleFillingFilter(HoleFillingFilter),
-    ZeroOrderFilter(ZeroOrderFilter),
-    Other(AnyProcessingBlock),
+/// Follows the standard pattern of errors where the enum variant describes what the low-level code
+/// was attempting to do while the string carried alongside describes the underlying error message
+/// from any C++ exceptions that occur.
+#[derive(Error, Debug)]
+pub enum ProcessingBlockError {
+    /// The processing block could not be created.
+    #[error("Could not create processing block. Type: {0}; Reason: {1}")]
+    CouldNotCreateProcessingBlock(Rs2Exception, String),
+    /// The queue that receives processed frames could not be created.
+    #[error("Could not create frame queue. Type: {0}; Reason: {1}")]
+    CouldNotCreateFrameQueue(Rs2Exception, String),
+    /// The processing block could not be started with its frame queue.
+    #[error("Could not start processing queue. Type: {0}; Reason: {1}")]
+    CouldNotStartProcessingQueue(Rs2Exception, String),
+    /// The frame could not be passed to the processing block.
+    #[error("Could not process frame. Type: {0}; Reason: {1}")]
+    CouldNotProcessFrame(Rs2Exception, String),
+    /// An internal error occurred while waiting for the processed frame.
+    #[error("Could not get processed frame. Type: {0}; Reason: {1}")]
+    CouldNotGetProcessedFrame(Rs2Exception, String),
+    /// The processing block did not produce a frame before the timeout.
+    #[error("Timed out while waiting for processed frame.")]
+    DidTimeoutBeforeFrameArrival,
 }
 
-/// The type of data processing unit.
+/// A librealsense2 processing block of kind `K`.
+///
+/// See the [module documentation](crate::processing_block) for an overview, and the aliases
+/// [`DecimationFilter`], [`SpatialFilter`], [`TemporalFilter`], [`HoleFillingFilter`] and
+/// [`DisparityTransform`] for the available kinds.
 #[derive(Debug)]
-pub struct ProcessingBlock<Kind>
-where
-    Kind: processing_block_kind::ProcessingBlockKind,
-{
-    pub(crate) ptr: NonNull<sys::rs2_processing_block>,
-    queue: FrameQueue,
-    _phantom: PhantomData<Kind>,
+pub struct ProcessingBlock<K: ProcessingBlockKind> {
+    /// A (non-null) pointer to the processing block.
+    block_ptr: NonNull<sys::rs2_processing_block>,
+    /// A (non-null) pointer to the queue that the block delivers processed frames to.
+    queue_ptr: NonNull<sys::rs2_frame_queue>,
+    /// Marker for the kind of processing block.
+    _phantom: PhantomData<K>,
 }
 
-// type aliases
-
-pub type DecimationFilter = ProcessingBlock<processing_block_kind::DecimationFilterKind>;
-pub type ThresholdFilter = ProcessingBlock<processing_block_kind::ThresholdFilterKind>;
-pub type DisparityFilter = ProcessingBlock<processing_block_kind::DisparityFilterKind>;
-pub type SpatialFilter = ProcessingBlock<processing_block_kind::SpatialFilterKind>;
-pub type TemporalFilter = ProcessingBlock<processing_block_kind::TemporalFilterKind>;
-pub type HoleFillingFilter = ProcessingBlock<processing_block_kind::HoleFillingFilterKind>;
-pub type ZeroOrderFilter = ProcessingBlock<processing_block_kind::ZeroOrderFilterKind>;
-pub type PointCloud = ProcessingBlock<processing_block_kind::PointCloudKind>;
-pub type YuyDecoder = ProcessingBlock<processing_block_kind::YuyDecoderKind>;
-pub type UnitsTransform = ProcessingBlock<processing_block_kind::UnitsTransformKind>;
-pub type Syncer = ProcessingBlock<processing_block_kind::SyncerKind>;
-pub type Align = ProcessingBlock<processing_block_kind::AlignKind>;
-pub type Colorizer = ProcessingBlock<processing_block_kind::ColorizerKind>;
-pub type HuffmanDepthDecompress =
-    ProcessingBlock<processing_block_kind::HuffmanDepthDecompressKind>;
-pub type RatesPrinter = ProcessingBlock<processing_block_kind::RatesPrinterKind>;
-pub type AnyProcessingBlock = ProcessingBlock<processing_block_kind::Any>;
-
-impl<Kind> ProcessingBlock<Kind>
-where
-    Kind: processing_block_kind::ProcessingBlockKind,
-{
-    pub fn process<K>(&mut self, input: Frame<K>) -> Result<AnyFrame>
-    where
-        K: FrameKind,
-    {
+/// A filter that reduces the resolution of depth frames.
+pub type DecimationFilter = ProcessingBlock<DecimationFilterKind>;
+/// A filter that smooths depth frames spatially while preserving edges.
+pub type SpatialFilter = ProcessingBlock<SpatialFilterKind>;
+/// A filter that smooths depth frames over time.
+pub type TemporalFilter = ProcessingBlock<TemporalFilterKind>;
+/// A filter that fills pixels without a valid depth from their neighbours.
+pub type HoleFillingFilter = ProcessingBlock<HoleFillingFilterKind>;
+/// A transform that converts depth frames to disparity frames or back.
+pub type DisparityTransform = ProcessingBlock<DisparityTransformKind>;
+
+impl<K: ProcessingBlockKind> Drop for ProcessingBlock<K> {
+    fn drop(&mut self) {
         unsafe {
-            let frame_ptr = input.into_raw();
-            let mut checker = ErrorChecker::new();
-            sys::rs2_process_frame(self.ptr.as_ptr(), frame_ptr, checker.inner_mut_ptr());
-            checker.check()?;
+            sys::rs2_delete_processing_block(self.block_ptr.as_ptr());
+            sys::rs2_delete_frame_queue(self.queue_ptr.as_ptr());
         }
-        let output = self.queue.wait(None)?;
-        Ok(output)
     }
+}
 
-    pub async fn process_async<K>(&mut self, input: Frame<K>) -> Result<AnyFrame>
-    where
-        K: FrameKind,
-    {
-        unsafe {
-            let frame_ptr = input.into_raw();
-            let mut checker = ErrorChecker::new();
-            sys::rs2_process_frame(self.ptr.as_ptr(), frame_ptr, checker.inner_mut_ptr());
-            checker.check()?;
-        }
-        let output = self.queue.wait_async(None).await?;
-        Ok(output)
-    }
+unsafe impl<K: ProcessingBlockKind> Send for ProcessingBlock<K> {}
 
-    pub fn into_raw_parts(self) -> (*mut sys::rs2_processing_block, FrameQueue) {
-        let ptr = self.ptr;
-        let queue = unsafe { self.queue.unsafe_clone() };
-        mem::forget(self);
-        (ptr.as_ptr(), queue)
-    }
+impl DecimationFilter {
+    /// Create a new decimation filter.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ProcessingBlockError`] if the block or its frame queue cannot be created.
+    pub fn new() -> Result<Self, ProcessingBlockError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let block_ptr = sys::rs2_create_decimation_filter_block(&mut err);
+            check_rs2_error!(err, ProcessingBlockError::CouldNotCreateProcessingBlock)?;
 
-    pub unsafe fn from_raw_parts(ptr: *mut sys::rs2_processing_block, queue: FrameQueue) -> Self {
-        Self {
-            ptr: NonNull::new(ptr).unwrap(),
-            queue,
-            _phantom: PhantomData,
+            Self::from_block_ptr(NonNull::new(block_ptr).unwrap())
         }
     }
-
-    pub(crate) unsafe fn new_from_raw_and_capacity(
-        ptr: NonNull<sys::rs2_processing_block>,
-        capacity: usize,
-    ) -> Result<Self> {
-        let queue = FrameQueue::with_capacity(capacity)?;
-
-        // start processing
-        {
-            let mut checker = ErrorChecker::new();
-            sys::rs2_start_processing_queue(
-                ptr.as_ptr(),
-                queue.ptr.as_ptr(),
-                checker.inner_mut_ptr(),
-            );
-            checker.check()?;
-        }
-
-        let block = Self {
-            ptr,
-            queue,
-            _phantom: PhantomData,
-        };
-        Ok(block)
-    }
-
-    pub(crate) unsafe fn new_from_raw(ptr: NonNull<sys::rs2_processing_block>) -> Result<Self> {
-        Self::new_from_raw_and_capacity(ptr, 1)
-    }
 }
 
-impl AnyProcessingBlock {
-    pub fn is_extendable_to<Kind>(&self) -> Result<bool>
-    where
-        Kind: processing_block_kind::ExtendableProcessingBlockKind,
-    {
+impl SpatialFilter {
+    /// Create a new spatial filter.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ProcessingBlockError`] if the block or its frame queue cannot be created.
+    pub fn new() -> Result<Self, ProcessingBlockError> {
         unsafe {
-            let mut checker = ErrorChecker::new();
-            let val = sys::rs2_is_processing_block_extendable_to(
-                self.ptr.as_ptr(),
-                Kind::EXTENSION as sys::rs2_extension,
-                checker.inner_mut_ptr(),
-            );
-            checker.check()?;
-            Ok(val != 0)
-        }
-    }
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let block_ptr = sys::rs2_create_spatial_filter_block(&mut err);
+            check_rs2_error!(err, ProcessingBlockError::CouldNotCreateProcessingBlock)?;
 
-    pub fn try_extend_to<Kind>(self) -> Result<result::Result<ProcessingBlock<Kind>, Self>>
-    where
-        Kind: processing_block_kind::ExtendableProcessingBlockKind,
-    {
-        unsafe {
-            let is_extendable = self.is_extendable_to::<Kind>()?;
-            if is_extendable {
-                let (ptr, queue) = self.into_raw_parts();
-                let block = ProcessingBlock::from_raw_parts(ptr, queue);
-                Ok(Ok(block))
-            } else {
-                Ok(Err(self))
-            }
+            Self::from_block_ptr(NonNull::new(block_ptr).unwrap())
         }
     }
-
-    pub fn try_extend(self) -> Result<ExtendedProcessingBlock> {
-        let frame_any = self;
-
-        let frame_any =
-            match frame_any.try_extend_to::<processing_block_kind::DecimationFilterKind>()? {
-                Ok(frame) => return Ok(ExtendedProcessingBlock::DecimationFilter(frame)),
-                Err(frame) => frame,
-            };
-
-        let frame_any =
-            match frame_any.try_extend_to::<processing_block_kind::ThresholdFilterKind>()? {
-                Ok(frame) => return Ok(ExtendedProcessingBlock::ThresholdFilter(frame)),
-                Err(frame) => frame,
-            };
-
-        let frame_any =
-            match frame_any.try_extend_to::<processing_block_kind::DisparityFilterKind>()? {
-                Ok(frame) => return Ok(ExtendedProcessingBlock::DisparityFilter(frame)),
-                Err(frame) => frame,
-            };
-
-        let frame_any =
-            match frame_any.try_extend_to::<processing_block_kind::SpatialFilterKind>()? {
-                Ok(frame) => return Ok(ExtendedProcessingBlock::SpatialFilter(frame)),
-                Err(frame) => frame,
-            };
-
-        let frame_any =
-            match frame_any.try_extend_to::<processing_block_kind::TemporalFilterKind>()? {
-                Ok(frame) => return Ok(ExtendedProcessingBlock::TemporalFilter(frame)),
-                Err(frame) => frame,
-            };
-
-        let frame_any =
-            match frame_any.try_extend_to::<processing_block_kind::HoleFillingFilterKind>()? {
-                Ok(frame) => return Ok(ExtendedProcessingBlock::HoleFillingFilter(frame)),
-                Err(frame) => frame,
-            };
-
-        let frame_any =
-            match frame_any.try_extend_to::<processing_block_kind::ZeroOrderFilterKind>()? {
-                Ok(frame) => return Ok(ExtendedProcessingBlock::ZeroOrderFilter(frame)),
-                Err(frame) => frame,
-            };
-
-        Ok(ExtendedProcessingBlock::Other(frame_any))
-    }
 }
 
-impl ThresholdFilter {
-    pub fn create() -> Result<Self> {
-        let processing_block = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_threshold(checker.inner_mut_ptr());
-            checker.check()?;
-            Self::new_from_raw(NonNull::new(ptr).unwrap())?
-        };
-        Ok(processing_block)
-    }
-
-    pub fn with_options(
-        min_dist: impl Into<Option<f32>>,
-        max_dist: impl Into<Option<f32>>,
-    ) -> Result<Self> {
-        let min_dist = min_dist.into();
-        let max_dist = max_dist.into();
-
-        let processing_block = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_threshold(checker.inner_mut_ptr());
-            checker.check()?;
-            Self::new_from_raw(NonNull::new(ptr).unwrap())?
-        };
+impl TemporalFilter {
+    /// Create a new temporal filter.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ProcessingBlockError`] if the block or its frame queue cannot be created.
+    pub fn new() -> Result<Self, ProcessingBlockError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let block_ptr = sys::rs2_create_temporal_filter_block(&mut err);
+            check_rs2_error!(err, ProcessingBlockError::CouldNotCreateProcessingBlock)?;
 
-        let options = processing_block.to_options()?;
-        if let Some(dist) = min_dist {
-            options[&Rs2Option::MinDistance].set_value(dist)?;
-        }
-        if let Some(dist) = max_dist {
-            options[&Rs2Option::MaxDistance].set_value(dist)?;
+            Self::from_block_ptr(NonNull::new(block_ptr).unwrap())
         }
-
-        Ok(processing_block)
     }
 }
 
-impl SpatialFilter {
-    pub fn create() -> Result<Self> {
-        let processing_block = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_spatial_filter_block(checker.inner_mut_ptr());
-            checker.check()?;
-            Self::new_from_raw(NonNull::new(ptr).unwrap())?
-        };
-        Ok(processing_block)
-    }
-
-    pub fn with_options(
-        smooth_alpha: f32,
-        smooth_delta: f32,
-        magnitude: f32,
-        hole_fill: f32,
-    ) -> Result<Self> {
-        let processing_block = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_temporal_filter_block(checker.inner_mut_ptr());
-            checker.check()?;
-            Self::new_from_raw(NonNull::new(ptr).unwrap())?
-        };
-
-        let options = processing_block.to_options()?;
-        options[&Rs2Option::FilterSmoothAlpha].set_value(smooth_alpha)?;
-        options[&Rs2Option::FilterSmoothDelta].set_value(smooth_delta)?;
-        options[&Rs2Option::FilterMagnitude].set_value(magnitude)?;
-        options[&Rs2Option::HolesFill].set_value(hole_fill)?;
+impl HoleFillingFilter {
+    /// Create a new hole filling filter.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ProcessingBlockError`] if the block or its frame queue cannot be created.
+    pub fn new() -> Result<Self, ProcessingBlockError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let block_ptr = sys::rs2_create_hole_filling_filter_block(&mut err);
+            check_rs2_error!(err, ProcessingBlockError::CouldNotCreateProcessingBlock)?;
 
-        Ok(processing_block)
+            Self::from_block_ptr(NonNull::new(block_ptr).unwrap())
+        }
     }
 }
 
-impl TemporalFilter {
-    pub fn create() -> Result<Self> {
-        let processing_block = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_temporal_filter_block(checker.inner_mut_ptr());
-            checker.check()?;
-            Self::new_from_raw(NonNull::new(ptr).unwrap())?
-        };
-        Ok(processing_block)
-    }
-
-    pub fn with_options(
-        smooth_alpha: f32,
-        smooth_delta: f32,
-        persistence_control: PersistenceControl,
-    ) -> Result<Self> {
-        let processing_block = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_temporal_filter_block(checker.inner_mut_ptr());
-            checker.check()?;
-            Self::new_from_raw(NonNull::new(ptr).unwrap())?
-        };
-
-        let options = processing_block.to_options()?;
-        options[&Rs2Option::HolesFill].set_value(persistence_control as usize as f32)?;
-        options[&Rs2Option::FilterSmoothAlpha].set_value(smooth_alpha)?;
-        options[&Rs2Option::FilterSmoothDelta].set_value(smooth_delta)?;
+impl DisparityTransform {
+    /// Create a new disparity transform.
+    ///
+    /// The transform converts [`DepthFrame`](crate::frame::DepthFrame)s to
+    /// [`DisparityFrame`](crate::frame::DisparityFrame)s if `transform_to_disparity` is true, and
+    /// disparity frames back to depth frames otherwise. Since the frame type changes, frames are
+    /// passed through the transform with [`ProcessingBlock::process_into`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ProcessingBlockError`] if the block or its frame queue cannot be created.
+    pub fn new(transform_to_disparity: bool) -> Result<Self, ProcessingBlockError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let block_ptr = sys::rs2_create_disparity_transform_block(
+                u8::from(transform_to_disparity),
+                &mut err,
+            );
+            check_rs2_error!(err, ProcessingBlockError::CouldNotCreateProcessingBlock)?;
 
-        Ok(processing_block)
+            Self::from_block_ptr(NonNull::new(block_ptr).unwrap())
+        }
     }
 }
 
-impl DecimationFilter {
-    pub fn create() -> Result<Self> {
-        let processing_block = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_decimation_filter_block(checker.inner_mut_ptr());
-            checker.check()?;
-            Self::new_from_raw(NonNull::new(ptr).unwrap())?
-        };
-        Ok(processing_block)
-    }
+impl<K: ProcessingBlockKind> ProcessingBlock<K> {
+    /// Construct a processing block from a newly created block pointer.
+    ///
+    /// Creates the queue that processed frames are delivered to and starts the block with it. The
+    /// block is deleted if this fails.
+    ///
+    /// # Safety
+    ///
+    /// `block_ptr` must be a valid processing block that is not owned by anything else.
+    unsafe fn from_block_ptr(
+        block_ptr: NonNull<sys::rs2_processing_block>,
+    ) -> Result<Self, ProcessingBlockError> {
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+
+        // A single frame is processed at a time, so a capacity of one is sufficient.
+        let queue_ptr = sys::rs2_create_frame_queue(1, &mut err);
+        if let Err(e) = check_rs2_error!(err, ProcessingBlockError::CouldNotCreateFrameQueue) {
+            sys::rs2_delete_processing_block(block_ptr.as_ptr());
+            return Err(e);
+        }
 
-    pub fn with_options(magnitude: f32) -> Result<Self> {
-        let processing_block = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_decimation_filter_block(checker.inner_mut_ptr());
-            checker.check()?;
-            Self::new_from_raw(NonNull::new(ptr).unwrap())?
+        // From here on, dropping the block deletes both pointers.
+        let block = Self {
+            block_ptr,
+            queue_ptr: NonNull::new(queue_ptr).unwrap(),
+            _phantom: PhantomData,
         };
 
-        let options = processing_block.to_options()?;
-        options[&Rs2Option::FilterMagnitude].set_value(magnitude)?;
+        sys::rs2_start_processing_queue(
+            block.block_ptr.as_ptr(),
+            block.queue_ptr.as_ptr(),
+            &mut err,
+        );
+        check_rs2_error!(err, ProcessingBlockError::CouldNotStartProcessingQueue)?;
 
-        Ok(processing_block)
-    }
-}
-
-impl HoleFillingFilter {
-    pub fn create() -> Result<Self> {
-        let processing_block = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_hole_filling_filter_block(checker.inner_mut_ptr());
-            checker.check()?;
-            Self::new_from_raw(NonNull::new(ptr).unwrap())?
-        };
-        Ok(processing_block)
+        Ok(block)
     }
 
-    pub fn with_options(mode: HoleFillingMode) -> Result<Self> {
-        let processing_block = Self::create()?;
+    /// Pass `frame` through the processing block and return the processed frame.
+    ///
+    /// The frame is consumed, and the processed frame has the same type, e.g. a
+    /// [`DepthFrame`](crate::frame::DepthFrame) passed through a [`DecimationFilter`] produces a
+    /// smaller `DepthFrame`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockError::CouldNotProcessFrame`] if the block fails to process the
+    /// frame, e.g. because it is of a type the block does not accept.
+    ///
+    /// Returns [`ProcessingBlockError::CouldNotGetProcessedFrame`] or
+    /// [`ProcessingBlockError::DidTimeoutBeforeFrameArrival`] if the processed frame cannot be
+    /// retrieved from the block.
+    ///
+    /// Returns an error if the processed frame cannot be constructed as an `F`.
+    pub fn process<F>(&mut self, frame: F) -> Result<F>
+    where
+        F: FrameEx + TryFrom<NonNull<sys::rs2_frame>, Error = anyhow::Error>,
+    {
+        self.process_into(frame)
+    }
+
+    /// Pass `frame` through the processing block and return the processed frame as a `G`.
+    ///
+    /// This is the same as [`ProcessingBlock::process`], but for blocks that produce frames of a
+    /// different type than they are given, e.g. a [`DisparityTransform`] that converts a
+    /// [`DepthFrame`](crate::frame::DepthFrame) into a
+    /// [`DisparityFrame`](crate::frame::DisparityFrame).
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`ProcessingBlock::process`], or an error if the processed frame
+    /// cannot be constructed as a `G`.
+    pub fn process_into<F, G>(&mut self, frame: F) -> Result<G>
+    where
+        F: FrameEx,
+        G: TryFrom<NonNull<sys::rs2_frame>, Error = anyhow::Error>,
+    {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_process_frame(
+                self.block_ptr.as_ptr(),
+                frame.get_owned_raw().as_ptr(),
+                &mut err,
+            );
+            check_rs2_error!(err, ProcessingBlockError::CouldNotProcessFrame)?;
+
+            let mut processed_ptr = std::ptr::null_mut::<sys::rs2_frame>();
+            let did_get_frame = sys::rs2_try_wait_for_frame(
+                self.queue_ptr.as_ptr(),
+                sys::RS2_DEFAULT_TIMEOUT,
+                &mut processed_ptr,
+                &mut err,
+            );
+            check_rs2_error!(err, ProcessingBlockError::CouldNotGetProcessedFrame)?;
 
-        let options = processing_block.to_options()?;
-        options[&Rs2Option::HolesFill].set_value(mode as usize as f32)?;
+            if did_get_frame == 0 {
+                return Err(ProcessingBlockError::DidTimeoutBeforeFrameArrival.into());
+            }
 
-        Ok(processing_block)
+            G::try_from(NonNull::new(processed_ptr).unwrap())
+        }
     }
-}
 
-impl DisparityFilter {
-    pub fn create() -> Result<Self> {
-        Self::with_options(true)
-    }
+    /// Get the value associated with the provided Rs2Option for the processing block.
+    ///
+    /// Returns An `f32` value corresponding to that option within the librealsense2 library, or None
+    /// if the option is not supported.
+    pub fn get_option(&self, option: Rs2Option) -> Option<f32> {
+        if !self.supports_option(option) {
+            return None;
+        }
 
-    pub fn with_options(transform_to_disparity: bool) -> Result<Self> {
-        let processing_block = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_disparity_transform_block(
-                transform_to_disparity as c_uchar,
-                checker.inner_mut_ptr(),
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let val = sys::rs2_get_option(
+                self.block_ptr.as_ptr().cast::<sys::rs2_options>(),
+                #[allow(clippy::useless_conversion)]
+                (option as i32).try_into().unwrap(),
+                &mut err,
             );
-            checker.check()?;
-            Self::new_from_raw(NonNull::new(ptr).unwrap())?
-        };
-        Ok(processing_block)
-    }
-}
-
-impl PointCloud {
-    pub fn create() -> Result<Self> {
-        let processing_block = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_pointcloud(checker.inner_mut_ptr());
-            checker.check()?;
-            Self::new_from_raw(NonNull::new(ptr).unwrap())?
-        };
-        Ok(processing_block)
-    }
 
-    pub fn calculate(&mut self, depth_frame: DepthFrame) -> Result<PointsFrame> {
-        let frame_any = self.process(depth_frame)?;
-        match frame_any.try_extend()? {
-            ExtendedFrame::Points(points_frame) => Ok(points_frame),
-            ExtendedFrame::Composite(composite_frame) => {
-                for result in composite_frame.try_into_iter()? {
-                    let frame = result?;
-                    if let Ok(points_frame) = frame.try_extend_to()? {
-                        return Ok(points_frame);
-                    }
-                }
-                unreachable!();
+            if err.as_ref().is_none() {
+                Some(val)
+            } else {
+                sys::rs2_free_error(err);
+                None
             }
-            _ => unreachable!(),
         }
     }
 
-    pub fn map_to(&mut self, color_frame: VideoFrame) -> Result<()> {
-        let StreamProfileData {
-            stream,
-            format,
-            index,
-            ..
-        } = color_frame.stream_profile()?.get_data()?;
-        let options = self.to_options()?;
-        options[&Rs2Option::StreamFilter].set_value(stream as sys::rs2_stream as f32)?;
-        options[&Rs2Option::StreamFormatFilter].set_value(format as sys::rs2_format as f32)?;
-        options[&Rs2Option::StreamIndexFilter].set_value(index as f32)?;
-
-        self.process(color_frame)?;
-        Ok(())
-    }
-
-    pub async fn calculate_async(&mut self, depth_frame: DepthFrame) -> Result<PointsFrame> {
-        let frame_any = self.process_async(depth_frame).await?;
-        match frame_any.try_extend()? {
-            ExtendedFrame::Points(points_frame) => Ok(points_frame),
-            ExtendedFrame::Composite(composite_frame) => {
-                for result in composite_frame.try_into_iter()? {
-                    let frame = result?;
-                    if let Ok(points_frame) = frame.try_extend_to()? {
-                        return Ok(points_frame);
-                    }
-                }
-                unreachable!();
-            }
-            _ => unreachable!(),
+    /// Sets the `value` associated with the provided `option` for the processing block.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptionSetError::OptionNotSupported`] if the option is not supported by this
+    /// processing block.
+    ///
+    /// Returns [`OptionSetError::OptionIsReadOnly`] if the option is supported but cannot be set
+    /// on this processing block.
+    ///
+    /// Returns [`OptionSetError::CouldNotSetOption`] if the option is supported and not read-only,
+    /// but could not be set for another reason (e.g. a value outside of the option range).
+    pub fn set_option(&mut self, option: Rs2Option, value: f32) -> Result<(), OptionSetError> {
+        if !self.supports_option(option) {
+            return Err(OptionSetError::OptionNotSupported);
         }
-    }
 
-    pub async fn map_to_async(&mut self, color_frame: VideoFrame) -> Result<()> {
-        let StreamProfileData {
-            stream,
-            format,
-            index,
-            ..
-        } = color_frame.stream_profile()?.get_data()?;
-        let options = self.to_options()?;
-        options[&Rs2Option::StreamFilter].set_value(stream as sys::rs2_stream as f32)?;
-        options[&Rs2Option::StreamFormatFilter].set_value(format as sys::rs2_format as f32)?;
-        options[&Rs2Option::StreamIndexFilter].set_value(index as f32)?;
-
-        self.process_async(color_frame).await?;
-        Ok(())
-    }
-}
-
-impl YuyDecoder {
-    pub fn create() -> Result<Self> {
-        let processing_block = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_yuy_decoder(checker.inner_mut_ptr());
-            checker.check()?;
-            Self::new_from_raw(NonNull::new(ptr).unwrap())?
-        };
-        Ok(processing_block)
-    }
-}
-
-impl UnitsTransform {
-    pub fn create() -> Result<Self> {
-        let processing_block = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_units_transform(checker.inner_mut_ptr());
-            checker.check()?;
-            Self::new_from_raw(NonNull::new(ptr).unwrap())?
-        };
-        Ok(processing_block)
-    }
-}
-
-impl Syncer {
-    pub fn create() -> Result<Self> {
-        let processing_block = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_sync_processing_block(checker.inner_mut_ptr());
-            checker.check()?;
-            Self::new_from_raw(NonNull::new(ptr).unwrap())?
-        };
-        Ok(processing_block)
-    }
-}
+        if self.is_option_read_only(option) {
+            return Err(OptionSetError::OptionIsReadOnly);
+        }
 
-impl Align {
-    pub fn create(align_to: Rs2StreamKind) -> Result<Self> {
-        let processing_block = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_align(align_to as sys::rs2_stream, checker.inner_mut_ptr());
-            checker.check()?;
-            Self::new_from_raw(NonNull::new(ptr).unwrap())?
-        };
-        Ok(processing_block)
-    }
-}
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_set_option(
+                self.block_ptr.as_ptr().cast::<sys::rs2_options>(),
+                #[allow(clippy::useless_conversion)]
+                (option as i32).try_into().unwrap(),
+                value,
+                &mut err,
+            );
+            check_rs2_error!(err, OptionSetError::CouldNotSetOption)?;
 
-impl Colorizer {
-    pub fn create() -> Result<Self> {
-        let processing_block = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_colorizer(checker.inner_mut_ptr());
-            checker.check()?;
-            Self::new_from_raw(NonNull::new(ptr).unwrap())?
-        };
-        Ok(processing_block)
+            Ok(())
+        }
     }
 
-    pub fn with_options(color_scheme: ColorScheme) -> Result<Self> {
-        let processing_block = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_colorizer(checker.inner_mut_ptr());
-            checker.check()?;
-            Self::new_from_raw(NonNull::new(ptr).unwrap())?
-        };
-
-        let options = processing_block.to_options()?;
-        options[&Rs2Option::ColorScheme].set_value(color_scheme as usize as f32)?;
+    /// Gets the range for a given option.
+    ///
+    /// Returns some option range if the processing block supports the option, else `None`.
+    pub fn get_option_range(&self, option: Rs2Option) -> Option<Rs2OptionRange> {
+        if !self.supports_option(option) {
+            return None;
+        }
 
-        Ok(processing_block)
-    }
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+
+            let mut min = MaybeUninit::uninit();
+            let mut max = MaybeUninit::uninit();
+            let mut step = MaybeUninit::uninit();
+            let mut default = MaybeUninit::uninit();
+
+            sys::rs2_get_option_range(
+                self.block_ptr.as_ptr().cast::<sys::rs2_options>(),
+                #[allow(clippy::useless_conversion)]
+                (option as i32).try_into().unwrap(),
+                min.as_mut_ptr(),
+                max.as_mut_ptr(),
+                step.as_mut_ptr(),
+                default.as_mut_ptr(),
+                &mut err,
+            );
 
-    pub fn colorize(&mut self, depth_frame: DepthFrame) -> Result<VideoFrame> {
-        let frame_any = self.process(depth_frame)?;
-        let color_frame: VideoFrame = frame_any.try_extend_to()?.unwrap();
-        Ok(color_frame)
+            if err.as_ref().is_none() {
+                Some(Rs2OptionRange {
+                    min: min.assume_init(),
+                    max: max.assume_init(),
+                    step: step.assume_init(),
+                    default: default.assume_init(),
+                })
+            } else {
+                sys::rs2_free_error(err);
+                None
+            }
+        }
     }
-}
 
-impl HuffmanDepthDecompress {
-    pub fn create() -> Result<Self> {
-        let processing_block = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_huffman_depth_decompress_block(checker.inner_mut_ptr());
-            checker.check()?;
-            Self::new_from_raw(NonNull::new(ptr).unwrap())?
-        };
-        Ok(processing_block)
-    }
-}
+    /// Predicate for determining if this processing block supports a given option.
+    ///
+    /// Returns true iff the option is supported by this processing block.
+    pub fn supports_option(&self, option: Rs2Option) -> bool {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let val = sys::rs2_supports_option(
+                self.block_ptr.as_ptr().cast::<sys::rs2_options>(),
+                #[allow(clippy::useless_conversion)]
+                (option as i32).try_into().unwrap(),
+                &mut err,
+            );
 
-impl RatesPrinter {
-    pub fn create() -> Result<Self> {
-        let processing_block = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_rates_printer_block(checker.inner_mut_ptr());
-            checker.check()?;
-            Self::new_from_raw(NonNull::new(ptr).unwrap())?
-        };
-        Ok(processing_block)
+            if err.as_ref().is_none() {
+                val != 0
+            } else {
+                sys::rs2_free_error(err);
+                false
+            }
+        }
     }
-}
 
-impl<Kind> ToOptions for ProcessingBlock<Kind>
-where
-    Kind: processing_block_kind::ProcessingBlockKind,
-{
-    fn options_ptr(&self) -> NonNull<sys::rs2_options> {
-        self.ptr.cast::<sys::rs2_options>()
-    }
-}
+    /// Predicate for determining if the provided option is immutable or not.
+    ///
+    /// Returns true if the option is supported and cannot be mutated, otherwise false.
+    pub fn is_option_read_only(&self, option: Rs2Option) -> bool {
+        if !self.supports_option(option) {
+            return false;
+        }
 
-impl<Kind> Drop for ProcessingBlock<Kind>
-where
-    Kind: processing_block_kind::ProcessingBlockKind,
-{
-    fn drop(&mut self) {
         unsafe {
-            sys::rs2_delete_processing_block(self.ptr.as_ptr());
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let val = sys::rs2_is_option_read_only(
+                self.block_ptr.as_ptr().cast::<sys::rs2_options>(),
+                #[allow(clippy::useless_conversion)]
+                (option as i32).try_into().unwrap(),
+                &mut err,
+            );
+
+            if err.as_ref().is_none() {
+                val != 0
+            } else {
+                sys::rs2_free_error(err);
+                false
+            }
         }
     }
 }
-
-unsafe impl<Kind> Send for ProcessingBlock<Kind> where
-    Kind: processing_block_kind::ProcessingBlockKind
-{
-}
@@ -1,96 +1,47 @@
-//! Marker traits and types for [ProcessingBlock](crate::processing_block::ProcessingBlock).
-
-use crate::kind::Rs2Extension;
-
+//! Marker types for the kinds of [`ProcessingBlock`](crate::processing_block::ProcessingBlock).
+//!
+//! Each processing block in librealsense2 is created through its own constructor, but otherwise
+//! shares the same interface for processing frames and configuring options. The types in this
+//! module are used as the type parameter of a processing block to tell the different filters apart
+//! at compile time.
+
+/// Marker trait implemented by every kind of processing block.
 pub trait ProcessingBlockKind {}
-pub trait ExtendableProcessingBlockKind
-where
-    Self: ProcessingBlockKind,
-{
-    const EXTENSION: Rs2Extension;
-}
-
-#[derive(Debug)]
-pub struct Any;
-impl ProcessingBlockKind for Any {}
 
+/// Marker type for the decimation filter.
+///
+/// Reduces the resolution of depth frames by a configurable factor, see
+/// [`Rs2Option::FilterMagnitude`](crate::kind::Rs2Option::FilterMagnitude).
 #[derive(Debug)]
 pub struct DecimationFilterKind;
 impl ProcessingBlockKind for DecimationFilterKind {}
-impl ExtendableProcessingBlockKind for DecimationFilterKind {
-    const EXTENSION: Rs2Extension = Rs2Extension::DecimationFilter;
-}
-
-#[derive(Debug)]
-pub struct ThresholdFilterKind;
-impl ProcessingBlockKind for ThresholdFilterKind {}
-impl ExtendableProcessingBlockKind for ThresholdFilterKind {
-    const EXTENSION: Rs2Extension = Rs2Extension::ThresholdFilter;
-}
-
-#[derive(Debug)]
-pub struct DisparityFilterKind;
-impl ProcessingBlockKind for DisparityFilterKind {}
-impl ExtendableProcessingBlockKind for DisparityFilterKind {
-    const EXTENSION: Rs2Extension = Rs2Extension::DisparityFilter;
-}
 
+/// Marker type for the spatial filter.
+///
+/// Smooths depth frames along the image axes while preserving edges.
 #[derive(Debug)]
 pub struct SpatialFilterKind;
 impl ProcessingBlockKind for SpatialFilterKind {}
-impl ExtendableProcessingBlockKind for SpatialFilterKind {
-    const EXTENSION: Rs2Extension = Rs2Extension::SpatialFilter;
-}
 
+/// Marker type for the temporal filter.
+///
+/// Smooths depth frames over time, based on the frames previously processed by the same filter.
 #[derive(Debug)]
 pub struct TemporalFilterKind;
 impl ProcessingBlockKind for TemporalFilterKind {}
-impl ExtendableProcessingBlockKind for TemporalFilterKind {
-    const EXTENSION: Rs2Extension = Rs2Extension::TemporalFilter;
-}
 
+/// Marker type for the hole filling filter.
+///
+/// Fills pixels without a valid depth from their neighbours, see
+/// [`HoleFillingMode`](crate::kind::HoleFillingMode).
 #[derive(Debug)]
 pub struct HoleFillingFilterKind;
 impl ProcessingBlockKind for HoleFillingFilterKind {}
-impl ExtendableProcessingBlockKind for HoleFillingFilterKind {
-    const EXTENSION: Rs2Extension = Rs2Extension::HoleFillingFilter;
-}
-
-#[derive(Debug)]
-pub struct ZeroOrderFilterKind;
-impl ProcessingBlockKind for ZeroOrderFilterKind {}
-impl ExtendableProcessingBlockKind for ZeroOrderFilterKind {
-    const EXTENSION: Rs2Extension = Rs2Extension::ZeroOrderFilter;
-}
-
-#[derive(Debug)]
-pub struct PointCloudKind;
-impl ProcessingBlockKind for PointCloudKind {}
-
-#[derive(Debug)]
-pub struct YuyDecoderKind;
-impl ProcessingBlockKind for YuyDecoderKind {}
-
-#[derive(Debug)]
-pub struct UnitsTransformKind;
-impl ProcessingBlockKind for UnitsTransformKind {}
-
-#[derive(Debug)]
-pub struct SyncerKind;
-impl ProcessingBlockKind for SyncerKind {}
-
-#[derive(Debug)]
-pub struct AlignKind;
-impl ProcessingBlockKind for AlignKind {}
-
-#[derive(Debug)]
-pub struct ColorizerKind;
-impl ProcessingBlockKind for ColorizerKind {}
-
-#[derive(Debug)]
-pub struct HuffmanDepthDecompressKind;
-impl ProcessingBlockKind for HuffmanDepthDecompressKind {}
 
+/// Marker type for the disparity transform.
+///
+/// Converts depth frames to disparity frames or back, depending on the direction the block was
+/// created with. The spatial and temporal filters work best in the disparity domain.
 #[derive(Debug)]
-pub struct RatesPrinterKind;
-impl ProcessingBlockKind for RatesPrinterKind {}
+pub struct DisparityTransformKind;
+impl ProcessingBlockKind for DisparityTransformKind {}
@@ -1,88 +1,18 @@
-//! Defines the iterable list of processing blocks.
+//! Defines the list of processing blocks recommended for a sensor.
 
-use crate::{
-    error::{ErrorChecker, Result},
-    processing_block::AnyProcessingBlock,
-};
+use crate::kind::{Rs2Extension, FILTER_EXTENSIONS};
+use realsense_sys as sys;
+use std::{convert::TryInto, ptr::NonNull};
 
-/// The iterable list of [AnyProcessingBlock](AnyProcessingBlock)s.
+/// The list of processing blocks returned by `rs2_get_recommended_processing_blocks`.
+///
+/// The list owns the underlying pointer and deletes it when dropped.
 #[derive(Debug)]
-pub struct ProcessingBlockList {
+pub(crate) struct ProcessingBlockList {
+    /// A (non-null) pointer to the processing block list.
     ptr: NonNull<sys::rs2_processing_block_list>,
 }
 
-impl ProcessingBlockList {
-    /// Retrieves the [AnyProcessingBlock](AnyProcessingBlock) instance at index.
-    pub fn get(&mut self, index: usize) -> Result<AnyProcessingBlock> {
-        let block = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_get_processing_block(
-                self.ptr.as_ptr(),
-                index as c_int,
-                checker.inner_mut_ptr(),
-            );
-            checker.check()?;
-            AnyProcessingBlock::new_from_raw(NonNull::new(ptr).unwrap())?
-        };
-        Ok(block)
-    }
-
-    /// Returns the length of list.
-    pub fn len(&mut self) -> Result<usize> {
-        unsafe {
-            let mut checker = ErrorChecker::new();
-            let val = sys::rs2_get_recommended_processing_blocks_count(
-                self.ptr.as_ptr(),
-                checker.inner_mut_ptr(),
-            );
-            checker.check()?;
-            Ok(val as usize)
-        }
-    }
-
-    /// Checks if the list is empty.
-    pub fn is_empty(&mut self) -> Result<bool> {
-        Ok(self.len()? == 0)
-    }
-
-    /// Converts to iterator type.
-    pub fn try_into_iter(mut self) -> Result<ProcessingBlockListIntoIter> {
-        let len = self.len()?;
-        let ptr = self.into_raw();
-        let iter = ProcessingBlockListIntoIter {
-            len,
-            index: 0,
-            ptr: NonNull::new(ptr).unwrap(),
-        };
-        Ok(iter)
-    }
-
-    pub fn into_raw(self) -> *mut sys::rs2_processing_block_list {
-        let ptr = self.ptr;
-        mem::forget(self);
-        ptr.as_ptr()
-    }
-
-    pub unsafe fn from_raw(ptr: *mut sys::rs2_processing_block_list) -> Self {
-        Self {
-            ptr: NonNull::new(ptr).unwrap(),
-        }
-    }
-}
-
-impl IntoIterator for ProcessingBlockList {
-    type Item = Result<AnyProcessingBlock>;
-    type IntoIter = ProcessingBlockListIntoIter;
-
-    /// The method calls [ProcessingBlockList::try_into_iter](ProcessingBlockList::try_into_iter).
-    ///
-    /// # Panics
-    /// It panics if [ProcessingBlockList::try_into_iter](ProcessingBlockList::try_into_iter) returns error.
-    fn into_iter(self) -> Self::IntoIter {
-        self.try_into_iter().unwrap()
-    }
-}
-
 impl Drop for ProcessingBlockList {
     fn drop(&mut self) {
         unsafe {
@@ -91,44 +21,70 @@ impl Drop for ProcessingBlockList {
     }
 }
 
-/// The iterator type returned by [ProcessingBlockList::try_into_iter](ProcessingBlockList::try_into_iter).
-pub struct ProcessingBlockListIntoIter {
-    len: usize,
-    index: usize,
-    ptr: NonNull<sys::rs2_processing_block_list>,
+impl From<NonNull<sys::rs2_processing_block_list>> for ProcessingBlockList {
+    /// Takes ownership of a processing block list returned from librealsense2.
+    fn from(ptr: NonNull<sys::rs2_processing_block_list>) -> Self {
+        Self { ptr }
+    }
 }
 
-impl Iterator for ProcessingBlockListIntoIter {
-    type Item = Result<AnyProcessingBlock>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.index < self.len {
-            let result = unsafe {
-                let mut checker = ErrorChecker::new();
-                let ptr = sys::rs2_get_processing_block(
-                    self.ptr.as_ptr(),
-                    self.index as c_int,
-                    checker.inner_mut_ptr(),
-                );
-                match checker.check() {
-                    Ok(()) => AnyProcessingBlock::new_from_raw(NonNull::new(ptr).unwrap()),
-                    Err(err) => return Some(Err(err)),
-                }
-            };
-            self.index += 1;
-            Some(result)
-        } else {
-            None
+impl ProcessingBlockList {
+    /// Gets the number of processing blocks in the list.
+    ///
+    /// Returns zero if the length cannot be queried.
+    pub(crate) fn len(&self) -> usize {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let len = sys::rs2_get_recommended_processing_blocks_count(self.ptr.as_ptr(), &mut err);
+
+            if err.as_ref().is_none() {
+                len as usize
+            } else {
+                sys::rs2_free_error(err);
+                0
+            }
         }
     }
-}
 
-impl FusedIterator for ProcessingBlockListIntoIter {}
-
-impl Drop for ProcessingBlockListIntoIter {
-    fn drop(&mut self) {
+    /// Gets the filter extension of the processing block at `index`.
+    ///
+    /// The block is classified as the first of the [`FILTER_EXTENSIONS`] it is extendable to.
+    /// Returns `None` if the block cannot be retrieved or is not one of the filter extensions.
+    pub(crate) fn filter_extension(&self, index: usize) -> Option<Rs2Extension> {
         unsafe {
-            sys::rs2_delete_recommended_processing_blocks(self.ptr.as_ptr());
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let block_ptr =
+                sys::rs2_get_processing_block(self.ptr.as_ptr(), index as i32, &mut err);
+            if err.as_ref().is_some() {
+                sys::rs2_free_error(err);
+                return None;
+            }
+
+            let extension = FILTER_EXTENSIONS
+                .iter()
+                .copied()
+                // Recommended filters is the extension of sensors that provide this list, not of
+                // the blocks in it.
+                .filter(|ext| *ext != Rs2Extension::RecommendedFilters)
+                .find(|ext| {
+                    let is_extendable = sys::rs2_is_processing_block_extendable_to(
+                        block_ptr,
+                        #[allow(clippy::useless_conversion)]
+                        (*ext as i32).try_into().unwrap(),
+                        &mut err,
+                    );
+
+                    if err.as_ref().is_none() {
+                        is_extendable != 0
+                    } else {
+                        sys::rs2_free_error(err);
+                        err = std::ptr::null_mut();
+                        false
+                    }
+                });
+
+            sys::rs2_delete_processing_block(block_ptr);
+            extension
         }
     }
 }
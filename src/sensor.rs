@@ -10,20 +10,35 @@
 //! The hierarchy is effectively:
 //!
 //! [`Device`] |-> [`Sensor`] |-> [`StreamProfile`]
+//!
+//! Sensors are usually streamed from through a [pipeline](crate::pipeline), but can also be
+//! streamed from directly. Similar to pipelines, a sensor that is being streamed from directly
+//! moves through separate types for each of its states: a [`Sensor`] is [opened](Sensor::open)
+//! with a stream profile into an [`OpenSensor`], which is in turn
+//! [started](OpenSensor::start_with_callback) into a [`StreamingSensor`].
+
+mod open;
+mod streaming;
+
+pub use open::{OpenSensor, SensorOpenError};
+pub use streaming::{SensorStreamError, StreamingSensor};
 
 use crate::{
-    base::Rs2Roi,
+    base::{Rs2Extrinsics, Rs2Roi},
     check_rs2_error,
     device::{Device, DeviceConstructionError},
     kind::{
-        OptionSetError, Rs2CameraInfo, Rs2Exception, Rs2Extension, Rs2Option, Rs2OptionRange,
-        SENSOR_EXTENSIONS,
+        EmitterMode, OptionSetError, Rs2CameraInfo, Rs2DepthAutoExposureMode, Rs2DigitalGain,
+        Rs2Exception, Rs2Extension, Rs2Option, Rs2OptionRange, Rs2SensorMode, SENSOR_EXTENSIONS,
     },
+    processing_block_list::ProcessingBlockList,
     stream_profile::StreamProfile,
 };
 use anyhow::Result;
+use num_traits::{FromPrimitive, ToPrimitive};
 use realsense_sys as sys;
 use std::{
+    collections::HashMap,
     convert::{From, TryInto},
     ffi::CStr,
     mem::MaybeUninit,
@@ -55,6 +70,87 @@ pub enum RoiSetError {
     CouldNotSetRoi(Rs2Exception, String),
 }
 
+/// Type describing errors that can occur when accessing the auto exposure region of interest of a
+/// sensor.
+///
+/// Follows the standard pattern of errors where the enum variant describes what the low-level code
+/// was attempting to do while the string carried alongside describes the underlying error message
+/// from any C++ exceptions that occur.
+#[derive(Error, Debug)]
+pub enum RoiError {
+    /// The sensor does not support the [`Rs2Extension::Roi`] extension.
+    #[error("Sensor does not support a region of interest.")]
+    SensorDoesNotSupportRoi,
+    /// Could not get region of interest for sensor.
+    #[error("Could not get region of interest for sensor. Type: {0}; Reason: {1}")]
+    CouldNotGetRoi(Rs2Exception, String),
+    /// Could not set region of interest for sensor.
+    #[error("Could not set region of interest for sensor. Type: {0}; Reason: {1}")]
+    CouldNotSetRoi(Rs2Exception, String),
+}
+
+/// Type describing errors that can occur when trying to get extrinsics between two sensors.
+///
+/// Follows the standard pattern of errors where the enum variant describes what the low-level code
+/// was attempting to do while the string carried alongside describes the underlying error message
+/// from any C++ exceptions that occur.
+#[derive(Error, Debug)]
+pub enum ExtrinsicsError {
+    /// One of the sensors does not have a default stream profile to compute extrinsics from.
+    #[error("Sensor does not have a default stream profile.")]
+    NoDefaultStreamProfile,
+    /// Could not get extrinsics between the default streams of the two sensors.
+    #[error("Could not get extrinsics between sensors. Type: {0}; Reason: {1}")]
+    CouldNotGetExtrinsics(Rs2Exception, String),
+}
+
+/// A snapshot of the values of every option supported by a sensor at a point in time.
+///
+/// Snapshots are taken with [`Sensor::snapshot_options`], and can later be compared against the
+/// sensor with [`OptionSnapshot::changed_since`] to detect options that were modified in the
+/// meantime (e.g. by another process using librealsense2).
+#[derive(Debug, Clone, Default)]
+pub struct OptionSnapshot {
+    /// The value of each supported option at the time the snapshot was taken.
+    values: HashMap<Rs2Option, f32>,
+}
+
+impl OptionSnapshot {
+    /// Get the value of `option` at the time the snapshot was taken.
+    ///
+    /// Returns `None` if the option was not supported by the sensor.
+    pub fn get(&self, option: Rs2Option) -> Option<f32> {
+        self.values.get(&option).copied()
+    }
+
+    /// Get an iterator over every option and its value at the time the snapshot was taken.
+    pub fn iter(&self) -> impl Iterator<Item = (Rs2Option, f32)> + '_ {
+        self.values.iter().map(|(option, value)| (*option, *value))
+    }
+
+    /// Compare the snapshot against the current option values of `sensor`.
+    ///
+    /// Returns `(option, old_value, new_value)` for every option whose current value differs from
+    /// the value in the snapshot, ordered by option. Options which can no longer be read from the
+    /// sensor are skipped.
+    pub fn changed_since(&self, sensor: &Sensor) -> Vec<(Rs2Option, f32, f32)> {
+        let mut changed: Vec<_> = self
+            .values
+            .iter()
+            .filter_map(|(&option, &old)| {
+                let new = sensor.get_option(option)?;
+                if new != old {
+                    Some((option, old, new))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        changed.sort_by_key(|(option, _, _)| *option as i32);
+        changed
+    }
+}
+
 /// Type for holding sensor-related data.
 ///
 /// A sensor in librealsense2 corresponds to a physical component on the unit in some way, shape,
@@ -87,6 +183,11 @@ impl Drop for Sensor {
 
 unsafe impl Send for Sensor {}
 
+// Reading options, camera info and stream profiles through `&self` is guarded by librealsense2,
+// while anything that changes the sensor state requires `&mut self`. See the thread safety
+// policy in `crate::docs::architecture`.
+unsafe impl Sync for Sensor {}
+
 impl std::convert::From<NonNull<sys::rs2_sensor>> for Sensor {
     /// Attempt to construct a Sensor from a non-null pointer to `rs2_sensor`.
     fn from(sensor_ptr: NonNull<sys::rs2_sensor>) -> Self {
@@ -160,26 +261,65 @@ impl Sensor {
     pub fn extension(&self) -> Rs2Extension {
         let ext = SENSOR_EXTENSIONS
             .iter()
-            .find(|ext| unsafe {
-                let mut err = std::ptr::null_mut::<sys::rs2_error>();
-                let is_extendable = sys::rs2_is_sensor_extendable_to(
-                    self.sensor_ptr.as_ptr(),
-                    #[allow(clippy::useless_conversion)]
-                    (**ext as i32).try_into().unwrap(),
-                    &mut err,
-                );
-
-                if err.as_ref().is_none() {
-                    is_extendable != 0
-                } else {
-                    sys::rs2_free_error(err);
-                    false
-                }
-            })
+            .find(|ext| self.is_extendable_to(**ext))
             .unwrap();
         *ext
     }
 
+    /// Predicate for whether the sensor supports the given extension.
+    ///
+    /// Unlike [`Sensor::extension`], which only returns the first matching extension, this checks
+    /// a single extension, so e.g. a stereo depth sensor is extendable to both
+    /// [`Rs2Extension::DepthStereoSensor`] and [`Rs2Extension::DepthSensor`].
+    ///
+    /// Returns false if an error occurs while checking the extension.
+    pub fn is_extendable_to(&self, extension: Rs2Extension) -> bool {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let is_extendable = sys::rs2_is_sensor_extendable_to(
+                self.sensor_ptr.as_ptr(),
+                #[allow(clippy::useless_conversion)]
+                (extension as i32).try_into().unwrap(),
+                &mut err,
+            );
+
+            if err.as_ref().is_none() {
+                is_extendable != 0
+            } else {
+                sys::rs2_free_error(err);
+                false
+            }
+        }
+    }
+
+    /// Get the filters that librealsense2 recommends for processing frames from this sensor.
+    ///
+    /// Each recommended processing block is classified as one of the
+    /// [`FILTER_EXTENSIONS`](crate::kind::FILTER_EXTENSIONS), e.g. a depth sensor typically
+    /// recommends [`Rs2Extension::DecimationFilter`] and [`Rs2Extension::SpatialFilter`] among
+    /// others. Blocks that are not one of the filter extensions are skipped. Extensions are
+    /// returned in the order librealsense2 recommends applying the filters.
+    ///
+    /// Returns an empty `Vec` if the sensor does not recommend any processing blocks or an error
+    /// occurs while querying them.
+    pub fn recommended_processing_blocks(&self) -> Vec<Rs2Extension> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let list_ptr =
+                sys::rs2_get_recommended_processing_blocks(self.sensor_ptr.as_ptr(), &mut err);
+
+            if err.as_ref().is_some() {
+                sys::rs2_free_error(err);
+                return Vec::new();
+            }
+
+            let list = ProcessingBlockList::from(NonNull::new(list_ptr).unwrap());
+            (0..list.len())
+                .filter_map(|i| list.filter_extension(i))
+                .collect()
+        }
+    }
+
     /// Get the value associated with the provided Rs2Option for the sensor.
     ///
     /// Returns An `f32` value corresponding to that option within the librealsense2 library, or None
@@ -245,6 +385,107 @@ impl Sensor {
         }
     }
 
+    /// Get the value of `option` as one of the typed option values in [`kind`](crate::kind).
+    ///
+    /// Returns `None` if the option is not supported, or if its value does not correspond to any
+    /// variant of `T`.
+    fn get_option_as<T: FromPrimitive>(&self, option: Rs2Option) -> Option<T> {
+        self.get_option(option).and_then(T::from_f32)
+    }
+
+    /// Set `option` to one of the typed option values in [`kind`](crate::kind).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptionSetError::CouldNotSetOption`] if `value` cannot be represented as an option
+    /// value.
+    ///
+    /// Otherwise returns the same errors as [`Sensor::set_option`].
+    fn set_option_as<T: ToPrimitive>(
+        &mut self,
+        option: Rs2Option,
+        value: T,
+    ) -> Result<(), OptionSetError> {
+        let value = value.to_f32().ok_or_else(|| {
+            OptionSetError::CouldNotSetOption(
+                Rs2Exception::InvalidValue,
+                String::from("The value cannot be represented as an option value."),
+            )
+        })?;
+        self.set_option(option, value)
+    }
+
+    /// Get the auto exposure algorithm used by the sensor.
+    ///
+    /// Returns `None` if the sensor does not support [`Rs2Option::DepthAutoExposureMode`].
+    pub fn get_depth_auto_exposure_mode(&self) -> Option<Rs2DepthAutoExposureMode> {
+        self.get_option_as(Rs2Option::DepthAutoExposureMode)
+    }
+
+    /// Set the auto exposure algorithm used by the sensor.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Sensor::set_option`] for
+    /// [`Rs2Option::DepthAutoExposureMode`].
+    pub fn set_depth_auto_exposure_mode(
+        &mut self,
+        mode: Rs2DepthAutoExposureMode,
+    ) -> Result<(), OptionSetError> {
+        self.set_option_as(Rs2Option::DepthAutoExposureMode, mode)
+    }
+
+    /// Get the digital gain applied by the sensor.
+    ///
+    /// Returns `None` if the sensor does not support [`Rs2Option::DigitalGain`].
+    pub fn get_digital_gain(&self) -> Option<Rs2DigitalGain> {
+        self.get_option_as(Rs2Option::DigitalGain)
+    }
+
+    /// Set the digital gain applied by the sensor.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Sensor::set_option`] for [`Rs2Option::DigitalGain`].
+    pub fn set_digital_gain(&mut self, gain: Rs2DigitalGain) -> Result<(), OptionSetError> {
+        self.set_option_as(Rs2Option::DigitalGain, gain)
+    }
+
+    /// Get the resolution mode of the sensor.
+    ///
+    /// Returns `None` if the sensor does not support [`Rs2Option::SensorMode`].
+    pub fn get_sensor_mode(&self) -> Option<Rs2SensorMode> {
+        self.get_option_as(Rs2Option::SensorMode)
+    }
+
+    /// Set the resolution mode of the sensor.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Sensor::set_option`] for [`Rs2Option::SensorMode`].
+    pub fn set_sensor_mode(&mut self, mode: Rs2SensorMode) -> Result<(), OptionSetError> {
+        self.set_option_as(Rs2Option::SensorMode, mode)
+    }
+
+    /// Get the emitter selection of the sensor.
+    ///
+    /// Returns `None` if the sensor does not support [`Rs2Option::EmitterEnabled`].
+    pub fn get_emitter_mode(&self) -> Option<EmitterMode> {
+        self.get_option_as(Rs2Option::EmitterEnabled)
+    }
+
+    /// Set the emitter selection of the sensor.
+    ///
+    /// Not every device supports every [`EmitterMode`]; e.g. only some devices have an LED
+    /// emitter.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Sensor::set_option`] for [`Rs2Option::EmitterEnabled`].
+    pub fn set_emitter_mode(&mut self, mode: EmitterMode) -> Result<(), OptionSetError> {
+        self.set_option_as(Rs2Option::EmitterEnabled, mode)
+    }
+
     /// Gets the range for a given option.
     ///
     /// Returns some option range if the sensor supports the option, else `None`.
@@ -253,6 +494,74 @@ impl Sensor {
             return None;
         }
 
+        self.option_range_of_supported(option)
+    }
+
+    /// Get the range of every option supported by this sensor.
+    ///
+    /// This is equivalent to calling [`Sensor::get_option_range`] for each of the
+    /// [`supported_options`](Sensor::supported_options), but only checks which options are
+    /// supported once. Options whose range cannot be read are left out.
+    pub fn all_option_ranges(&self) -> HashMap<Rs2Option, Rs2OptionRange> {
+        self.supported_options()
+            .into_iter()
+            .filter_map(|option| {
+                self.option_range_of_supported(option)
+                    .map(|range| (option, range))
+            })
+            .collect()
+    }
+
+    /// Get every option supported by this sensor.
+    ///
+    /// The options are read from the list of options librealsense2 keeps for the sensor, rather
+    /// than checking each [`Rs2Option`] variant in turn. Options that this crate has no variant for
+    /// are left out.
+    ///
+    /// Returns an empty `Vec` if the list of options cannot be read.
+    pub fn supported_options(&self) -> Vec<Rs2Option> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let list = sys::rs2_get_options_list(
+                self.sensor_ptr.as_ptr().cast::<sys::rs2_options>(),
+                &mut err,
+            );
+            if err.as_ref().is_some() {
+                sys::rs2_free_error(err);
+                return Vec::new();
+            }
+
+            let len = sys::rs2_get_options_list_size(list, &mut err);
+            if err.as_ref().is_some() {
+                sys::rs2_free_error(err);
+                sys::rs2_delete_options_list(list);
+                return Vec::new();
+            }
+
+            let mut options = Vec::with_capacity(len as usize);
+            for i in 0..len {
+                let option = sys::rs2_get_option_from_list(list, i, &mut err);
+                if err.as_ref().is_some() {
+                    sys::rs2_free_error(err);
+                    err = std::ptr::null_mut();
+                    continue;
+                }
+
+                #[allow(clippy::useless_conversion)]
+                if let Some(option) = Rs2Option::from_i32(option.try_into().unwrap()) {
+                    options.push(option);
+                }
+            }
+
+            sys::rs2_delete_options_list(list);
+            options
+        }
+    }
+
+    /// Gets the range for an option that is known to be supported by this sensor.
+    ///
+    /// Returns `None` if the range cannot be read.
+    fn option_range_of_supported(&self, option: Rs2Option) -> Option<Rs2OptionRange> {
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
 
@@ -286,6 +595,19 @@ impl Sensor {
         }
     }
 
+    /// Take a snapshot of the current value of every option supported by this sensor.
+    ///
+    /// See [`OptionSnapshot::changed_since`] for detecting options that were modified after the
+    /// snapshot was taken.
+    pub fn snapshot_options(&self) -> OptionSnapshot {
+        let values = (0..sys::rs2_option_RS2_OPTION_COUNT as i32)
+            .filter_map(Rs2Option::from_i32)
+            .filter_map(|option| self.get_option(option).map(|value| (option, value)))
+            .collect();
+
+        OptionSnapshot { values }
+    }
+
     /// Predicate for determining if this sensor supports a given option
     ///
     /// Returns true iff the option is supported by this sensor.
@@ -334,6 +656,31 @@ impl Sensor {
         }
     }
 
+    /// Get the number of stream profiles associated with this sensor.
+    ///
+    /// This is cheaper than [`Sensor::stream_profiles`], as it does not construct any of the
+    /// profiles. Returns zero if an error occurs while getting the stream profiles.
+    pub fn stream_profiles_count(&self) -> usize {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let profiles_ptr = sys::rs2_get_stream_profiles(self.sensor_ptr.as_ptr(), &mut err);
+            if err.as_ref().is_some() {
+                sys::rs2_free_error(err);
+                return 0;
+            }
+
+            let len = sys::rs2_get_stream_profiles_count(profiles_ptr, &mut err);
+            sys::rs2_delete_stream_profiles_list(profiles_ptr);
+
+            if err.as_ref().is_none() {
+                len as usize
+            } else {
+                sys::rs2_free_error(err);
+                0
+            }
+        }
+    }
+
     /// Get a list of stream profiles associated with this sensor
     ///
     /// Returns a vector containing all the stream profiles associated with the sensor. The vector
@@ -357,6 +704,7 @@ impl Sensor {
                 return profiles;
             }
 
+            profiles.reserve_exact(len as usize);
             for i in 0..len {
                 match StreamProfile::try_create(&nonnull_profiles_ptr, i) {
                     Ok(s) => {
@@ -372,6 +720,64 @@ impl Sensor {
         profiles
     }
 
+    /// Format all stream profiles of the sensor for diagnostics.
+    ///
+    /// Produces one line per [stream profile](Sensor::stream_profiles), formatted with the
+    /// [`Display`](std::fmt::Display) implementation of [`StreamProfile`].
+    pub fn print_stream_profiles(&self) -> String {
+        self.stream_profiles()
+            .iter()
+            .map(|profile| format!("{}\n", profile))
+            .collect()
+    }
+
+    /// Get the extrinsics (rigid transform) from this sensor to the `other` sensor.
+    ///
+    /// Extrinsics in librealsense2 are defined between streams rather than sensors, so this uses
+    /// the default stream profile of each sensor. This is the typical way to obtain e.g. the
+    /// depth-to-color transform for alignment, since the default streams of the depth and color
+    /// sensors are the depth and color streams.
+    ///
+    /// Streams of the same sensor do not necessarily share an origin: the two infrared streams of
+    /// a stereo depth sensor are a baseline apart, for instance. Use
+    /// [`StreamProfile::extrinsics`] directly when the transform between specific streams is
+    /// needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ExtrinsicsError::NoDefaultStreamProfile`] if either sensor does not have a
+    /// default stream profile.
+    ///
+    /// Returns [`ExtrinsicsError::CouldNotGetExtrinsics`] if the extrinsics between the two
+    /// streams cannot be retrieved.
+    pub fn get_extrinsics_to(&self, other: &Sensor) -> Result<Rs2Extrinsics, ExtrinsicsError> {
+        let from = self
+            .stream_profiles()
+            .into_iter()
+            .find(|p| p.is_default())
+            .ok_or(ExtrinsicsError::NoDefaultStreamProfile)?;
+        let to = other
+            .stream_profiles()
+            .into_iter()
+            .find(|p| p.is_default())
+            .ok_or(ExtrinsicsError::NoDefaultStreamProfile)?;
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let mut extrinsics = MaybeUninit::<sys::rs2_extrinsics>::uninit();
+
+            sys::rs2_get_extrinsics(
+                from.get_raw().as_ptr(),
+                to.get_raw().as_ptr(),
+                extrinsics.as_mut_ptr(),
+                &mut err,
+            );
+            check_rs2_error!(err, ExtrinsicsError::CouldNotGetExtrinsics)?;
+
+            Ok(Rs2Extrinsics(extrinsics.assume_init()))
+        }
+    }
+
     // fn recommended_processing_blocks(&self) -> Vec<ProcessingBlock>{}
 
     /// Gets the value associated with the provided camera info key from the sensor.
@@ -424,11 +830,55 @@ impl Sensor {
         }
     }
 
+    /// Get all camera info supported by the sensor.
+    ///
+    /// Returns a map from every [`Rs2CameraInfo`] key supported by the sensor to its value. Values
+    /// that are not valid UTF-8 are converted lossily.
+    pub fn all_camera_info(&self) -> HashMap<Rs2CameraInfo, String> {
+        (0..sys::rs2_camera_info_RS2_CAMERA_INFO_COUNT as i32)
+            .filter_map(Rs2CameraInfo::from_i32)
+            .filter_map(|key| {
+                self.info(key)
+                    .map(|value| (key, value.to_string_lossy().into_owned()))
+            })
+            .collect()
+    }
+
+    /// Format all camera info supported by the sensor for diagnostics.
+    ///
+    /// Produces one `key: value` line per supported [`Rs2CameraInfo`] key, in the order the keys
+    /// are defined by librealsense2.
+    pub fn print_info(&self) -> String {
+        (0..sys::rs2_camera_info_RS2_CAMERA_INFO_COUNT as i32)
+            .filter_map(Rs2CameraInfo::from_i32)
+            .filter_map(|key| {
+                self.info(key)
+                    .map(|value| format!("{:?}: {}\n", key, value.to_string_lossy()))
+            })
+            .collect()
+    }
+
     /// Gets the auto exposure's region of interest for the sensor.
     ///
     /// Returns the region of interest for the auto exposure or None
-    /// if this isn't available.
+    /// if this isn't available. See [`Sensor::get_auto_exposure_roi`] for the reason why.
     pub fn get_region_of_interest(&self) -> Option<Rs2Roi> {
+        self.get_auto_exposure_roi().ok()
+    }
+
+    /// Gets the auto exposure's region of interest for the sensor.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RoiError::SensorDoesNotSupportRoi`] if the sensor does not support the
+    /// [`Rs2Extension::Roi`] extension.
+    ///
+    /// Returns [`RoiError::CouldNotGetRoi`] if getting the region of interest failed.
+    pub fn get_auto_exposure_roi(&self) -> Result<Rs2Roi, RoiError> {
+        if !self.is_extendable_to(Rs2Extension::Roi) {
+            return Err(RoiError::SensorDoesNotSupportRoi);
+        }
+
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
             let mut roi = Rs2Roi {
@@ -445,12 +895,9 @@ impl Sensor {
                 &mut roi.max_y,
                 &mut err,
             );
-            if err.as_ref().is_none() {
-                Some(roi)
-            } else {
-                sys::rs2_free_error(err);
-                None
-            }
+            check_rs2_error!(err, RoiError::CouldNotGetRoi)?;
+
+            Ok(roi)
         }
     }
 
@@ -482,4 +929,35 @@ impl Sensor {
             check_rs2_error!(err, RoiSetError::CouldNotSetRoi)
         }
     }
+
+    /// Sets the auto exposure's region of interest to `roi` for the sensor.
+    ///
+    /// Unlike [`Sensor::set_region_of_interest`], this checks that the sensor supports a region of
+    /// interest first. The same known issue applies: setting the region of interest can fail
+    /// directly after the pipeline is started.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RoiError::SensorDoesNotSupportRoi`] if the sensor does not support the
+    /// [`Rs2Extension::Roi`] extension.
+    ///
+    /// Returns [`RoiError::CouldNotSetRoi`] if setting the region of interest failed.
+    pub fn set_auto_exposure_roi(&mut self, roi: Rs2Roi) -> Result<(), RoiError> {
+        if !self.is_extendable_to(Rs2Extension::Roi) {
+            return Err(RoiError::SensorDoesNotSupportRoi);
+        }
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_set_region_of_interest(
+                self.sensor_ptr.as_ptr(),
+                roi.min_x,
+                roi.min_y,
+                roi.max_x,
+                roi.max_y,
+                &mut err,
+            );
+            check_rs2_error!(err, RoiError::CouldNotSetRoi)
+        }
+    }
 }
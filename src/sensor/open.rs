@@ -0,0 +1,337 @@
+//! Defines the open sensor type.
+
+use super::{
+    streaming::{any_trampoline, trampoline, SensorStreamError, StreamingSensor},
+    Sensor,
+};
+use crate::{
+    check_rs2_error,
+    frame::{AnyFrame, FrameCategory},
+    kind::{Rs2Exception, Rs2StreamKind},
+    stream_profile::StreamProfile,
+};
+use realsense_sys as sys;
+use std::{
+    collections::HashSet,
+    convert::TryFrom,
+    marker::PhantomData,
+    os::raw::c_void,
+    ptr::{self, NonNull},
+};
+use thiserror::Error;
+
+/// Type describing errors that can occur when trying to open a sensor.
+///
+/// Follows the standard pattern of errors where the enum variant describes what the low-level code
+/// was attempting to do while the string carried alongside describes the underlying error message
+/// from any C++ exceptions that occur.
+#[derive(Error, Debug)]
+pub enum SensorOpenError {
+    /// Could not open the sensor with the requested stream profile.
+    #[error("Could not open sensor. Type: {0}; Reason: {1}")]
+    CouldNotOpenSensor(Rs2Exception, String),
+    /// More than one of the requested stream profiles is for the same stream.
+    ///
+    /// Holds the kinds of the streams that were requested more than once.
+    #[error("Stream profiles conflict with one another. Kinds: {0:?}")]
+    ConflictingProfiles(Vec<Rs2StreamKind>),
+}
+
+/// Type representing a sensor that has been opened for exclusive access with a stream profile.
+///
+/// An open sensor is configured to stream, but is not yet streaming. Streaming is started with
+/// [`OpenSensor::start_with_callback`], and the sensor can be returned to its closed state with
+/// [`OpenSensor::close`]. Dropping an open sensor closes it.
+///
+/// The lifetime `'a` ties the open sensor to the [`StreamProfile`] it was opened with, so that the
+/// profile cannot be dropped while the sensor is configured with it.
+#[derive(Debug)]
+pub struct OpenSensor<'a> {
+    /// The underlying non-null sensor pointer.
+    sensor_ptr: NonNull<sys::rs2_sensor>,
+    /// Whether the sensor pointer is owned by us and should be deleted once closed.
+    should_drop: bool,
+    /// Marker tying this sensor to the stream profile it was opened with.
+    _profile: PhantomData<&'a StreamProfile>,
+}
+
+impl<'a> Drop for OpenSensor<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_close(self.sensor_ptr.as_ptr(), &mut err);
+            if err.as_ref().is_some() {
+                sys::rs2_free_error(err);
+            }
+
+            if self.should_drop {
+                sys::rs2_delete_sensor(self.sensor_ptr.as_ptr());
+            }
+        }
+    }
+}
+
+unsafe impl<'a> Send for OpenSensor<'a> {}
+
+impl<'a> OpenSensor<'a> {
+    /// Constructs a new open sensor from the constituent components.
+    ///
+    /// This is only to be used / called from the [`Sensor`] type, after the sensor has been opened
+    /// with `rs2_open`.
+    pub(crate) fn new(sensor_ptr: NonNull<sys::rs2_sensor>, should_drop: bool) -> Self {
+        Self {
+            sensor_ptr,
+            should_drop,
+            _profile: PhantomData,
+        }
+    }
+
+    /// Get the underlying low-level pointer to the sensor.
+    ///
+    /// # Safety
+    ///
+    /// This method is not intended to be called or used outside of the crate itself. The pointer
+    /// is only valid for as long as the open sensor is alive.
+    pub(crate) unsafe fn get_raw(&self) -> NonNull<sys::rs2_sensor> {
+        self.sensor_ptr
+    }
+
+    /// Close the sensor, releasing exclusive access to it.
+    ///
+    /// This method consumes the open sensor and returns the underlying sensor.
+    ///
+    /// Closing cannot fail in a way that leaves the sensor open. An open sensor is never
+    /// streaming, so `rs2_close` only reports an error if the sensor is no longer valid, e.g.
+    /// because its device was disconnected. There is nothing left to close in that case, so the
+    /// error is ignored and the sensor is returned regardless.
+    pub fn close(self) -> Sensor {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_close(self.sensor_ptr.as_ptr(), &mut err);
+            if err.as_ref().is_some() {
+                sys::rs2_free_error(err);
+            }
+
+            let sensor = Sensor {
+                sensor_ptr: self.sensor_ptr,
+                should_drop: self.should_drop,
+            };
+
+            std::mem::forget(self);
+            sensor
+        }
+    }
+
+    /// Start streaming from the sensor, calling `callback` with every frame it produces.
+    ///
+    /// The callback is run on a librealsense2 thread. Only frames that can be represented as `K`
+    /// are passed to the callback; any other frames are released immediately. `K` must implement
+    /// [`FrameCategory`], e.g. [`DepthFrame`](crate::frame::DepthFrame) or
+    /// [`AccelFrame`](crate::frame::AccelFrame).
+    ///
+    /// The callback must not panic. If it does, the process is aborted, since unwinding across the
+    /// FFI boundary is undefined behaviour.
+    ///
+    /// # Errors
+    ///
+    /// Returns the open sensor alongside [`SensorStreamError::CouldNotStartSensor`] if the sensor
+    /// could not start streaming. The callback is dropped in that case.
+    pub fn start_with_callback<K, F>(
+        self,
+        callback: F,
+    ) -> Result<StreamingSensor<'a, F>, (Self, SensorStreamError)>
+    where
+        K: TryFrom<NonNull<sys::rs2_frame>> + FrameCategory,
+        F: FnMut(K) + Send + 'static,
+    {
+        self.start(callback, trampoline::<K, F>)
+    }
+
+    /// Start streaming from the sensor, calling `callback` with every frame it produces,
+    /// regardless of its type.
+    ///
+    /// This is useful for sensors that produce frames of several types, such as the accelerometer
+    /// and gyroscope frames of an IMU. The callback is run on a librealsense2 thread, and converts
+    /// each [`AnyFrame`] into the frame types it is interested in.
+    ///
+    /// The callback must not panic. If it does, the process is aborted, since unwinding across the
+    /// FFI boundary is undefined behaviour.
+    ///
+    /// # Errors
+    ///
+    /// Returns the open sensor alongside [`SensorStreamError::CouldNotStartSensor`] if the sensor
+    /// could not start streaming. The callback is dropped in that case.
+    pub fn start_with_any_callback<F>(
+        self,
+        callback: F,
+    ) -> Result<StreamingSensor<'a, F>, (Self, SensorStreamError)>
+    where
+        F: FnMut(AnyFrame) + Send + 'static,
+    {
+        self.start(callback, any_trampoline::<F>)
+    }
+
+    /// Start streaming with `rs2_start`, passing `callback` to librealsense2 through `trampoline`.
+    fn start<F>(
+        self,
+        callback: F,
+        trampoline: unsafe extern "C" fn(*mut sys::rs2_frame, *mut c_void),
+    ) -> Result<StreamingSensor<'a, F>, (Self, SensorStreamError)> {
+        unsafe {
+            let mut err = ptr::null_mut::<sys::rs2_error>();
+            let callback_ptr = NonNull::new(Box::into_raw(Box::new(callback))).unwrap();
+
+            sys::rs2_start(
+                self.sensor_ptr.as_ptr(),
+                Some(trampoline),
+                callback_ptr.as_ptr().cast::<c_void>(),
+                &mut err,
+            );
+
+            if let Err(e) = check_rs2_error!(err, SensorStreamError::CouldNotStartSensor) {
+                drop(Box::from_raw(callback_ptr.as_ptr()));
+                return Err((self, e));
+            }
+
+            Ok(StreamingSensor::new(self, callback_ptr))
+        }
+    }
+}
+
+impl Sensor {
+    /// Open the sensor for exclusive access, configuring it to stream with `profile`.
+    ///
+    /// This allows streaming directly from the sensor without a
+    /// [pipeline](crate::pipeline::InactivePipeline). The profile should be one of the sensor's own
+    /// [stream profiles](Sensor::stream_profiles), and must outlive the returned [`OpenSensor`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the sensor alongside [`SensorOpenError::CouldNotOpenSensor`] if the sensor could not
+    /// be opened, e.g. because the profile does not belong to the sensor or the sensor is already
+    /// in use.
+    pub fn open(self, profile: &StreamProfile) -> Result<OpenSensor<'_>, (Self, SensorOpenError)> {
+        unsafe {
+            let mut err = ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_open(
+                self.sensor_ptr.as_ptr(),
+                profile.get_raw().as_ptr(),
+                &mut err,
+            );
+            if let Err(e) = check_rs2_error!(err, SensorOpenError::CouldNotOpenSensor) {
+                return Err((self, e));
+            }
+
+            let open = OpenSensor::new(self.sensor_ptr, self.should_drop);
+
+            std::mem::forget(self);
+            Ok(open)
+        }
+    }
+
+    /// Open the sensor for exclusive access, configuring it to stream with all of `profiles` at
+    /// once.
+    ///
+    /// This is the same as [`Sensor::open`], but for sensors that stream more than one stream
+    /// simultaneously, e.g. both infrared streams of a stereo depth sensor. All of the profiles
+    /// must outlive the returned [`OpenSensor`].
+    ///
+    /// # Errors
+    ///
+    /// Every error is returned alongside the sensor, so that opening it can be retried.
+    ///
+    /// Returns [`SensorOpenError::ConflictingProfiles`] if more than one profile is for the same
+    /// stream, i.e. has the same kind and index, such as two depth profiles with different
+    /// resolutions.
+    ///
+    /// Returns [`SensorOpenError::CouldNotOpenSensor`] if the sensor could not be opened, e.g.
+    /// because the profiles are otherwise incompatible, a profile does not belong to the sensor,
+    /// or the sensor is already in use.
+    pub fn open_multiple<'a>(
+        self,
+        profiles: &[&'a StreamProfile],
+    ) -> Result<OpenSensor<'a>, (Self, SensorOpenError)> {
+        if let [profile] = profiles {
+            return self.open(profile);
+        }
+
+        let conflicts = conflicting_kinds(
+            profiles
+                .iter()
+                .map(|profile| (profile.kind(), profile.index())),
+        );
+        if !conflicts.is_empty() {
+            return Err((self, SensorOpenError::ConflictingProfiles(conflicts)));
+        }
+
+        unsafe {
+            let mut raw_profiles = profiles
+                .iter()
+                .map(|profile| profile.get_raw().as_ptr() as *const sys::rs2_stream_profile)
+                .collect::<Vec<_>>();
+
+            let mut err = ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_open_multiple(
+                self.sensor_ptr.as_ptr(),
+                raw_profiles.as_mut_ptr(),
+                raw_profiles.len() as i32,
+                &mut err,
+            );
+            if let Err(e) = check_rs2_error!(err, SensorOpenError::CouldNotOpenSensor) {
+                return Err((self, e));
+            }
+
+            let open = OpenSensor::new(self.sensor_ptr, self.should_drop);
+
+            std::mem::forget(self);
+            Ok(open)
+        }
+    }
+}
+
+/// Finds the kinds of the streams that appear more than once in `streams`.
+///
+/// Streams are identified by their kind and index. Each conflicting kind is reported once, in the
+/// order it first conflicts.
+fn conflicting_kinds<I>(streams: I) -> Vec<Rs2StreamKind>
+where
+    I: IntoIterator<Item = (Rs2StreamKind, usize)>,
+{
+    let mut seen = HashSet::new();
+    let mut conflicts = Vec::new();
+
+    for (kind, index) in streams {
+        if !seen.insert((kind, index)) && !conflicts.contains(&kind) {
+            conflicts.push(kind);
+        }
+    }
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streams_requested_twice_conflict() {
+        let conflicts = conflicting_kinds(vec![
+            (Rs2StreamKind::Infrared, 1),
+            (Rs2StreamKind::Infrared, 2),
+            (Rs2StreamKind::Depth, 0),
+            (Rs2StreamKind::Depth, 0),
+            (Rs2StreamKind::Depth, 0),
+        ]);
+
+        assert_eq!(conflicts, vec![Rs2StreamKind::Depth]);
+    }
+
+    #[test]
+    fn distinct_streams_do_not_conflict() {
+        let conflicts = conflicting_kinds(vec![
+            (Rs2StreamKind::Infrared, 1),
+            (Rs2StreamKind::Infrared, 2),
+        ]);
+
+        assert!(conflicts.is_empty());
+    }
+}
@@ -0,0 +1,178 @@
+//! Defines the streaming sensor type.
+
+use super::open::OpenSensor;
+use crate::{
+    check_rs2_error,
+    frame::{AnyFrame, FrameCategory},
+    kind::{Rs2Exception, Rs2StreamKind},
+};
+use realsense_sys as sys;
+use std::{
+    convert::{TryFrom, TryInto},
+    os::raw::c_void,
+    panic::{self, AssertUnwindSafe},
+    ptr::NonNull,
+};
+use thiserror::Error;
+
+/// Type describing errors that can occur when starting or stopping streaming from a sensor.
+///
+/// Follows the standard pattern of errors where the enum variant describes what the low-level code
+/// was attempting to do while the string carried alongside describes the underlying error message
+/// from any C++ exceptions that occur.
+#[derive(Error, Debug)]
+pub enum SensorStreamError {
+    /// Could not start streaming from the sensor.
+    #[error("Could not start streaming from sensor. Type: {0}; Reason: {1}")]
+    CouldNotStartSensor(Rs2Exception, String),
+    /// Could not stop streaming from the sensor.
+    #[error("Could not stop streaming from sensor. Type: {0}; Reason: {1}")]
+    CouldNotStopSensor(Rs2Exception, String),
+}
+
+/// Frame callback passed to `rs2_start`.
+///
+/// `data` is the boxed user callback of type `F`. Frames passed to this function are owned by us,
+/// so any frame that cannot be handed off to the user callback as a `K` is released here.
+pub(crate) unsafe extern "C" fn trampoline<K, F>(frame: *mut sys::rs2_frame, data: *mut c_void)
+where
+    K: TryFrom<NonNull<sys::rs2_frame>> + FrameCategory,
+    F: FnMut(K) + Send + 'static,
+{
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let frame_ptr = match NonNull::new(frame) {
+            Some(ptr) => ptr,
+            None => return,
+        };
+        let callback = &mut *data.cast::<F>();
+
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        let is_extendable_to = sys::rs2_is_frame_extendable_to(
+            frame_ptr.as_ptr(),
+            #[allow(clippy::useless_conversion)]
+            (K::extension() as i32).try_into().unwrap(),
+            &mut err,
+        );
+
+        if err.as_ref().is_some() {
+            sys::rs2_free_error(err);
+        } else if is_extendable_to != 0 {
+            if let Ok(f) = K::try_from(frame_ptr) {
+                // Frames of the wrong kind are released when `f` is dropped.
+                if K::kind() == Rs2StreamKind::Any || f.has_correct_kind() {
+                    callback(f);
+                }
+                return;
+            }
+        }
+
+        sys::rs2_release_frame(frame_ptr.as_ptr());
+    }));
+
+    if result.is_err() {
+        eprintln!("Sensor frame callback panicked");
+        std::process::abort();
+    }
+}
+
+/// Frame callback passed to `rs2_start` for callbacks that take frames of any type.
+///
+/// `data` is the boxed user callback of type `F`, which takes ownership of every frame.
+pub(crate) unsafe extern "C" fn any_trampoline<F>(frame: *mut sys::rs2_frame, data: *mut c_void)
+where
+    F: FnMut(AnyFrame) + Send + 'static,
+{
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        if let Some(frame_ptr) = NonNull::new(frame) {
+            let callback = &mut *data.cast::<F>();
+            callback(AnyFrame::from(frame_ptr));
+        }
+    }));
+
+    if result.is_err() {
+        eprintln!("Sensor frame callback panicked");
+        std::process::abort();
+    }
+}
+
+/// Type representing a sensor that is streaming frames to a callback.
+///
+/// The callback is owned by the streaming sensor. Streaming is stopped, and the callback dropped,
+/// with [`StreamingSensor::stop`] or when the streaming sensor is dropped.
+#[derive(Debug)]
+pub struct StreamingSensor<'a, F> {
+    /// The underlying open sensor.
+    ///
+    /// This is an `Option` only so that it can be moved out in [`StreamingSensor::stop`]; it is
+    /// always `Some` otherwise.
+    sensor: Option<OpenSensor<'a>>,
+    /// The boxed user callback that librealsense2 calls with every frame.
+    callback: NonNull<F>,
+}
+
+impl<'a, F> Drop for StreamingSensor<'a, F> {
+    fn drop(&mut self) {
+        let mut stopped = true;
+        if let Some(sensor) = self.sensor.as_ref() {
+            unsafe {
+                let mut err = std::ptr::null_mut::<sys::rs2_error>();
+                sys::rs2_stop(sensor.get_raw().as_ptr(), &mut err);
+                if err.as_ref().is_some() {
+                    sys::rs2_free_error(err);
+                    stopped = false;
+                }
+            }
+        }
+
+        // If the sensor could not be stopped, librealsense2 may still call the callback, so it is
+        // leaked rather than freed.
+        if stopped {
+            // SAFETY: librealsense2 no longer calls the callback once `rs2_stop` has returned.
+            unsafe {
+                drop(Box::from_raw(self.callback.as_ptr()));
+            }
+        }
+    }
+}
+
+unsafe impl<'a, F: Send> Send for StreamingSensor<'a, F> {}
+
+impl<'a, F> StreamingSensor<'a, F> {
+    /// Constructs a new streaming sensor from the constituent components.
+    ///
+    /// This is only to be used / called from the [`OpenSensor`] type, after streaming has been
+    /// started with `rs2_start`.
+    pub(crate) fn new(sensor: OpenSensor<'a>, callback: NonNull<F>) -> Self {
+        Self {
+            sensor: Some(sensor),
+            callback,
+        }
+    }
+
+    /// Stop streaming from the sensor.
+    ///
+    /// This method consumes the streaming sensor, drops the callback, and returns the sensor in its
+    /// open (but not streaming) state.
+    ///
+    /// # Errors
+    ///
+    /// Returns the streaming sensor alongside [`SensorStreamError::CouldNotStopSensor`] if the
+    /// sensor could not be stopped. The sensor is still streaming in that case, so stopping it can
+    /// be retried, or it can be dropped to release it regardless.
+    pub fn stop(mut self) -> Result<OpenSensor<'a>, (Self, SensorStreamError)> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_stop(self.sensor.as_ref().unwrap().get_raw().as_ptr(), &mut err);
+            if let Err(stop_error) = check_rs2_error!(err, SensorStreamError::CouldNotStopSensor) {
+                return Err((self, stop_error));
+            }
+
+            // SAFETY: librealsense2 no longer calls the callback once `rs2_stop` has returned.
+            drop(Box::from_raw(self.callback.as_ptr()));
+        }
+
+        let sensor = self.sensor.take().unwrap();
+        std::mem::forget(self);
+        Ok(sensor)
+    }
+}
@@ -0,0 +1,63 @@
+//! Defines the list of sensors of a device.
+
+use crate::sensor::Sensor;
+use realsense_sys as sys;
+use std::ptr::NonNull;
+
+/// The list of sensors returned by `rs2_query_sensors`.
+///
+/// The list owns the underlying pointer and deletes it when dropped. Sensors created from the list
+/// keep a reference to their device, so they remain valid after the list is dropped.
+#[derive(Debug)]
+pub(crate) struct SensorList {
+    /// A (non-null) pointer to the sensor list.
+    ptr: NonNull<sys::rs2_sensor_list>,
+}
+
+impl Drop for SensorList {
+    fn drop(&mut self) {
+        unsafe {
+            sys::rs2_delete_sensor_list(self.ptr.as_ptr());
+        }
+    }
+}
+
+impl From<NonNull<sys::rs2_sensor_list>> for SensorList {
+    /// Takes ownership of a sensor list returned from librealsense2.
+    fn from(ptr: NonNull<sys::rs2_sensor_list>) -> Self {
+        Self { ptr }
+    }
+}
+
+impl SensorList {
+    /// Gets the number of sensors in the list.
+    ///
+    /// Returns zero if the length cannot be queried.
+    pub(crate) fn len(&self) -> usize {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let len = sys::rs2_get_sensors_count(self.ptr.as_ptr(), &mut err);
+
+            if err.as_ref().is_none() {
+                len as usize
+            } else {
+                sys::rs2_free_error(err);
+                0
+            }
+        }
+    }
+
+    /// Creates the sensor at `index`.
+    ///
+    /// Returns `None` if the sensor cannot be created.
+    pub(crate) fn sensor(&self, index: usize) -> Option<Sensor> {
+        Sensor::try_create(&self.ptr, index as i32).ok()
+    }
+
+    /// Consumes the list, creating its sensors one at a time as the iterator advances.
+    ///
+    /// Sensors that cannot be created are skipped.
+    pub(crate) fn into_sensors(self) -> impl Iterator<Item = Sensor> {
+        (0..self.len()).filter_map(move |index| self.sensor(index))
+    }
+}
@@ -0,0 +1,560 @@
+//! Types for injecting frames into librealsense2 from outside of a physical device.
+//!
+//! A [`SoftwareDevice`] behaves like any other device registered with a
+//! [`Context`](crate::context::Context), except that its frames are not read from hardware but
+//! injected by the application through a [`SoftwareSensor`]. This is useful for running recorded
+//! or synthetic data through the rest of librealsense2, e.g. processing blocks or pipelines,
+//! without a camera attached.
+//!
+//! Streams are described with a [`SoftwareVideoStream`], or added by mirroring an existing
+//! [`StreamProfile`] so that the software stream has the same kind, format, resolution and
+//! intrinsics as the stream it stands in for. The software sensor is then
+//! [opened and started](SoftwareSensor::sensor) like any other [`Sensor`]:
+//!
+//! ```no_run
+//! use realsense_rust::{
+//!     base::Rs2Intrinsics,
+//!     context::Context,
+//!     frame::{DepthFrame, FrameEx},
+//!     kind::{Rs2Format, Rs2StreamKind, Rs2TimestampDomain},
+//!     software_device::{SoftwareDevice, SoftwareVideoStream},
+//! };
+//! use realsense_sys as sys;
+//! use std::ffi::CString;
+//!
+//! let context = Context::new()?;
+//! let software_device = SoftwareDevice::new(&context)?;
+//! let mut software_sensor = software_device.add_sensor(&CString::new("Depth")?)?;
+//! software_sensor.add_video_stream(&SoftwareVideoStream {
+//!     kind: Rs2StreamKind::Depth,
+//!     index: 0,
+//!     unique_id: 1,
+//!     width: 640,
+//!     height: 480,
+//!     framerate: 30,
+//!     format: Rs2Format::Z16,
+//!     bytes_per_pixel: 2,
+//!     intrinsics: Rs2Intrinsics(sys::rs2_intrinsics {
+//!         width: 640,
+//!         height: 480,
+//!         ppx: 320.0,
+//!         ppy: 240.0,
+//!         fx: 600.0,
+//!         fy: 600.0,
+//!         model: sys::rs2_distortion_RS2_DISTORTION_NONE,
+//!         coeffs: [0.0; 5],
+//!     }),
+//! })?;
+//!
+//! let sensor = software_sensor.sensor()?;
+//! let profile = &sensor.stream_profiles()[0];
+//! let streaming = sensor
+//!     .open(profile)
+//!     .map_err(|(_, e)| e)?
+//!     .start_with_callback(|frame: DepthFrame| println!("{}", frame.frame_number()))
+//!     .map_err(|(_, e)| e)?;
+//!
+//! let depth = vec![0u8; 640 * 480 * 2];
+//! software_sensor.inject_frame(&depth, 0.0, Rs2TimestampDomain::SystemTime, 0, 0.001, profile)?;
+//! # drop(streaming);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::{
+    base::Rs2Intrinsics,
+    check_rs2_error,
+    context::Context,
+    kind::{Rs2Exception, Rs2Format, Rs2StreamKind, Rs2TimestampDomain},
+    sensor::Sensor,
+    sensor_list::SensorList,
+    stream_profile::StreamProfile,
+};
+use realsense_sys as sys;
+use std::{
+    alloc::{self, Layout},
+    convert::TryInto,
+    ffi::CStr,
+    os::raw::c_void,
+    ptr::NonNull,
+};
+use thiserror::Error;
+
+/// Type describing errors that can occur when creating or using a software device.
+///
+/// Follows the standard pattern of errors where the enum variant describes what the low-level code
+/// was attempting to do while the string carried alongside describes the underlying error message
+/// from any C++ exceptions that occur.
+#[derive(Error, Debug)]
+pub enum SoftwareDeviceError {
+    /// The software device could not be created.
+    #[error("Could not create software device. Type: {0}; Reason: {1}")]
+    CouldNotCreateSoftwareDevice(Rs2Exception, String),
+    /// The software device could not be added to the context.
+    #[error("Could not add software device to the context. Type: {0}; Reason: {1}")]
+    CouldNotAddToContext(Rs2Exception, String),
+    /// A sensor could not be added to the software device.
+    #[error("Could not add sensor to software device. Type: {0}; Reason: {1}")]
+    CouldNotAddSensor(Rs2Exception, String),
+    /// A stream profile could not be added to the software sensor.
+    #[error("Could not add stream profile to software sensor. Type: {0}; Reason: {1}")]
+    CouldNotAddStreamProfile(Rs2Exception, String),
+    /// The software sensor could not be retrieved as a [`Sensor`].
+    #[error("Could not get sensor of software device. Type: {0}; Reason: {1}")]
+    CouldNotGetSensor(Rs2Exception, String),
+    /// The frame was injected for a stream profile that was not added to the software sensor.
+    #[error("Stream profile with unique id {0} was not added to the software sensor.")]
+    StreamProfileNotAdded(i32),
+    /// The frame data does not match the size of a frame of the stream profile.
+    #[error("Frame data is {actual} bytes, but the stream profile expects {expected} bytes.")]
+    FrameDataSizeMismatch {
+        /// The number of bytes in a frame of the stream profile.
+        expected: usize,
+        /// The number of bytes that were passed in.
+        actual: usize,
+    },
+    /// The frame could not be injected into the software sensor.
+    #[error("Could not inject frame into software sensor. Type: {0}; Reason: {1}")]
+    CouldNotInjectFrame(Rs2Exception, String),
+}
+
+/// Type representing a device whose frames are injected by the application.
+///
+/// The device is registered with the context it was created from, so it is listed alongside any
+/// physical devices by [`Context::query_devices`].
+#[derive(Debug)]
+pub struct SoftwareDevice {
+    /// A non-null pointer to the underlying librealsense software device.
+    device_ptr: NonNull<sys::rs2_device>,
+}
+
+impl Drop for SoftwareDevice {
+    fn drop(&mut self) {
+        unsafe {
+            sys::rs2_delete_device(self.device_ptr.as_ptr());
+        }
+    }
+}
+
+unsafe impl Send for SoftwareDevice {}
+
+impl SoftwareDevice {
+    /// Create a new software device and add it to `context`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SoftwareDeviceError::CouldNotCreateSoftwareDevice`] if the device cannot be
+    /// created.
+    ///
+    /// Returns [`SoftwareDeviceError::CouldNotAddToContext`] if the device cannot be added to the
+    /// context.
+    pub fn new(context: &Context) -> Result<Self, SoftwareDeviceError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let device_ptr = sys::rs2_create_software_device(&mut err);
+            check_rs2_error!(err, SoftwareDeviceError::CouldNotCreateSoftwareDevice)?;
+
+            // Constructed before adding to the context so that the device is released on error.
+            let device = Self {
+                device_ptr: NonNull::new(device_ptr).unwrap(),
+            };
+
+            sys::rs2_context_add_software_device(
+                context.get_raw().as_ptr(),
+                device.device_ptr.as_ptr(),
+                &mut err,
+            );
+            check_rs2_error!(err, SoftwareDeviceError::CouldNotAddToContext)?;
+
+            Ok(device)
+        }
+    }
+
+    /// Add a new sensor called `name` to the software device.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SoftwareDeviceError::CouldNotAddSensor`] if the sensor cannot be added.
+    pub fn add_sensor(&self, name: &CStr) -> Result<SoftwareSensor, SoftwareDeviceError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let sensor_ptr = sys::rs2_software_device_add_sensor(
+                self.device_ptr.as_ptr(),
+                name.as_ptr(),
+                &mut err,
+            );
+            check_rs2_error!(err, SoftwareDeviceError::CouldNotAddSensor)?;
+
+            // Constructed before querying the sensors so that the sensor is released on error.
+            let mut sensor = SoftwareSensor {
+                sensor_ptr: NonNull::new(sensor_ptr).unwrap(),
+                index: 0,
+                streams: Vec::new(),
+            };
+
+            // Sensors are appended to the device, so the new sensor is the last one in its list.
+            let sensors_ptr = sys::rs2_query_sensors(self.device_ptr.as_ptr(), &mut err);
+            check_rs2_error!(err, SoftwareDeviceError::CouldNotAddSensor)?;
+            sensor.index = SensorList::from(NonNull::new(sensors_ptr).unwrap())
+                .len()
+                .saturating_sub(1);
+
+            Ok(sensor)
+        }
+    }
+}
+
+/// Description of a video stream to add to a [`SoftwareSensor`].
+///
+/// Unlike the profiles of a physical sensor, nothing about a software stream is queried from
+/// hardware, so every property of the stream is given up front. Frames of the stream are injected
+/// with [`SoftwareSensor::inject_frame`].
+#[derive(Debug)]
+pub struct SoftwareVideoStream {
+    /// The kind of stream, e.g. depth or color.
+    pub kind: Rs2StreamKind,
+    /// The index of the stream, to tell apart several streams of the same kind (e.g. infrared).
+    pub index: usize,
+    /// The unique id of the stream, which must not be shared with any other stream of the context.
+    pub unique_id: i32,
+    /// The width of a frame, in pixels.
+    pub width: usize,
+    /// The height of a frame, in pixels.
+    pub height: usize,
+    /// The framerate of the stream, in frames per second.
+    pub framerate: i32,
+    /// The format of the pixels of a frame.
+    pub format: Rs2Format,
+    /// The number of bytes each pixel occupies in a frame.
+    ///
+    /// Rows of injected frames are `width * bytes_per_pixel` bytes long.
+    pub bytes_per_pixel: usize,
+    /// The intrinsics of the stream.
+    pub intrinsics: Rs2Intrinsics,
+}
+
+/// A video stream that was added to a [`SoftwareSensor`].
+#[derive(Debug)]
+struct SoftwareStream {
+    /// The unique id of the stream.
+    unique_id: i32,
+    /// The profile of the stream, which is owned by the sensor and must not be deleted.
+    profile_ptr: NonNull<sys::rs2_stream_profile>,
+    /// The width of a frame, in pixels.
+    width: usize,
+    /// The height of a frame, in pixels.
+    height: usize,
+    /// The number of bytes each pixel occupies in a frame.
+    bytes_per_pixel: usize,
+}
+
+/// Type representing a sensor of a [`SoftwareDevice`], into which frames are injected.
+///
+/// To stream from the sensor, get it as a [`Sensor`] with [`SoftwareSensor::sensor`], then open
+/// and start it with one of the stream profiles that were added to it.
+#[derive(Debug)]
+pub struct SoftwareSensor {
+    /// A non-null pointer to the underlying librealsense software sensor.
+    sensor_ptr: NonNull<sys::rs2_sensor>,
+    /// The index of the sensor in the sensor list of its device.
+    index: usize,
+    /// The video streams added to the sensor.
+    streams: Vec<SoftwareStream>,
+}
+
+impl Drop for SoftwareSensor {
+    fn drop(&mut self) {
+        unsafe {
+            sys::rs2_delete_sensor(self.sensor_ptr.as_ptr());
+        }
+    }
+}
+
+unsafe impl Send for SoftwareSensor {}
+
+impl SoftwareSensor {
+    /// Get the software sensor as a [`Sensor`] of its device.
+    ///
+    /// The returned sensor lists the streams added to the software sensor as its
+    /// [stream profiles](Sensor::stream_profiles), and can be [opened](Sensor::open) and started
+    /// like the sensor of a physical device. Frames injected while it is streaming are passed to
+    /// its callback.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SoftwareDeviceError::CouldNotGetSensor`] if the sensor cannot be retrieved from
+    /// its device.
+    pub fn sensor(&self) -> Result<Sensor, SoftwareDeviceError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            // The software sensor keeps its device alive, so the device can be recovered from it.
+            let device_ptr = sys::rs2_create_device_from_sensor(self.sensor_ptr.as_ptr(), &mut err);
+            check_rs2_error!(err, SoftwareDeviceError::CouldNotGetSensor)?;
+
+            let sensors_ptr = sys::rs2_query_sensors(device_ptr, &mut err);
+            sys::rs2_delete_device(device_ptr);
+            check_rs2_error!(err, SoftwareDeviceError::CouldNotGetSensor)?;
+
+            SensorList::from(NonNull::new(sensors_ptr).unwrap())
+                .sensor(self.index)
+                .ok_or_else(|| {
+                    SoftwareDeviceError::CouldNotGetSensor(
+                        Rs2Exception::InvalidValue,
+                        format!("Device has no sensor at index {}.", self.index),
+                    )
+                })
+        }
+    }
+
+    /// Add the video stream described by `stream` to the sensor.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SoftwareDeviceError::CouldNotAddStreamProfile`] if the stream cannot be added.
+    pub fn add_video_stream(
+        &mut self,
+        stream: &SoftwareVideoStream,
+    ) -> Result<(), SoftwareDeviceError> {
+        let video_stream = sys::rs2_video_stream {
+            type_: stream.kind as sys::rs2_stream,
+            index: stream.index as i32,
+            uid: stream.unique_id,
+            width: stream.width as i32,
+            height: stream.height as i32,
+            fps: stream.framerate,
+            bpp: stream.bytes_per_pixel as i32,
+            fmt: stream.format as sys::rs2_format,
+            intrinsics: stream.intrinsics.0,
+        };
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let profile_ptr = sys::rs2_software_sensor_add_video_stream(
+                self.sensor_ptr.as_ptr(),
+                video_stream,
+                &mut err,
+            );
+            check_rs2_error!(err, SoftwareDeviceError::CouldNotAddStreamProfile)?;
+
+            self.streams.push(SoftwareStream {
+                unique_id: stream.unique_id,
+                profile_ptr: NonNull::new(profile_ptr).unwrap(),
+                width: stream.width,
+                height: stream.height,
+                bytes_per_pixel: stream.bytes_per_pixel,
+            });
+            Ok(())
+        }
+    }
+
+    /// Add a video stream to the sensor that mirrors `profile`.
+    ///
+    /// The new stream has the same kind, index, unique id, format, framerate, resolution and
+    /// intrinsics as `profile`. Streams with packed formats, whose pixels do not each occupy a
+    /// whole number of bytes (e.g. [`Rs2Format::Raw10`] or [`Rs2Format::Y12I`]), cannot be
+    /// mirrored; describe them with [`SoftwareSensor::add_video_stream`] instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SoftwareDeviceError::CouldNotAddStreamProfile`] if `profile` is not a video
+    /// stream, its format is packed or has no fixed number of bytes per pixel, or the stream cannot
+    /// be added.
+    pub fn add_stream_profile(
+        &mut self,
+        profile: &StreamProfile,
+    ) -> Result<(), SoftwareDeviceError> {
+        let invalid_profile = |reason: String| {
+            SoftwareDeviceError::CouldNotAddStreamProfile(Rs2Exception::InvalidValue, reason)
+        };
+
+        let video = profile
+            .try_as_video()
+            .map_err(|e| invalid_profile(e.to_string()))?;
+        let intrinsics = profile
+            .intrinsics()
+            .map_err(|e| invalid_profile(e.to_string()))?;
+        let bytes_per_pixel = bytes_per_pixel(profile.format()).ok_or_else(|| {
+            invalid_profile(format!(
+                "Format {:?} does not have a whole number of bytes per pixel.",
+                profile.format()
+            ))
+        })?;
+
+        self.add_video_stream(&SoftwareVideoStream {
+            kind: profile.kind(),
+            index: profile.index(),
+            unique_id: profile.unique_id(),
+            width: video.width(),
+            height: video.height(),
+            framerate: profile.framerate(),
+            format: profile.format(),
+            bytes_per_pixel,
+            intrinsics,
+        })
+    }
+
+    /// Inject a video frame into the stream with the same unique id as `profile`.
+    ///
+    /// `profile` is usually one of the [stream profiles](Sensor::stream_profiles) of
+    /// [`SoftwareSensor::sensor`], or the profile that the stream was mirrored from.
+    ///
+    /// `data` is copied, so it does not need to outlive the call. It must hold one tightly packed
+    /// frame of the stream, i.e. `width * height * bytes_per_pixel` bytes. Frames are only
+    /// delivered while the sensor is streaming; otherwise they are dropped by librealsense2.
+    ///
+    /// `timestamp` is in milliseconds, measured in the given `domain`. `depth_units` is the number
+    /// of meters represented by a single unit of depth, as in
+    /// [`DepthFrame::depth_units`](crate::frame::DepthFrame::depth_units). It is only used for
+    /// depth frames, e.g. `0.001` for millimeters; pass `0.0` for other streams.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SoftwareDeviceError::StreamProfileNotAdded`] if no stream with the unique id of
+    /// `profile` was added to the sensor.
+    ///
+    /// Returns [`SoftwareDeviceError::FrameDataSizeMismatch`] if `data` does not hold exactly one
+    /// frame of the stream.
+    ///
+    /// Returns [`SoftwareDeviceError::CouldNotInjectFrame`] if `frame_number` does not fit in a C
+    /// `int`, or if the frame cannot be injected.
+    pub fn inject_frame(
+        &self,
+        data: &[u8],
+        timestamp: f64,
+        domain: Rs2TimestampDomain,
+        frame_number: u64,
+        depth_units: f32,
+        profile: &StreamProfile,
+    ) -> Result<(), SoftwareDeviceError> {
+        let stream = self
+            .streams
+            .iter()
+            .find(|stream| stream.unique_id == profile.unique_id())
+            .ok_or(SoftwareDeviceError::StreamProfileNotAdded(
+                profile.unique_id(),
+            ))?;
+
+        let stride = stream.width * stream.bytes_per_pixel;
+        let expected = stride * stream.height;
+        if data.len() != expected {
+            return Err(SoftwareDeviceError::FrameDataSizeMismatch {
+                expected,
+                actual: data.len(),
+            });
+        }
+
+        let frame_number = frame_number.try_into().map_err(|_| {
+            SoftwareDeviceError::CouldNotInjectFrame(
+                Rs2Exception::InvalidValue,
+                format!(
+                    "Frame number {} is too large for a software frame.",
+                    frame_number
+                ),
+            )
+        })?;
+
+        let pixels = allocate_pixels(data).cast::<c_void>();
+        let frame = sys::rs2_software_video_frame {
+            pixels,
+            deleter: Some(free_pixels),
+            stride: stride as i32,
+            bpp: stream.bytes_per_pixel as i32,
+            timestamp,
+            #[allow(clippy::useless_conversion)]
+            domain: (domain as i32).try_into().unwrap(),
+            frame_number,
+            profile: stream.profile_ptr.as_ptr(),
+            depth_units,
+        };
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            // librealsense2 takes ownership of the pixels and calls the deleter once the frame is
+            // released, including when the frame is dropped because the sensor is not streaming.
+            sys::rs2_software_sensor_on_video_frame(self.sensor_ptr.as_ptr(), frame, &mut err);
+            if let Err(e) = check_rs2_error!(err, SoftwareDeviceError::CouldNotInjectFrame) {
+                // The call failed before the frame, and with it the pixels, were handed over.
+                free_pixels(pixels);
+                return Err(e);
+            }
+
+            Ok(())
+        }
+    }
+}
+
+/// The number of bytes each pixel of `format` occupies in a frame.
+///
+/// Returns `None` for formats without a fixed per-pixel layout, and for packed formats whose
+/// channels are not a whole number of bytes wide (e.g. 10-bit raw or 12-bit infrared pairs).
+fn bytes_per_pixel(format: Rs2Format) -> Option<usize> {
+    let bits = format.bit_depth_per_channel()? as usize;
+    let channels = format.channel_count()? as usize;
+
+    if bits % 8 != 0 {
+        return None;
+    }
+    Some(bits / 8 * channels)
+}
+
+/// Alignment of the pixel buffers handed to librealsense2.
+///
+/// This is also the size of the header in front of the pixels that records their length, so that
+/// [`free_pixels`] can reconstruct the allocation from the pixel pointer alone.
+const PIXEL_BUFFER_ALIGN: usize = 16;
+
+/// Copy `data` into a new pixel buffer that is released with [`free_pixels`].
+fn allocate_pixels(data: &[u8]) -> *mut u8 {
+    let layout = Layout::from_size_align(data.len() + PIXEL_BUFFER_ALIGN, PIXEL_BUFFER_ALIGN)
+        .expect("Frame data is too large to allocate.");
+
+    unsafe {
+        let base = alloc::alloc(layout);
+        if base.is_null() {
+            alloc::handle_alloc_error(layout);
+        }
+        base.cast::<usize>().write(data.len());
+
+        let pixels = base.add(PIXEL_BUFFER_ALIGN);
+        std::ptr::copy_nonoverlapping(data.as_ptr(), pixels, data.len());
+        pixels
+    }
+}
+
+/// Deleter passed to librealsense2 for pixel buffers created by [`allocate_pixels`].
+unsafe extern "C" fn free_pixels(pixels: *mut c_void) {
+    let base = pixels.cast::<u8>().sub(PIXEL_BUFFER_ALIGN);
+    let len = base.cast::<usize>().read();
+
+    alloc::dealloc(
+        base,
+        Layout::from_size_align_unchecked(len + PIXEL_BUFFER_ALIGN, PIXEL_BUFFER_ALIGN),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes_per_pixel_rejects_packed_formats() {
+        assert_eq!(bytes_per_pixel(Rs2Format::Z16), Some(2));
+        assert_eq!(bytes_per_pixel(Rs2Format::Rgb8), Some(3));
+        assert_eq!(bytes_per_pixel(Rs2Format::Bgra8), Some(4));
+        assert_eq!(bytes_per_pixel(Rs2Format::Y10Bpack), None);
+        assert_eq!(bytes_per_pixel(Rs2Format::Raw10), None);
+        assert_eq!(bytes_per_pixel(Rs2Format::Y12I), None);
+        assert_eq!(bytes_per_pixel(Rs2Format::Mjpeg), None);
+    }
+
+    #[test]
+    fn pixel_buffers_copy_data_and_are_freed() {
+        let data = [1u8, 2, 3, 4, 5];
+        let pixels = allocate_pixels(&data);
+
+        unsafe {
+            assert_eq!(pixels as usize % PIXEL_BUFFER_ALIGN, 0);
+            assert_eq!(std::slice::from_raw_parts(pixels, data.len()), &data);
+            free_pixels(pixels.cast::<c_void>());
+        }
+    }
+}
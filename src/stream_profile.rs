@@ -44,7 +44,13 @@ use crate::{
 use anyhow::Result;
 use num_traits::FromPrimitive;
 use realsense_sys as sys;
-use std::{convert::TryFrom, mem::MaybeUninit, ptr::NonNull};
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+    hash::{Hash, Hasher},
+    mem::MaybeUninit,
+    ptr::NonNull,
+};
 use thiserror::Error;
 
 /// Type describing errors that can occur when trying to construct a stream profile.
@@ -95,6 +101,9 @@ pub enum DataError {
     /// Could not get motion intrinsics from the requested stream.
     #[error("Could not get motion intrinsics. Type: {0}; Reason: {1}")]
     CouldNotGetMotionIntrinsics(Rs2Exception, String),
+    /// Could not get the resolution of the requested video stream.
+    #[error("Could not get video stream resolution. Type: {0}; Reason: {1}")]
+    CouldNotGetResolution(Rs2Exception, String),
 }
 
 /// Type for holding the stream profile information.
@@ -210,6 +219,44 @@ impl Drop for StreamProfile {
     }
 }
 
+impl Display for StreamProfile {
+    /// Formats the profile on a single line, e.g.
+    /// `kind=Depth format=Z16 res=640x480 fps=30 default=true unique_id=1`.
+    ///
+    /// The resolution is omitted for streams that are not video streams.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "kind={} format={:?}", self.stream, self.format)?;
+        if let Ok(video) = self.try_as_video() {
+            write!(f, " res={}x{}", video.width(), video.height())?;
+        }
+        write!(
+            f,
+            " fps={} default={} unique_id={}",
+            self.framerate, self.is_default, self.unique_id
+        )
+    }
+}
+
+impl PartialEq for StreamProfile {
+    /// Two stream profiles are equal if they have the same [unique ID](StreamProfile::unique_id).
+    ///
+    /// The unique ID identifies a stream across the different ways of obtaining its profile, so a
+    /// profile from [`Sensor::stream_profiles`](crate::sensor::Sensor::stream_profiles) compares
+    /// equal to the profile of a frame from that stream. No other attributes are compared.
+    fn eq(&self, other: &Self) -> bool {
+        self.unique_id == other.unique_id
+    }
+}
+
+impl Eq for StreamProfile {}
+
+impl Hash for StreamProfile {
+    /// Hashes only the [unique ID](StreamProfile::unique_id), consistent with [`PartialEq`].
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.unique_id.hash(state);
+    }
+}
+
 impl StreamProfile {
     /// Attempt to construct a stream profile from a profile list and index.
     ///
@@ -378,14 +425,8 @@ impl StreamProfile {
     ///
     /// Returns [`DataError::CouldNotGetIntrinsics`] if this call fails for any other reason.
     pub fn intrinsics(&self) -> Result<Rs2Intrinsics, DataError> {
-        match self.stream {
-            Rs2StreamKind::Depth => (),
-            Rs2StreamKind::Color => (),
-            Rs2StreamKind::Infrared => (),
-            Rs2StreamKind::Fisheye => (),
-            _ => {
-                return Err(DataError::StreamDoesNotHaveVideoIntrinsics);
-            }
+        if !self.stream.supports_video_intrinsics() {
+            return Err(DataError::StreamDoesNotHaveVideoIntrinsics);
         }
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
@@ -416,12 +457,8 @@ impl StreamProfile {
     /// Returns [`DataError::CouldNotGetMotionIntrinsics`](DataError::CouldNotGetMotionIntrinsics)
     /// if this call fails for any other reason.
     pub fn motion_intrinsics(&self) -> Result<Rs2MotionDeviceIntrinsics, DataError> {
-        match self.stream {
-            Rs2StreamKind::Gyro => (),
-            Rs2StreamKind::Accel => (),
-            _ => {
-                return Err(DataError::StreamDoesNotHaveMotionIntrinsics);
-            }
+        if !self.stream.supports_motion_intrinsics() {
+            return Err(DataError::StreamDoesNotHaveMotionIntrinsics);
         }
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
@@ -433,4 +470,86 @@ impl StreamProfile {
             Ok(Rs2MotionDeviceIntrinsics(intrinsics.assume_init()))
         }
     }
+
+    /// Get a view of the stream as a video stream, with its resolution.
+    ///
+    /// Video streams are the streams that have [video intrinsics](StreamProfile::intrinsics),
+    /// e.g. depth, color or infrared streams. Getting the resolution this way is cheaper than
+    /// getting the full set of intrinsics.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DataError::StreamDoesNotHaveVideoIntrinsics`] if the stream is not a video
+    /// stream.
+    ///
+    /// Returns [`DataError::CouldNotGetResolution`] if this call fails for any other reason.
+    pub fn try_as_video(&self) -> Result<VideoStreamProfile<'_>, DataError> {
+        if !self.stream.supports_video_intrinsics() {
+            return Err(DataError::StreamDoesNotHaveVideoIntrinsics);
+        }
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let mut width = 0;
+            let mut height = 0;
+
+            sys::rs2_get_video_stream_resolution(
+                self.ptr.as_ptr(),
+                &mut width,
+                &mut height,
+                &mut err,
+            );
+            check_rs2_error!(err, DataError::CouldNotGetResolution)?;
+
+            Ok(VideoStreamProfile {
+                profile: self,
+                width: width as usize,
+                height: height as usize,
+            })
+        }
+    }
+
+    /// Get the underlying low-level pointer to the stream profile.
+    ///
+    /// # Safety
+    ///
+    /// This method is not intended to be called or used outside of the crate itself. Be warned, it
+    /// is _undefined behaviour_ to call [`realsense_sys::rs2_delete_stream_profile`] on this
+    /// pointer. If you do, you risk a double-free error when the [`StreamProfile`] struct itself
+    /// is dropped.
+    ///
+    pub(crate) unsafe fn get_raw(&self) -> NonNull<sys::rs2_stream_profile> {
+        self.ptr
+    }
+}
+
+/// A view of a video [`StreamProfile`] alongside its resolution.
+///
+/// Obtained through [`StreamProfile::try_as_video`]. The resolution is read once on construction,
+/// so that profiles can be sorted or filtered by resolution without further calls into
+/// librealsense2.
+#[derive(Debug)]
+pub struct VideoStreamProfile<'a> {
+    /// The underlying stream profile.
+    profile: &'a StreamProfile,
+    /// The width of the stream's frames, in pixels.
+    width: usize,
+    /// The height of the stream's frames, in pixels.
+    height: usize,
+}
+
+impl<'a> VideoStreamProfile<'a> {
+    /// Get the underlying stream profile.
+    pub fn profile(&self) -> &'a StreamProfile {
+        self.profile
+    }
+
+    /// Get the width of the stream's frames, in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get the height of the stream's frames, in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
 }
@@ -3,16 +3,28 @@
 #![cfg(feature = "test-single-device")]
 
 use realsense_rust::{
+    advanced_mode::AdvancedModeDevice,
     base::Rs2Roi,
-    config::Config,
+    config::{Config, ConfigurationError},
     context::Context,
-    frame::{ColorFrame, DepthFrame, FrameEx, InfraredFrame},
-    kind::{Rs2CameraInfo, Rs2Extension, Rs2Format, Rs2Option, Rs2ProductLine, Rs2StreamKind},
+    depth_pipeline::{create_standard_depth_pipeline, DepthPipelinePreset},
+    firmware_logger::FirmwareLogger,
+    frame::{AnyFrame, ColorFrame, CompositeFrame, DepthFrame, FrameEx, InfraredFrame},
+    frame_queue::FrameQueue,
+    kind::{
+        Rs2CameraInfo, Rs2Extension, Rs2Format, Rs2LogSeverity, Rs2Option, Rs2ProductLine,
+        Rs2StreamKind, Rs2TimestampDomain, FILTER_EXTENSIONS,
+    },
     pipeline::InactivePipeline,
+    processing_block::DecimationFilter,
+    sensor::SensorOpenError,
+    software_device::{SoftwareDevice, SoftwareDeviceError},
 };
 use std::{
     collections::{HashMap, HashSet},
     convert::TryFrom,
+    ffi::CString,
+    sync::mpsc,
     time::Duration,
 };
 
@@ -386,3 +398,926 @@ fn d400_region_of_interest_accessible() {
         color_sensor.set_region_of_interest(roi).unwrap();
     }
 }
+
+/// Verify that the profile of a started pipeline reports the streams that were enabled.
+#[test]
+fn d400_pipeline_profile_reports_active_streams() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let pipeline = pipeline.start(Some(config)).unwrap();
+        let profile = pipeline.profile();
+
+        assert_eq!(
+            profile.device().info(Rs2CameraInfo::SerialNumber),
+            Some(serial)
+        );
+
+        let depth = profile.stream(Rs2StreamKind::Depth, None).unwrap();
+        assert_eq!(depth.format(), Rs2Format::Z16);
+        assert!(profile
+            .stream(Rs2StreamKind::Depth, Some(depth.index()))
+            .is_some());
+        assert!(profile.stream(Rs2StreamKind::Color, None).is_none());
+    }
+}
+
+/// Verify that stream profiles of the same stream compare equal however they were obtained.
+#[test]
+fn d400_stream_profiles_are_identified_by_unique_id() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        let frames = pipeline.wait(None).unwrap();
+        let depth_frame = frames.frames_of_type::<DepthFrame>().pop().unwrap();
+        let active = pipeline
+            .profile()
+            .stream(Rs2StreamKind::Depth, None)
+            .unwrap();
+        assert!(depth_frame.stream_profile() == active);
+
+        let sensor_profiles: HashSet<_> = device
+            .sensors()
+            .into_iter()
+            .flat_map(|sensor| sensor.stream_profiles())
+            .collect();
+        assert!(sensor_profiles.contains(active));
+    }
+}
+
+/// Verify that the auto exposure's region of interest reads back what was set.
+#[test]
+fn d400_auto_exposure_roi_round_trips() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Color, None, 0, 0, Rs2Format::Rgba8, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        // Wait until a frame is received to make sure the camera is properly initialized.
+        let _ = pipeline.wait(None).unwrap();
+
+        let profile = pipeline.profile();
+        let intrinsics = profile.streams().first().unwrap().intrinsics().unwrap();
+        let width = intrinsics.width() as i32;
+        let height = intrinsics.height() as i32;
+
+        let mut color_sensor = profile.device().first_color_sensor().unwrap();
+        color_sensor
+            .set_option(Rs2Option::EnableAutoExposure, 1.0)
+            .unwrap();
+
+        let roi = Rs2Roi {
+            min_x: width / 4,
+            min_y: height / 4,
+            max_x: width * 3 / 4,
+            max_y: height * 3 / 4,
+        };
+        color_sensor.set_auto_exposure_roi(roi.clone()).unwrap();
+
+        let read_back = color_sensor.get_auto_exposure_roi().unwrap();
+        assert_eq!(
+            (
+                read_back.min_x,
+                read_back.min_y,
+                read_back.max_x,
+                read_back.max_y
+            ),
+            (roi.min_x, roi.min_y, roi.max_x, roi.max_y)
+        );
+    }
+}
+
+/// Verify that a depth sensor can be opened and streamed from directly, without a pipeline.
+#[test]
+fn d400_can_stream_from_open_sensor() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let sensor = device
+            .sensors()
+            .into_iter()
+            .find(|sensor| sensor.extension() == Rs2Extension::DepthSensor)
+            .unwrap();
+
+        let profiles = sensor.stream_profiles();
+        let profile = profiles
+            .iter()
+            .find(|p| {
+                p.kind() == Rs2StreamKind::Depth
+                    && p.format() == Rs2Format::Z16
+                    && p.framerate() == 30
+            })
+            .unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        let open = sensor.open(profile).map_err(|(_, e)| e).unwrap();
+        let streaming = open
+            .start_with_callback(move |frame: DepthFrame| {
+                let _ = sender.send(frame.frame_number());
+            })
+            .map_err(|(_, e)| e)
+            .unwrap();
+
+        for _ in 0..5 {
+            receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        }
+
+        let _sensor = streaming.stop().map_err(|(_, e)| e).unwrap().close();
+    }
+}
+
+/// Verify that both infrared streams of the depth sensor can be opened and streamed at once.
+#[test]
+fn d400_can_open_sensor_with_both_infrared_streams() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let sensor = device
+            .sensors()
+            .into_iter()
+            .find(|sensor| sensor.extension() == Rs2Extension::DepthSensor)
+            .unwrap();
+
+        let profiles = sensor.stream_profiles();
+        let infrared = |index| {
+            profiles
+                .iter()
+                .find(|p| {
+                    p.kind() == Rs2StreamKind::Infrared
+                        && p.index() == index
+                        && p.format() == Rs2Format::Y8
+                        && p.framerate() == 30
+                        && p.try_as_video().is_ok_and(|v| v.width() == 640)
+                })
+                .unwrap()
+        };
+        let (left, right) = (infrared(1), infrared(2));
+
+        let (sender, receiver) = mpsc::channel();
+        let open = sensor
+            .open_multiple(&[left, right])
+            .map_err(|(_, e)| e)
+            .unwrap();
+        let streaming = open
+            .start_with_callback(move |frame: InfraredFrame| {
+                let _ = sender.send(frame.stream_profile().index());
+            })
+            .map_err(|(_, e)| e)
+            .unwrap();
+
+        let mut indices = HashSet::new();
+        while indices.len() < 2 {
+            indices.insert(receiver.recv_timeout(Duration::from_secs(5)).unwrap());
+        }
+
+        let sensor = streaming.stop().map_err(|(_, e)| e).unwrap().close();
+        assert!(matches!(
+            sensor.open_multiple(&[left, left]),
+            Err((_, SensorOpenError::ConflictingProfiles(kinds))) if kinds == vec![Rs2StreamKind::Infrared]
+        ));
+    }
+}
+
+/// Verify that frames of any type streamed from a sensor can be converted into concrete frames.
+#[test]
+fn d400_can_stream_any_frames_from_open_sensor() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let sensor = device
+            .sensors()
+            .into_iter()
+            .find(|sensor| sensor.extension() == Rs2Extension::DepthSensor)
+            .unwrap();
+
+        let profiles = sensor.stream_profiles();
+        let profile = profiles
+            .iter()
+            .find(|p| {
+                p.kind() == Rs2StreamKind::Depth
+                    && p.format() == Rs2Format::Z16
+                    && p.framerate() == 30
+            })
+            .unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        let open = sensor.open(profile).map_err(|(_, e)| e).unwrap();
+        let streaming = open
+            .start_with_any_callback(move |frame: AnyFrame| {
+                // Panicking in the callback aborts, so the checks are made on the test thread.
+                let is_color = frame.is::<ColorFrame>();
+                let _ = sender.send((is_color, DepthFrame::try_from(frame).is_ok()));
+            })
+            .map_err(|(_, e)| e)
+            .unwrap();
+
+        for _ in 0..5 {
+            let (is_color, is_depth) = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+            assert!(!is_color);
+            assert!(is_depth);
+        }
+
+        let _sensor = streaming.stop().map_err(|(_, e)| e).unwrap().close();
+    }
+}
+
+#[test]
+fn d400_decimation_filter_reduces_resolution() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        let mut decimation = DecimationFilter::new().unwrap();
+        decimation
+            .set_option(Rs2Option::FilterMagnitude, 2.0)
+            .unwrap();
+
+        let frameset = pipeline.wait(None).unwrap();
+        let depth_frame = frameset
+            .frames_of_type::<DepthFrame>()
+            .into_iter()
+            .next()
+            .unwrap();
+        let (width, height) = (depth_frame.width(), depth_frame.height());
+
+        let decimated = decimation.process(depth_frame).unwrap();
+        assert!(decimated.width() < width);
+        assert!(decimated.height() < height);
+    }
+}
+
+#[test]
+fn d400_standard_depth_pipeline_keeps_resolution() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        let mut chain = create_standard_depth_pipeline(DepthPipelinePreset::HighDensity).unwrap();
+
+        for _ in 0..5 {
+            let frameset = pipeline.wait(None).unwrap();
+            let depth_frame = frameset
+                .frames_of_type::<DepthFrame>()
+                .into_iter()
+                .next()
+                .unwrap();
+            let (width, height) = (depth_frame.width(), depth_frame.height());
+
+            let filtered = chain.process(depth_frame).unwrap();
+            assert_eq!(filtered.width(), width);
+            assert_eq!(filtered.height(), height);
+        }
+    }
+}
+
+#[test]
+fn d400_frame_queue_returns_enqueued_frames() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        let queue = FrameQueue::with_capacity(2).unwrap();
+        let mut frame_numbers = Vec::new();
+        for _ in 0..2 {
+            let frameset = pipeline.wait(None).unwrap();
+            let depth_frame = frameset
+                .frames_of_type::<DepthFrame>()
+                .into_iter()
+                .next()
+                .unwrap();
+            frame_numbers.push(depth_frame.frame_number());
+            queue.enqueue(depth_frame);
+        }
+
+        let frame = queue
+            .dequeue(Some(Duration::from_millis(100)))
+            .unwrap()
+            .unwrap();
+        assert!(frame.is::<DepthFrame>());
+        let depth_frame = DepthFrame::try_from(frame).ok().unwrap();
+        assert_eq!(depth_frame.frame_number(), frame_numbers[0]);
+
+        queue.flush();
+        assert!(queue
+            .dequeue(Some(Duration::from_millis(100)))
+            .unwrap()
+            .is_none());
+    }
+}
+
+#[test]
+fn d400_iter_of_type_matches_frames_of_type() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 640, 0, Rs2Format::Z16, 30)
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Color, None, 640, 0, Rs2Format::Rgb8, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        let frameset = pipeline.wait(None).unwrap();
+
+        let depth_frames = frameset.iter_of_type::<DepthFrame>().collect::<Vec<_>>();
+        assert_eq!(depth_frames.len(), 1);
+        assert_eq!(
+            depth_frames.len(),
+            frameset.frames_of_type::<DepthFrame>().len()
+        );
+
+        let color_frames = frameset.iter_of_type::<ColorFrame>().collect::<Vec<_>>();
+        assert_eq!(color_frames.len(), 1);
+        assert_eq!(color_frames[0].width(), 640);
+
+        // Color frames are not depth frames, so the iterator must not yield them as such.
+        assert!(frameset
+            .iter_of_type::<DepthFrame>()
+            .all(|frame| frame.stream_profile().kind() == Rs2StreamKind::Depth));
+    }
+}
+
+#[test]
+fn d400_depth_frame_data_can_be_viewed_as_u16() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        let frameset = pipeline.wait(None).unwrap();
+        let depth_frame = frameset.iter_of_type::<DepthFrame>().next().unwrap();
+
+        let depth = depth_frame.as_u16_slice();
+        assert_eq!(depth.len() * 2, depth_frame.get_data_size());
+        assert_eq!(depth_frame.as_u8_slice().len(), depth_frame.get_data_size());
+
+        let (col, row) = (depth_frame.width() / 2, depth_frame.height() / 2);
+        let index = row * depth_frame.stride() / 2 + col;
+        assert_eq!(
+            depth_frame.get(col, row).unwrap().to_gray_u16(),
+            Some(depth[index])
+        );
+    }
+}
+
+#[test]
+fn d400_depth_rows_match_pixels() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        let frameset = pipeline.wait(None).unwrap();
+        let depth_frame = frameset.iter_of_type::<DepthFrame>().next().unwrap();
+
+        assert_eq!(depth_frame.rows().count(), depth_frame.height());
+        assert!(depth_frame.row(depth_frame.height()).is_none());
+
+        let row = depth_frame.height() / 2;
+        for (col, pixel) in depth_frame.row(row).unwrap().enumerate() {
+            assert_eq!(
+                pixel.to_gray_u16(),
+                depth_frame.get(col, row).unwrap().to_gray_u16()
+            );
+        }
+        assert!(depth_frame
+            .rows()
+            .all(|pixels| pixels.count() == depth_frame.width()));
+    }
+}
+
+#[test]
+fn d400_can_stream_to_pipeline_callback() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let streaming = pipeline
+            .start_streaming_with_config(config, move |frameset: CompositeFrame| {
+                let depth_frames = frameset.frames_of_type::<DepthFrame>();
+                if let Some(frame) = depth_frames.first() {
+                    let _ = sender.send(frame.frame_number());
+                }
+            })
+            .unwrap();
+
+        for _ in 0..5 {
+            receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        }
+
+        let _pipeline = streaming.stop().map_err(|(_, e)| e).unwrap();
+    }
+}
+
+#[test]
+fn d400_software_sensor_accepts_mirrored_depth_frames() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let depth_sensor = device.first_depth_sensor().unwrap();
+        let profile = depth_sensor
+            .stream_profiles()
+            .into_iter()
+            .find(|p| p.kind() == Rs2StreamKind::Depth && p.format() == Rs2Format::Z16)
+            .unwrap();
+        let video = profile.try_as_video().unwrap();
+        let frame_len = video.width() * video.height() * 2;
+
+        let software_device = SoftwareDevice::new(&context).unwrap();
+        let mut sensor = software_device
+            .add_sensor(&CString::new("Depth").unwrap())
+            .unwrap();
+
+        assert!(matches!(
+            sensor.inject_frame(
+                &vec![0; frame_len],
+                0.0,
+                Rs2TimestampDomain::SystemTime,
+                0,
+                0.001,
+                &profile,
+            ),
+            Err(SoftwareDeviceError::StreamProfileNotAdded(_))
+        ));
+
+        sensor.add_stream_profile(&profile).unwrap();
+
+        assert!(matches!(
+            sensor.inject_frame(
+                &[0; 4],
+                0.0,
+                Rs2TimestampDomain::SystemTime,
+                0,
+                0.001,
+                &profile
+            ),
+            Err(SoftwareDeviceError::FrameDataSizeMismatch { .. })
+        ));
+        sensor
+            .inject_frame(
+                &vec![0; frame_len],
+                0.0,
+                Rs2TimestampDomain::SystemTime,
+                0,
+                0.001,
+                &profile,
+            )
+            .unwrap();
+    }
+}
+
+#[test]
+fn d400_sensors_can_be_filtered_by_extension() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let depth_sensors = device.depth_sensors().collect::<Vec<_>>();
+        assert!(!depth_sensors.is_empty());
+        assert!(depth_sensors
+            .iter()
+            .all(|s| s.is_extendable_to(Rs2Extension::DepthSensor)));
+
+        let expected = device
+            .sensors()
+            .iter()
+            .filter(|s| s.is_extendable_to(Rs2Extension::ColorSensor))
+            .count();
+        assert_eq!(device.color_sensors().count(), expected);
+    }
+}
+
+#[test]
+fn d400_depth_sensor_recommends_filters() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let depth_sensor = device.first_depth_sensor().unwrap();
+        let recommended = depth_sensor.recommended_processing_blocks();
+
+        assert!(recommended.contains(&Rs2Extension::DecimationFilter));
+        assert!(recommended
+            .iter()
+            .all(|ext| FILTER_EXTENSIONS.contains(ext)));
+    }
+}
+
+#[test]
+fn d400_config_resolves_against_specific_device() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let mut config = Config::new();
+        config
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let profile = config.resolve_with_device(&pipeline, device).unwrap();
+
+        assert_eq!(
+            profile.device().info(Rs2CameraInfo::SerialNumber),
+            device.info(Rs2CameraInfo::SerialNumber)
+        );
+
+        let mut other_device_config = Config::new();
+        other_device_config
+            .enable_device_from_serial(&CString::new("000000000000").unwrap())
+            .unwrap();
+        assert!(matches!(
+            other_device_config.resolve_with_device(&pipeline, device),
+            Err(ConfigurationError::ConfigCannotBeResolved)
+        ));
+    }
+}
+
+#[test]
+fn d400_config_enables_device_from_usb_port() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let port = device.info(Rs2CameraInfo::PhysicalPort).unwrap();
+        let mut config = Config::new();
+        config
+            .enable_device_from_usb_port(port)
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let profile = pipeline.resolve(&config).unwrap();
+        assert_eq!(
+            profile.device().info(Rs2CameraInfo::SerialNumber),
+            device.info(Rs2CameraInfo::SerialNumber)
+        );
+
+        assert!(matches!(
+            Config::new().enable_device_from_usb_port(&CString::new("no-such-port").unwrap()),
+            Err(ConfigurationError::CouldNotEnableDeviceFromPort(_, _))
+        ));
+    }
+}
+
+#[test]
+fn d400_resolved_profile_can_start_on_its_context() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let profile = pipeline.resolve(&config).unwrap();
+        assert!(profile.can_start(&context));
+    }
+}
+
+#[test]
+fn d400_supported_options_match_per_option_queries() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        for sensor in device.sensors() {
+            let supported = sensor.supported_options();
+            assert!(supported
+                .iter()
+                .all(|option| sensor.supports_option(*option)));
+
+            let ranges = sensor.all_option_ranges();
+            for (option, range) in &ranges {
+                assert!(supported.contains(option));
+                assert_eq!(sensor.get_option_range(*option).as_ref(), Some(range));
+            }
+        }
+    }
+}
+
+#[test]
+fn d400_depth_frame_copy_can_be_reinterpreted_as_u16() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        let frameset = pipeline.wait(None).unwrap();
+        let depth_frame = frameset.iter_of_type::<DepthFrame>().next().unwrap();
+
+        let bytes = depth_frame.copy_to_vec();
+        assert_eq!(bytes.len(), depth_frame.get_data_size());
+        assert_eq!(&*depth_frame.copy_to_boxed_slice(), &bytes[..]);
+
+        let depth: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|b| u16::from_ne_bytes([b[0], b[1]]))
+            .collect();
+        assert_eq!(depth, depth_frame.as_u16_slice());
+    }
+}
+
+#[test]
+fn d400_device_hub_returns_connected_device_within_timeout() {
+    let context = Context::new().unwrap().into_shared();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    if !context.query_devices(queryable_set).is_empty() {
+        let hub = Context::create_device_hub(&context).unwrap();
+        let device = hub.wait_for_device(Some(Duration::from_secs(5))).unwrap();
+
+        assert!(hub.is_connected(&device));
+    }
+}
+
+/// Verify that the configuration of a device in advanced mode can be saved and loaded back.
+///
+/// Advanced mode is not toggled, since that resets the device.
+#[test]
+fn d400_advanced_mode_json_round_trips() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let mut devices = context.query_devices(queryable_set);
+
+    if !devices.is_empty() {
+        let mut advanced = AdvancedModeDevice::from_device(devices.remove(0)).unwrap();
+
+        if advanced.is_enabled().unwrap() {
+            let json = advanced.serialize_json().unwrap();
+            assert!(json.trim_start().starts_with('{'));
+
+            advanced.load_json(&json).unwrap();
+            assert_eq!(advanced.serialize_json().unwrap(), json);
+        }
+    }
+}
+
+/// Verify that the firmware log of a device can be polled without a parser.
+#[test]
+fn d400_firmware_log_messages_are_formatted() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let mut devices = context.query_devices(queryable_set);
+
+    if !devices.is_empty() {
+        let device = devices.remove(0);
+        if !device.is_extendable_to(Rs2Extension::FirmwareLogger) {
+            return;
+        }
+
+        let mut logger = FirmwareLogger::try_from(device).unwrap();
+        let mut session = logger.start_collecting(Rs2LogSeverity::Debug).unwrap();
+
+        for message in std::iter::from_fn(|| session.get_message()).take(10) {
+            assert!(message.starts_with('['));
+        }
+    }
+}
@@ -0,0 +1,94 @@
+//! Property tests for the lens distortion models of `Rs2Intrinsics`
+
+use proptest::prelude::*;
+use realsense_rust::base::Rs2Intrinsics;
+use realsense_sys as sys;
+
+/// Intrinsics resembling a D400 color stream, with the given distortion model.
+fn intrinsics(model: sys::rs2_distortion, coeffs: [f32; 5]) -> Rs2Intrinsics {
+    Rs2Intrinsics(sys::rs2_intrinsics {
+        width: 1280,
+        height: 720,
+        ppx: 640.5,
+        ppy: 360.25,
+        fx: 915.0,
+        fy: 914.0,
+        model,
+        coeffs,
+    })
+}
+
+/// Brown-Conrady coefficients of the magnitude found in RealSense lenses.
+fn brown_conrady_coeffs() -> impl Strategy<Value = [f32; 5]> {
+    (
+        -0.1f32..0.1,
+        -0.05f32..0.05,
+        -0.002f32..0.002,
+        -0.002f32..0.002,
+        -0.01f32..0.01,
+    )
+        .prop_map(|(k1, k2, p1, p2, k3)| [k1, k2, p1, p2, k3])
+}
+
+/// Pixels within the image.
+fn pixel() -> impl Strategy<Value = [f32; 2]> {
+    (0.0f32..1280.0, 0.0f32..720.0).prop_map(|(u, v)| [u, v])
+}
+
+fn assert_round_trip(intrinsics: &Rs2Intrinsics, pixel: [f32; 2]) -> Result<(), TestCaseError> {
+    let round_trip = intrinsics.undistort(intrinsics.distort(pixel));
+    prop_assert!(
+        (round_trip[0] - pixel[0]).abs() < 0.05,
+        "{:?} != {:?}",
+        round_trip,
+        pixel
+    );
+    prop_assert!(
+        (round_trip[1] - pixel[1]).abs() < 0.05,
+        "{:?} != {:?}",
+        round_trip,
+        pixel
+    );
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn brown_conrady_round_trips(coeffs in brown_conrady_coeffs(), pixel in pixel()) {
+        let intrinsics = intrinsics(sys::rs2_distortion_RS2_DISTORTION_BROWN_CONRADY, coeffs);
+        assert_round_trip(&intrinsics, pixel)?;
+    }
+
+    #[test]
+    fn modified_brown_conrady_round_trips(coeffs in brown_conrady_coeffs(), pixel in pixel()) {
+        let intrinsics =
+            intrinsics(sys::rs2_distortion_RS2_DISTORTION_MODIFIED_BROWN_CONRADY, coeffs);
+        assert_round_trip(&intrinsics, pixel)?;
+    }
+
+    #[test]
+    fn inverse_brown_conrady_round_trips(coeffs in brown_conrady_coeffs(), pixel in pixel()) {
+        let intrinsics =
+            intrinsics(sys::rs2_distortion_RS2_DISTORTION_INVERSE_BROWN_CONRADY, coeffs);
+        assert_round_trip(&intrinsics, pixel)?;
+    }
+
+    #[test]
+    fn ftheta_round_trips(w in 0.5f32..1.2, pixel in pixel()) {
+        let intrinsics = intrinsics(sys::rs2_distortion_RS2_DISTORTION_FTHETA, [w, 0.0, 0.0, 0.0, 0.0]);
+        assert_round_trip(&intrinsics, pixel)?;
+    }
+
+    #[test]
+    fn kannala_brandt_round_trips(
+        k1 in -0.05f32..0.05,
+        k2 in -0.05f32..0.05,
+        k3 in -0.01f32..0.01,
+        k4 in -0.005f32..0.005,
+        pixel in pixel(),
+    ) {
+        let intrinsics =
+            intrinsics(sys::rs2_distortion_RS2_DISTORTION_KANNALA_BRANDT4, [k1, k2, k3, k4, 0.0]);
+        assert_round_trip(&intrinsics, pixel)?;
+    }
+}
@@ -0,0 +1,73 @@
+//! Tests for receiving librealsense2 log messages through a `LogMonitor`
+//!
+//! The librealsense2 logger is global to the process, so these run in their own test binary to
+//! keep the installed callbacks from leaking into other tests.
+
+// librealsense2 is not linked in docs-only builds.
+#![cfg(not(feature = "docs-only"))]
+
+use realsense_rust::{context::Context, kind::Rs2LogSeverity, log_monitor::LogMonitor};
+use realsense_sys as sys;
+use std::{
+    ffi::CString,
+    sync::{mpsc, Arc, Mutex},
+};
+
+/// Log `message` through librealsense2 with the given severity.
+fn log(severity: Rs2LogSeverity, message: &str) {
+    let message = CString::new(message).unwrap();
+    unsafe {
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        sys::rs2_log(
+            severity as sys::rs2_log_severity,
+            message.as_ptr(),
+            &mut err,
+        );
+        assert!(err.is_null());
+    }
+}
+
+#[test]
+fn callback_receives_messages_until_dropped() {
+    let (sender, receiver) = mpsc::channel();
+    let sender = Mutex::new(sender);
+    let monitor = Context::set_log_callback(Rs2LogSeverity::Warn, move |severity, message| {
+        if message.contains("log monitor test") {
+            let _ = sender.lock().unwrap().send((severity, message.to_owned()));
+        }
+    })
+    .unwrap();
+
+    log(Rs2LogSeverity::Error, "log monitor test");
+
+    let (severity, text) = receiver.recv().unwrap();
+    assert_eq!(severity, Rs2LogSeverity::Error);
+    assert!(text.contains("log monitor test"));
+
+    drop(monitor);
+    assert!(receiver.recv().is_err());
+}
+
+#[test]
+fn callback_can_drop_its_own_monitor() {
+    let (sender, receiver) = mpsc::channel();
+    let sender = Mutex::new(sender);
+    let slot: Arc<Mutex<Option<LogMonitor>>> = Arc::new(Mutex::new(None));
+
+    let monitor = {
+        let slot = Arc::clone(&slot);
+        Context::set_log_callback(Rs2LogSeverity::Warn, move |_, message| {
+            if message.contains("log monitor self drop") {
+                drop(slot.lock().unwrap().take());
+                let _ = sender.lock().unwrap().send(());
+            }
+        })
+        .unwrap()
+    };
+    *slot.lock().unwrap() = Some(monitor);
+
+    log(Rs2LogSeverity::Error, "log monitor self drop");
+
+    receiver.recv().unwrap();
+    assert!(slot.lock().unwrap().is_none());
+}
@@ -0,0 +1,165 @@
+//! Tests for streaming injected frames from a `SoftwareDevice`
+//!
+//! These do not need a physical device, only the librealsense2 runtime.
+
+// librealsense2 is not linked in docs-only builds.
+#![cfg(not(feature = "docs-only"))]
+
+use realsense_rust::{
+    base::Rs2Intrinsics,
+    context::Context,
+    frame::{DepthFrame, FrameEx, PixelKind},
+    frame_queue::FrameQueue,
+    kind::{Rs2Format, Rs2StreamKind, Rs2TimestampDomain},
+    software_device::{SoftwareDevice, SoftwareDeviceError, SoftwareVideoStream},
+};
+use realsense_sys as sys;
+use std::{
+    convert::TryFrom,
+    ffi::CString,
+    sync::{mpsc, Arc},
+    time::Duration,
+};
+
+/// Width of the injected depth frames.
+const WIDTH: usize = 4;
+/// Height of the injected depth frames.
+const HEIGHT: usize = 2;
+
+/// Describe a small Z16 depth stream.
+fn depth_stream() -> SoftwareVideoStream {
+    SoftwareVideoStream {
+        kind: Rs2StreamKind::Depth,
+        index: 0,
+        unique_id: 4242,
+        width: WIDTH,
+        height: HEIGHT,
+        framerate: 30,
+        format: Rs2Format::Z16,
+        bytes_per_pixel: 2,
+        intrinsics: Rs2Intrinsics(sys::rs2_intrinsics {
+            width: WIDTH as i32,
+            height: HEIGHT as i32,
+            ppx: 2.0,
+            ppy: 1.0,
+            fx: 10.0,
+            fy: 10.0,
+            model: sys::rs2_distortion_RS2_DISTORTION_NONE,
+            coeffs: [0.0; 5],
+        }),
+    }
+}
+
+#[test]
+fn streaming_sensor_receives_injected_frames() {
+    let context = Context::new().unwrap();
+    let software_device = SoftwareDevice::new(&context).unwrap();
+    let mut software_sensor = software_device
+        .add_sensor(&CString::new("Depth").unwrap())
+        .unwrap();
+    software_sensor.add_video_stream(&depth_stream()).unwrap();
+
+    let sensor = software_sensor.sensor().unwrap();
+    let profiles = sensor.stream_profiles();
+    let profile = profiles
+        .iter()
+        .find(|p| p.unique_id() == 4242)
+        .expect("Software sensor should list the added stream");
+
+    let (sender, receiver) = mpsc::channel();
+    let streaming = sensor
+        .open(profile)
+        .map_err(|(_, e)| e)
+        .unwrap()
+        .start_with_callback(move |frame: DepthFrame| {
+            let depth = match frame.get(1, 0) {
+                Some(PixelKind::Z16 { depth }) => *depth,
+                _ => 0,
+            };
+            let _ = sender.send((frame.frame_number(), depth));
+        })
+        .map_err(|(_, e)| e)
+        .unwrap();
+
+    assert!(matches!(
+        software_sensor.inject_frame(
+            &[0; 3],
+            0.0,
+            Rs2TimestampDomain::SystemTime,
+            1,
+            0.001,
+            profile,
+        ),
+        Err(SoftwareDeviceError::FrameDataSizeMismatch { .. })
+    ));
+
+    let depth: Vec<u8> = (0..(WIDTH * HEIGHT) as u16)
+        .flat_map(|d| (d * 100).to_ne_bytes())
+        .collect();
+    software_sensor
+        .inject_frame(
+            &depth,
+            0.0,
+            Rs2TimestampDomain::SystemTime,
+            7,
+            0.001,
+            profile,
+        )
+        .unwrap();
+
+    let (frame_number, depth) = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(frame_number, 7);
+    assert_eq!(depth, 100);
+
+    streaming.stop().map_err(|(_, e)| e).unwrap();
+}
+
+#[test]
+fn frame_queue_can_be_shared_between_threads() {
+    let context = Context::new().unwrap();
+    let software_device = SoftwareDevice::new(&context).unwrap();
+    let mut software_sensor = software_device
+        .add_sensor(&CString::new("Depth").unwrap())
+        .unwrap();
+    software_sensor.add_video_stream(&depth_stream()).unwrap();
+
+    let sensor = software_sensor.sensor().unwrap();
+    let profiles = sensor.stream_profiles();
+    let profile = &profiles[0];
+
+    // Frames are enqueued on the librealsense2 callback thread and dequeued on this one.
+    let queue = Arc::new(FrameQueue::with_capacity(4).unwrap());
+    let streaming = {
+        let queue = Arc::clone(&queue);
+        sensor
+            .open(profile)
+            .map_err(|(_, e)| e)
+            .unwrap()
+            .start_with_callback(move |frame: DepthFrame| queue.enqueue(frame))
+            .map_err(|(_, e)| e)
+            .unwrap()
+    };
+
+    let depth = vec![0; WIDTH * HEIGHT * 2];
+    for frame_number in 1..=3 {
+        software_sensor
+            .inject_frame(
+                &depth,
+                0.0,
+                Rs2TimestampDomain::SystemTime,
+                frame_number,
+                0.001,
+                profile,
+            )
+            .unwrap();
+
+        let frame = queue
+            .dequeue(Some(Duration::from_secs(5)))
+            .unwrap()
+            .expect("Injected frame should be passed through the queue");
+        let frame = DepthFrame::try_from(frame).unwrap();
+        assert_eq!(frame.frame_number(), frame_number);
+    }
+
+    streaming.stop().map_err(|(_, e)| e).unwrap();
+}